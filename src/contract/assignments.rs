@@ -22,7 +22,7 @@
 
 use core::cmp::Ordering;
 use core::fmt::Debug;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::hash::{Hash, Hasher};
 use std::{io, vec};
 
@@ -195,6 +195,32 @@ impl<State: ExposedState, Seal: ExposedSeal> Assign<State, Seal> {
             _ => None,
         }
     }
+
+    /// Merges revealed seal and state from other copy of the same assignment,
+    /// returning `None` if the assignments have different concealed seal or
+    /// state.
+    pub fn merge_reveal(self, other: Self) -> Option<Self> {
+        if self != other {
+            return None;
+        }
+        let seal = self.revealed_seal().or_else(|| other.revealed_seal());
+        let state = self
+            .as_revealed_state()
+            .or_else(|| other.as_revealed_state())
+            .cloned();
+        Some(match (seal, state) {
+            (Some(seal), Some(state)) => Assign::Revealed { seal, state },
+            (Some(seal), None) => Assign::ConfidentialState {
+                seal,
+                state: self.to_confidential_state(),
+            },
+            (None, Some(state)) => Assign::ConfidentialSeal {
+                seal: self.to_confidential_seal(),
+                state,
+            },
+            (None, None) => self,
+        })
+    }
 }
 
 impl<State: ExposedState, Seal: ExposedSeal> Conceal for Assign<State, Seal>
@@ -331,6 +357,43 @@ impl<Seal: ExposedSeal> Conceal for TypedAssigns<Seal> {
 }
 
 impl<Seal: ExposedSeal> TypedAssigns<Seal> {
+    /// Merges revealed seals and state from other copy of the same
+    /// assignments, returning `None` if the assignments have different state
+    /// types, numbers of assignments, or concealed data.
+    pub fn merge_reveal(self, other: Self) -> Option<Self> {
+        fn merge<State: ExposedState, Seal: ExposedSeal>(
+            ours: SmallVec<Assign<State, Seal>>,
+            theirs: SmallVec<Assign<State, Seal>>,
+        ) -> Option<SmallVec<Assign<State, Seal>>> {
+            if ours.len() != theirs.len() {
+                return None;
+            }
+            let merged = ours
+                .into_inner()
+                .into_iter()
+                .zip(theirs.into_inner())
+                .map(|(ours, theirs)| ours.merge_reveal(theirs))
+                .collect::<Option<Vec<_>>>()?;
+            Some(SmallVec::try_from(merged).expect("same size"))
+        }
+
+        Some(match (self, other) {
+            (TypedAssigns::Declarative(ours), TypedAssigns::Declarative(theirs)) => {
+                TypedAssigns::Declarative(merge(ours, theirs)?)
+            }
+            (TypedAssigns::Fungible(ours), TypedAssigns::Fungible(theirs)) => {
+                TypedAssigns::Fungible(merge(ours, theirs)?)
+            }
+            (TypedAssigns::Structured(ours), TypedAssigns::Structured(theirs)) => {
+                TypedAssigns::Structured(merge(ours, theirs)?)
+            }
+            (TypedAssigns::Attachment(ours), TypedAssigns::Attachment(theirs)) => {
+                TypedAssigns::Attachment(merge(ours, theirs)?)
+            }
+            _ => return None,
+        })
+    }
+
     pub fn is_empty(&self) -> bool {
         match self {
             TypedAssigns::Declarative(set) => set.is_empty(),
@@ -631,6 +694,25 @@ impl<Seal: ExposedSeal> CommitEncode for Assignments<Seal> {
     }
 }
 
+impl<Seal: ExposedSeal> Assignments<Seal> {
+    /// Merges revealed seals and state from other copy of the same
+    /// assignments, returning `None` if the assignments have different types
+    /// or concealed data.
+    pub fn merge_reveal(self, other: Self) -> Option<Self> {
+        let mut theirs = other.0.into_inner();
+        let merged = self
+            .0
+            .into_inner()
+            .into_iter()
+            .map(|(ty, ours)| Some((ty, ours.merge_reveal(theirs.remove(&ty)?)?)))
+            .collect::<Option<BTreeMap<_, _>>>()?;
+        if !theirs.is_empty() {
+            return None;
+        }
+        Some(Assignments(Confined::try_from(merged).expect("same size")))
+    }
+}
+
 impl Assignments<GenesisSeal> {
     pub fn transmutate_seals(&self) -> Assignments<GraphSeal> {
         Assignments(
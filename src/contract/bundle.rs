@@ -20,11 +20,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeSet;
+
 use amplify::confinement::{TinyOrdMap, TinyOrdSet};
 use amplify::{Bytes32, Wrapper};
 use commit_verify::{mpc, CommitStrategy, CommitmentId, Conceal};
 
-use super::{OpId, Transition};
+use super::{ContractId, OpId, Operation, Transition};
+use crate::validation::Failure;
 use crate::LIB_NAME_RGB;
 
 /// Unique state transition bundle identifier equivalent to the bundle
@@ -99,7 +102,37 @@ impl CommitmentId for TransitionBundle {
 }
 
 impl TransitionBundle {
+    /// Computes bundle id, committing to the ids of the bundle transitions
+    /// and the inputs they spend. Revealed transition data do not affect the
+    /// id.
     pub fn bundle_id(&self) -> BundleId { self.commitment_id() }
+
+    /// Verifies that the bundle has the `expected` id, failing with
+    /// [`Failure::BundleInvalid`] for the expected id otherwise.
+    #[allow(clippy::result_large_err)]
+    pub fn verify_bundle_id(&self, expected: BundleId) -> Result<(), Failure> {
+        if self.bundle_id() != expected {
+            return Err(Failure::BundleInvalid(expected));
+        }
+        Ok(())
+    }
+
+    /// Computes commitment which must be embedded into the witness
+    /// transaction as a deterministic bitcoin commitment (DBC) for the bundle
+    /// of contract `contract_id`.
+    ///
+    /// The commitment is the root of the multi-protocol commitment tree, and
+    /// thus depends on the rest of the protocols committed in the same
+    /// transaction; it is reconstructed from the bundle id and the
+    /// `mpc_proof` of the bundle inclusion into the tree. This is the same
+    /// procedure the validation uses for checking anchors.
+    pub fn expected_dbc_commitment(
+        &self,
+        contract_id: ContractId,
+        mpc_proof: &mpc::MerkleProof,
+    ) -> Result<mpc::Commitment, mpc::InvalidProof> {
+        mpc_proof.convolve(contract_id.into(), self.bundle_id().into())
+    }
 }
 
 impl TransitionBundle {
@@ -113,4 +146,69 @@ impl TransitionBundle {
         }
         true
     }
+
+    /// Returns ids under which the bundle commits to revealed transitions
+    /// not matching the data of those transitions, meaning that some of the
+    /// transition data (like metadata) were tampered with after the
+    /// commitment.
+    pub fn mismatching_ids(&self) -> BTreeSet<OpId> {
+        self.iter()
+            .filter_map(|(opid, item)| {
+                let transition = item.transition.as_ref()?;
+                (transition.id() != *opid).then_some(*opid)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::SmallBlob;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::validation::{CommitmentVerifier, DefaultVerifier};
+
+    #[test]
+    fn expected_dbc_commitment() {
+        let bundle = TransitionBundle::strict_dumb();
+        let anchor = crate::Anchor::<mpc::MerkleProof>::strict_dumb();
+        let contract_id = ContractId::from([0xAB; 32]);
+        assert_eq!(
+            bundle
+                .expected_dbc_commitment(contract_id, &anchor.mpc_proof)
+                .ok(),
+            DefaultVerifier.verify_mpc(&anchor, contract_id, bundle.bundle_id().into())
+        );
+    }
+
+    #[test]
+    fn bundle_id() {
+        let opid = OpId::from([0xAB; 32]);
+        let mut bundle = TransitionBundle::from(tiny_bmap! { opid => BundleItem {
+            inputs: tiny_bset! { 0 },
+            transition: None,
+        }});
+        let bundle_id = bundle.bundle_id();
+        assert_eq!(bundle.verify_bundle_id(bundle_id), Ok(()));
+
+        bundle.get_mut(&opid).unwrap().inputs = tiny_bset! { 1 };
+        assert_ne!(bundle.bundle_id(), bundle_id);
+        assert_eq!(bundle.verify_bundle_id(bundle_id), Err(Failure::BundleInvalid(bundle_id)));
+    }
+
+    #[test]
+    fn tampered_metadata() {
+        let mut transition = Transition::strict_dumb();
+        let opid = transition.id();
+        let mut bundle = TransitionBundle::from(tiny_bmap! { opid => BundleItem {
+            inputs: none!(),
+            transition: Some(transition.clone()),
+        }});
+        assert!(bundle.mismatching_ids().is_empty());
+
+        transition.metadata = SmallBlob::try_from(vec![0xDE, 0xAD]).unwrap();
+        bundle.get_mut(&opid).unwrap().transition = Some(transition);
+        assert_eq!(bundle.mismatching_ids(), bset! { opid });
+    }
 }
@@ -278,6 +278,51 @@ impl StrictDecode for PedersenCommitment {
     }
 }
 
+impl PedersenCommitment {
+    /// Adds up the commitments, producing a commitment to the sum of their
+    /// values blinded with the sum of their blinding factors.
+    ///
+    /// Returns `None` if there are no commitments or if they sum up to the
+    /// point at infinity.
+    pub fn sum(commitments: impl IntoIterator<Item = PedersenCommitment>) -> Option<Self> {
+        let mut iter = commitments.into_iter();
+        let first = iter.next()?;
+        iter.try_fold(first, PedersenCommitment::checked_add)
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        use secp256k1_zkp::PublicKey;
+
+        // Serialized commitment encodes the sign of the point y coordinate
+        // differently from a public key, so we recover the points up to their
+        // sign, and pick the candidate sum which balances the commitments.
+        let point = |commitment: Self| {
+            let mut data = commitment.0.serialize();
+            data[0] = 0x02;
+            PublicKey::from_slice(&data).ok()
+        };
+        let (a, b) = (point(self)?, point(other)?);
+        [a.combine(&b), a.combine(&b.negate(SECP256K1))]
+            .into_iter()
+            .filter_map(Result::ok)
+            .flat_map(|candidate| {
+                let x = candidate.serialize();
+                [0x08, 0x09].map(move |prefix| {
+                    let mut data = x;
+                    data[0] = prefix;
+                    data
+                })
+            })
+            .filter_map(|data| secp256k1_zkp::PedersenCommitment::from_slice(&data).ok())
+            .find(|sum| {
+                secp256k1_zkp::verify_commitments_sum_to_equal(SECP256K1, &[self.0, other.0], &[
+                    *sum,
+                ])
+            })
+            .map(PedersenCommitment)
+    }
+}
+
 impl CommitVerify<RevealedValue, UntaggedProtocol> for PedersenCommitment {
     fn commit(revealed: &RevealedValue) -> Self {
         use secp256k1_zkp::{Generator, Tag, Tweak};
@@ -435,4 +480,26 @@ mod test {
             .collect::<HashSet<_>>();
         assert_eq!(generators.len(), 1);
     }
+
+    #[test]
+    fn commitments_sum() {
+        let b1 = BlindingFactor::try_from([1u8; 32]).unwrap();
+        let b2 = BlindingFactor::try_from([2u8; 32]).unwrap();
+        let b12 = BlindingFactor::from(
+            secp256k1_zkp::SecretKey::from(b1)
+                .add_tweak(&secp256k1_zkp::Scalar::from(secp256k1_zkp::SecretKey::from(b2)))
+                .unwrap(),
+        );
+
+        let c1 = PedersenCommitment::commit(&RevealedValue::with(4u64, b1));
+        let c2 = PedersenCommitment::commit(&RevealedValue::with(6u64, b2));
+        let c12 = PedersenCommitment::commit(&RevealedValue::with(10u64, b12));
+        assert_eq!(PedersenCommitment::sum([c1, c2]), Some(c12));
+        assert_eq!(PedersenCommitment::sum([c2, c1]), Some(c12));
+        assert_eq!(PedersenCommitment::sum([c1]), Some(c1));
+        assert_eq!(PedersenCommitment::sum([]), None);
+
+        let c9 = PedersenCommitment::commit(&RevealedValue::with(9u64, b12));
+        assert_ne!(PedersenCommitment::sum([c1, c2]), Some(c9));
+    }
 }
@@ -21,7 +21,7 @@
 // limitations under the License.
 
 use std::cmp::Ordering;
-use std::collections::{btree_map, btree_set};
+use std::collections::{btree_map, btree_set, BTreeMap};
 use std::fmt::{self, Display, Formatter};
 use std::iter;
 use std::str::FromStr;
@@ -29,15 +29,15 @@ use std::str::FromStr;
 use amplify::confinement::{SmallBlob, TinyOrdMap, TinyOrdSet};
 use amplify::hex::{FromHex, ToHex};
 use amplify::{hex, ByteArray, Bytes32, Wrapper};
-use baid58::{Baid58ParseError, Chunking, FromBaid58, ToBaid58, CHUNKING_32CHECKSUM};
+use baid58::{Baid58ParseError, Chunking, FromBaid58, ToBaid58, CHUNKING_32, CHUNKING_32CHECKSUM};
 use bp::Chain;
 use commit_verify::{mpc, CommitmentId, Conceal};
 use strict_encoding::{StrictDeserialize, StrictEncode, StrictSerialize};
 
 use crate::schema::{self, ExtensionType, OpFullType, OpType, SchemaId, TransitionType};
 use crate::{
-    AssignmentType, Assignments, AssignmentsRef, Ffv, GenesisSeal, GlobalState, GraphSeal, Opout,
-    ReservedByte, TypedAssigns, LIB_NAME_RGB,
+    Assign, AssignFungible, AssignmentType, Assignments, AssignmentsRef, ExposedSeal, Ffv,
+    GenesisSeal, GlobalState, GraphSeal, Opout, ReservedByte, TypedAssigns, LIB_NAME_RGB,
 };
 
 #[derive(Wrapper, WrapperMut, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, From)]
@@ -156,6 +156,13 @@ impl OpId {
     }
 }
 
+impl ToBaid58<32> for OpId {
+    const HRI: &'static str = "op";
+    const CHUNKING: Option<Chunking> = CHUNKING_32;
+    fn to_baid58_payload(&self) -> [u8; 32] { self.to_byte_array() }
+    fn to_baid58_string(&self) -> String { self.to_baid58().to_string() }
+}
+
 /// Unique contract identifier equivalent to the contract genesis commitment
 #[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From)]
 #[wrapper(Deref, BorrowSlice, Hex, Index, RangeOps)]
@@ -264,6 +271,50 @@ pub trait Operation {
     /// While public state extension do have parent nodes, they do not contain
     /// indexed rights.
     fn inputs(&self) -> Inputs;
+
+    /// Lists outputs of the previous operations spent by this operation, in
+    /// the order of [`Operation::inputs`]. Always empty for genesis and
+    /// extensions.
+    fn input_opouts(&self) -> Vec<Opout> {
+        self.inputs().iter().map(|input| input.prev_out).collect()
+    }
+
+    /// Summarizes total fungible value spent and assigned by the operation,
+    /// per assignment type, as `(input, output)` pairs.
+    ///
+    /// Operations do not contain the state they spend, so the caller has to
+    /// provide it as `prev_state` (like the one extracted by the validator
+    /// from the consignment). Genesis and extensions should be provided with
+    /// an empty set. Only revealed values are counted; concealed ones are
+    /// skipped, so the summary is exact only when all of the state is known.
+    fn value_summary(
+        &self,
+        prev_state: &Assignments<GraphSeal>,
+    ) -> BTreeMap<AssignmentType, (u64, u64)> {
+        fn sum<Seal: ExposedSeal>(assigns: &[AssignFungible<Seal>]) -> u64 {
+            assigns
+                .iter()
+                .filter_map(Assign::as_revealed_state)
+                .fold(0u64, |sum, state| sum.saturating_add(state.value.as_u64()))
+        }
+
+        let mut summary = BTreeMap::<AssignmentType, (u64, u64)>::new();
+        for (ty, assigns) in prev_state.iter() {
+            if assigns.is_fungible() {
+                summary.entry(*ty).or_default().0 = sum(assigns.as_fungible());
+            }
+        }
+        let assignments = self.assignments();
+        for ty in assignments.types() {
+            let Some(assigns) = assignments.get(ty) else {
+                continue;
+            };
+            if assigns.is_fungible() {
+                summary.entry(ty).or_default().1 = sum(assigns.as_fungible());
+            }
+        }
+        summary
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -394,6 +445,17 @@ impl CommitmentId for Extension {
     type Id = OpId;
 }
 
+impl Genesis {
+    /// Returns the network on which the contract is issued. All witness
+    /// transactions of the contract must belong to this network.
+    #[inline]
+    pub fn network(&self) -> Chain { self.chain }
+
+    /// Returns id of the schema the contract is issued under.
+    #[inline]
+    pub fn declared_schema_id(&self) -> SchemaId { self.schema_id }
+}
+
 impl Transition {
     /// Returns reference to information about the owned rights in form of
     /// [`Inputs`] wrapper structure which this operation updates with
@@ -643,7 +705,75 @@ impl<'op> Operation for OpRef<'op> {
 
 #[cfg(test)]
 mod test {
+    use std::collections::BTreeSet;
+
+    use amplify::confinement::Confined;
+    use strict_encoding::StrictDumb;
+
     use super::*;
+    use crate::{BlindingFactor, RevealedValue};
+
+    fn fungible(values: &[u64]) -> TypedAssigns<GraphSeal> {
+        let blinding = BlindingFactor::try_from([1u8; 32]).unwrap();
+        let assigns = values
+            .iter()
+            .map(|value| {
+                AssignFungible::revealed(
+                    GraphSeal::strict_dumb(),
+                    RevealedValue::with(*value, blinding),
+                )
+            })
+            .collect::<Vec<_>>();
+        TypedAssigns::Fungible(Confined::try_from(assigns).unwrap())
+    }
+
+    #[test]
+    fn genesis_network() {
+        let genesis = Genesis {
+            chain: Chain::Testnet3,
+            ..Genesis::strict_dumb()
+        };
+        assert_eq!(genesis.network(), Chain::Testnet3);
+        assert_ne!(genesis.network(), Chain::Bitcoin);
+    }
+
+    #[test]
+    fn input_opouts() {
+        let opouts = [
+            Opout::new(OpId::from([1u8; 32]), 1, 0),
+            Opout::new(OpId::from([1u8; 32]), 2, 3),
+            Opout::new(OpId::from([2u8; 32]), 1, 1),
+        ];
+        let transition = Transition {
+            inputs: Inputs::from(
+                Confined::try_from(
+                    opouts
+                        .iter()
+                        .copied()
+                        .map(Input::with)
+                        .collect::<BTreeSet<_>>(),
+                )
+                .unwrap(),
+            ),
+            ..Transition::strict_dumb()
+        };
+        assert_eq!(transition.input_opouts(), opouts.to_vec());
+        assert!(Genesis::strict_dumb().input_opouts().is_empty());
+    }
+
+    #[test]
+    fn value_summary() {
+        let prev_state = Assignments::from(
+            Confined::try_from(bmap! { 1 => fungible(&[60, 40]), 2 => fungible(&[5]) }).unwrap(),
+        );
+        let transition = Transition {
+            assignments: Confined::try_from(bmap! { 1 => fungible(&[70, 20, 10]) })
+                .unwrap()
+                .into(),
+            ..Transition::strict_dumb()
+        };
+        assert_eq!(transition.value_summary(&prev_state), bmap! { 1 => (100, 100), 2 => (5, 0) });
+    }
 
     #[test]
     fn contract_id_display() {
@@ -59,6 +59,8 @@ pub trait ExposedState:
 
 /// Categories of the state
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB, tags = order)]
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
@@ -67,6 +69,7 @@ pub trait ExposedState:
 #[display(lowercase)]
 pub enum StateType {
     /// No state data
+    #[strict_type(dumb)]
     Void,
 
     /// Value-based state, i.e. which can be committed to with a Pedersen
@@ -42,6 +42,7 @@ extern crate commit_verify;
 #[cfg(feature = "serde")]
 #[macro_use]
 extern crate serde_crate as serde;
+extern crate alloc;
 extern crate core;
 
 pub mod contract;
@@ -50,6 +51,7 @@ pub mod validation;
 pub mod vm;
 #[cfg(feature = "stl")]
 pub mod stl;
+mod limits;
 
 pub mod prelude {
     pub use bp::dbc::{Anchor, AnchorId};
@@ -61,6 +63,7 @@ pub mod prelude {
     pub use super::{schema, vm};
 }
 
+pub use limits::{DecodeLimits, LimitedDecode};
 pub use prelude::*;
 
 pub const LIB_NAME_RGB: &str = "RGB";
@@ -112,6 +115,11 @@ mod _reserved {
 )]
 pub struct Ffv(u16);
 
+impl Ffv {
+    /// Constructs fast-forward version from its numeric code.
+    pub const fn with(code: u16) -> Self { Ffv(code) }
+}
+
 mod _ffv {
     use strict_encoding::{DecodeError, ReadTuple, StrictDecode, TypedRead};
 
@@ -0,0 +1,174 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2023 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2023 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2023 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Limits applied when decoding RGB data from untrusted sources.
+
+use std::io;
+
+use strict_encoding::{DecodeError, StrictDecode, StrictReader};
+
+use crate::{Genesis, Schema, SchemaLimits, SchemaRoot, TransitionBundle};
+
+/// Maximal sizes (in bytes) of the strict-encoded data accepted by
+/// [`LimitedDecode::decode_limited`], together with the maximal number of
+/// items in the collections of the decoded schemata.
+///
+/// Since strict-encoded collections are prefixed with their length, an
+/// attacker may provide data declaring huge collections; limiting the amount
+/// of data the decoder may consume makes the decoding fail early instead of
+/// attempting to read (and allocate) the declared number of items.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct DecodeLimits {
+    pub schema: usize,
+    pub genesis: usize,
+    pub bundle: usize,
+    pub schema_items: SchemaLimits,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits {
+            schema: 1 << 20,
+            genesis: 1 << 20,
+            bundle: 1 << 24,
+            schema_items: default!(),
+        }
+    }
+}
+
+/// Strict decoding of RGB data with the size limits defined by
+/// [`DecodeLimits`].
+pub trait LimitedDecode: StrictDecode {
+    /// Returns maximal size of the strict-encoded data for this type.
+    fn max_len(limits: &DecodeLimits) -> usize;
+
+    /// Checks lengths of the collections declared by the decoded data against
+    /// the limits. Types without specific collection limits accept any
+    /// lengths fitting [`LimitedDecode::max_len`].
+    fn check_lengths(&self, limits: &DecodeLimits) -> Result<(), DecodeError> {
+        let _ = limits;
+        Ok(())
+    }
+
+    /// Decodes data, failing if they exceed (or declare collections
+    /// exceeding) the limit defined for this type.
+    fn decode_limited(data: &[u8], limits: &DecodeLimits) -> Result<Self, DecodeError> {
+        let max_len = Self::max_len(limits);
+        if data.len() > max_len {
+            return Err(DecodeError::DataIntegrityError(format!(
+                "data size {} exceeds the limit of {max_len} bytes",
+                data.len()
+            )));
+        }
+        let mut reader = StrictReader::with(max_len, io::Cursor::new(data));
+        let decoded = Self::strict_decode(&mut reader)?;
+        decoded.check_lengths(limits)?;
+        Ok(decoded)
+    }
+}
+
+impl<Root: SchemaRoot> LimitedDecode for Schema<Root> {
+    fn max_len(limits: &DecodeLimits) -> usize { limits.schema }
+
+    fn check_lengths(&self, limits: &DecodeLimits) -> Result<(), DecodeError> {
+        let metrics = self.metrics();
+        if !metrics.within(&limits.schema_items) {
+            return Err(DecodeError::DataIntegrityError(format!(
+                "schema collections {metrics:?} exceed the limits {:?}",
+                limits.schema_items
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl LimitedDecode for Genesis {
+    fn max_len(limits: &DecodeLimits) -> usize { limits.genesis }
+}
+
+impl LimitedDecode for TransitionBundle {
+    fn max_len(limits: &DecodeLimits) -> usize { limits.bundle }
+}
+
+#[cfg(test)]
+mod test {
+    use strict_encoding::{StrictDumb, StrictEncode, StrictWriter};
+
+    use super::*;
+    use crate::{GlobalStateSchema, RootSchema, SubSchema};
+
+    fn encode(data: &impl StrictEncode) -> Vec<u8> {
+        let mut buf = vec![];
+        data.strict_encode(StrictWriter::with(u32::MAX as usize, &mut buf))
+            .unwrap();
+        buf
+    }
+
+    #[test]
+    fn oversized_collection() {
+        let limits = DecodeLimits {
+            bundle: 64,
+            ..default!()
+        };
+
+        let mut data = vec![];
+        let bundle = TransitionBundle::strict_dumb();
+        bundle
+            .strict_encode(StrictWriter::with(u32::MAX as usize, &mut data))
+            .unwrap();
+        assert_eq!(TransitionBundle::decode_limited(&data, &limits).unwrap(), bundle);
+
+        // Header declaring a bundle with 255 items, each of which is larger
+        // than the limit
+        let mut data = vec![0xFF];
+        data.extend([0u8; 63]);
+        assert!(TransitionBundle::decode_limited(&data, &limits).is_err());
+
+        data.push(0);
+        assert!(TransitionBundle::decode_limited(&data, &limits).is_err());
+    }
+
+    #[test]
+    fn schema_collections() {
+        let schema = RootSchema {
+            global_types: tiny_bmap! {
+                1 => GlobalStateSchema::once(default!()),
+                2 => GlobalStateSchema::once(default!()),
+                3 => GlobalStateSchema::once(default!()),
+            },
+            ..default!()
+        };
+        let data = encode(&schema);
+        let limits = DecodeLimits::default();
+        assert_eq!(RootSchema::decode_limited(&data, &limits).unwrap(), schema);
+
+        // Declared number of global types exceeds the limit, while the data
+        // still fit the size limit
+        let mut limits = DecodeLimits::default();
+        limits.schema_items.global_types = 2;
+        assert!(data.len() <= limits.schema);
+        assert!(RootSchema::decode_limited(&data, &limits).is_err());
+
+        let subschema = SubSchema::default();
+        assert_eq!(SubSchema::decode_limited(&encode(&subschema), &limits).unwrap(), subschema);
+    }
+}
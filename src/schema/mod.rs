@@ -33,8 +33,9 @@ pub use operations::{
     OpSchema, OpType, TransitionSchema, ValencySchema, ValencyType,
 };
 pub use schema::{
-    ExtensionType, GlobalStateType, RootSchema, Schema, SchemaId, SchemaRoot, SchemaTypeIndex,
-    SubSchema, TransitionType, BLANK_TRANSITION_ID,
+    ExtensionType, GlobalStateType, RootSchema, Schema, SchemaBuilder, SchemaDiff, SchemaError,
+    SchemaId, SchemaLimits, SchemaMetrics, SchemaRoot, SchemaTypeIndex, SubSchema, TransitionType,
+    TypesDiff, UnusedTypes, BLANK_TRANSITION_ID,
 };
-pub use script::{Script, VmType};
+pub use script::{Script, ScriptComplexity, VmType};
 pub use state::{FungibleType, GlobalStateSchema, MediaType, StateSchema};
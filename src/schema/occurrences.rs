@@ -153,6 +153,8 @@ impl StrictDecode for Occurrences {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Display)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(crate = "serde_crate"))]
 #[display("expected from {min} to {max} elements, while {found} were provided")]
 pub struct OccurrencesMismatch {
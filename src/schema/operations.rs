@@ -65,6 +65,8 @@ pub enum OpType {
 /// Aggregated type used to supply full contract operation type and
 /// transition/state extension type information
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB, tags = order)]
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
@@ -73,6 +75,7 @@ pub enum OpType {
 pub enum OpFullType {
     /// Genesis operation (no subtypes)
     #[display("genesis")]
+    #[strict_type(dumb)]
     Genesis,
 
     /// State transition contract operation, subtyped by transition type
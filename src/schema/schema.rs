@@ -20,22 +20,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{self, Display, Formatter};
+use std::iter;
 use std::str::FromStr;
 
+use aluvm::isa::{Bytecode, Instr};
 use amplify::confinement::{TinyOrdMap, TinyOrdSet};
 use amplify::{ByteArray, Bytes32};
 use baid58::{Baid58ParseError, Chunking, FromBaid58, ToBaid58, CHUNKING_32};
-use commit_verify::{CommitStrategy, CommitmentId};
-use strict_encoding::{StrictDecode, StrictDeserialize, StrictEncode, StrictSerialize, StrictType};
-use strict_types::TypeSystem;
+use commit_verify::{CommitStrategy, CommitmentId, Digest, Sha256};
+use strict_encoding::{
+    StrictDecode, StrictDeserialize, StrictEncode, StrictSerialize, StrictType, StrictWriter,
+};
+use strict_types::{SemId, TypeSystem};
 
 use super::{
-    AssignmentType, ExtensionSchema, GenesisSchema, Script, StateSchema, TransitionSchema,
-    ValencyType,
+    AssignmentType, ExtensionSchema, GenesisSchema, OpSchema, Script, StateSchema,
+    TransitionSchema, ValencyType,
 };
-use crate::{Ffv, GlobalStateSchema, Occurrences, LIB_NAME_RGB};
+use crate::vm::DisasmError;
+use crate::{Ffv, GlobalStateSchema, Occurrences, StateType, LIB_NAME_RGB};
 
 pub trait SchemaTypeIndex:
     Copy + Eq + Ord + Default + StrictType + StrictEncode + StrictDecode
@@ -93,6 +100,25 @@ impl FromStr for SchemaId {
 }
 impl SchemaId {
     pub fn to_mnemonic(&self) -> String { self.to_baid58().mnemonic() }
+
+    /// Checks whether the mnemonic (like `distant-thermos-arctic`) matches the
+    /// id. Words may be separated with dashes, underscores or spaces and are
+    /// case-insensitive.
+    ///
+    /// Mnemonic is just a checksum of the id, so the id can't be recovered
+    /// from it, and many ids share the same mnemonic. Thus, the method must
+    /// be used only to match ids against their displayed representations,
+    /// and not to identify schemata.
+    pub fn matches_mnemonic(&self, mnemonic: &str) -> bool {
+        let normalized = mnemonic
+            .trim()
+            .trim_start_matches('#')
+            .split(|c| c == '-' || c == '_' || c == ' ')
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>()
+            .join("-");
+        normalized == self.to_mnemonic()
+    }
 }
 
 pub trait SchemaRoot: Clone + Eq + StrictType + StrictEncode + StrictDecode + Default {}
@@ -126,6 +152,9 @@ pub struct Schema<Root: SchemaRoot> {
     pub script: Script,
 }
 
+/// Tag used in computing [`Schema::equivalence_key`].
+const SCHEMA_EQUIVALENCE_TAG: &[u8] = b"urn:lnpbp:rgb:schema:equivalence:v01";
+
 impl<Root: SchemaRoot> PartialEq for Schema<Root> {
     fn eq(&self, other: &Self) -> bool { self.schema_id() == other.schema_id() }
 }
@@ -154,21 +183,948 @@ impl<Root: SchemaRoot> Schema<Root> {
     #[inline]
     pub fn schema_id(&self) -> SchemaId { self.commitment_id() }
 
-    pub fn blank_transition(&self) -> TransitionSchema {
+    /// Returns type of the state for the owned state (assignment) type, or
+    /// `None` if the assignment type is not defined by the schema.
+    pub fn state_type(&self, ty: AssignmentType) -> Option<StateType> {
+        self.owned_types.get(&ty).map(StateSchema::state_type)
+    }
+
+    pub fn blank_transition(&self) -> TransitionSchema { self.blank_transition_excluding(&none!()) }
+
+    /// Constructs the same transition schema as [`Schema::blank_transition`],
+    /// except that it doesn't allow inputs and assignments of the `exclude`
+    /// types.
+    pub fn blank_transition_excluding(
+        &self,
+        exclude: &BTreeSet<AssignmentType>,
+    ) -> TransitionSchema {
         let mut schema = TransitionSchema::default();
-        for id in self.owned_types.keys() {
+        for id in self.owned_types.keys().filter(|id| !exclude.contains(id)) {
             schema.inputs.insert(*id, Occurrences::NoneOrMore).ok();
             schema.assignments.insert(*id, Occurrences::NoneOrMore).ok();
         }
         schema
     }
+
+    /// Returns schema of the state transition type.
+    ///
+    /// Blank state transitions ([`BLANK_TRANSITION_ID`]) are not defined by
+    /// schemata explicitly; for them the method returns owned schema
+    /// constructed with [`Schema::blank_transition`], unless the schema
+    /// (invalidly) redefines the blank transition type.
+    pub fn transition_schema(&self, ty: TransitionType) -> Option<Cow<TransitionSchema>> {
+        match self.transitions.get(&ty) {
+            Some(schema) => Some(Cow::Borrowed(schema)),
+            None if ty == BLANK_TRANSITION_ID => Some(Cow::Owned(self.blank_transition())),
+            None => None,
+        }
+    }
+
+    /// Returns schema of the state extension type.
+    pub fn extension_schema(&self, ty: ExtensionType) -> Option<&ExtensionSchema> {
+        self.extensions.get(&ty)
+    }
+
+    /// Computes key identifying class of schemata equivalent from the
+    /// consensus point of view.
+    ///
+    /// Unlike [`Schema::schema_id`], the key commits only to the state and
+    /// operation types, their occurrences and the script, but not to the
+    /// type system and feature version. Since state and operations refer
+    /// types by their semantic ids, which commit to the complete type
+    /// definitions, schemata differing only in types not used by the state
+    /// (like added documentation types) share the same key.
+    pub fn equivalence_key(&self) -> [u8; 32] {
+        let mut data = Vec::new();
+        let w = StrictWriter::with(u32::MAX as usize, &mut data);
+        let w = self.subset_of.strict_encode(w).expect("in-memory encoding");
+        let w = self
+            .global_types
+            .strict_encode(w)
+            .expect("in-memory encoding");
+        let w = self
+            .owned_types
+            .strict_encode(w)
+            .expect("in-memory encoding");
+        let w = self
+            .valency_types
+            .strict_encode(w)
+            .expect("in-memory encoding");
+        let w = self.genesis.strict_encode(w).expect("in-memory encoding");
+        let w = self
+            .extensions
+            .strict_encode(w)
+            .expect("in-memory encoding");
+        let w = self
+            .transitions
+            .strict_encode(w)
+            .expect("in-memory encoding");
+        self.script.strict_encode(w).expect("in-memory encoding");
+
+        let mut engine = Sha256::new_with_prefix(SCHEMA_EQUIVALENCE_TAG);
+        engine.update(&data);
+        engine.finalize().into()
+    }
+
+    /// Compares schema with the `other` one field by field, reporting types
+    /// added, removed or changed in the `other` schema. Unlike comparing
+    /// [`Schema::schema_id`]s, this explains why the ids of the schemata
+    /// differ.
+    pub fn diff(&self, other: &Self) -> SchemaDiff {
+        SchemaDiff {
+            ffv: self.ffv != other.ffv,
+            subset_of: self.subset_of != other.subset_of,
+            global_types: TypesDiff::with(&self.global_types, &other.global_types),
+            owned_types: TypesDiff::with(&self.owned_types, &other.owned_types),
+            valency_types: TypesDiff::with(
+                self.valency_types.iter().map(|ty| (ty, &())),
+                other.valency_types.iter().map(|ty| (ty, &())),
+            ),
+            genesis: self.genesis != other.genesis,
+            extensions: TypesDiff::with(&self.extensions, &other.extensions),
+            transitions: TypesDiff::with(&self.transitions, &other.transitions),
+            type_system: self.type_system != other.type_system,
+            script: self.script != other.script,
+        }
+    }
+
+    /// Detects global, owned and valency types declared by the schema which
+    /// are not referenced by genesis, state transitions or state extensions
+    /// (as their global state, inputs, assignments, redeemed valencies or
+    /// valencies).
+    pub fn unused_types(&self) -> UnusedTypes {
+        let ops = iter::once(&self.genesis as &dyn OpSchema)
+            .chain(self.transitions.values().map(|op| op as &dyn OpSchema))
+            .chain(self.extensions.values().map(|op| op as &dyn OpSchema))
+            .collect::<Vec<_>>();
+
+        let mut unused = UnusedTypes {
+            global_types: self.global_types.keys().copied().collect(),
+            owned_types: self.owned_types.keys().copied().collect(),
+            valency_types: self.valency_types.iter().copied().collect(),
+        };
+        for op in ops {
+            for ty in op.globals().keys() {
+                unused.global_types.remove(ty);
+            }
+            for ty in op
+                .assignments()
+                .keys()
+                .chain(op.inputs().into_iter().flat_map(|i| i.keys()))
+            {
+                unused.owned_types.remove(ty);
+            }
+            for ty in op
+                .valencies()
+                .iter()
+                .chain(op.redeems().into_iter().flatten())
+            {
+                unused.valency_types.remove(ty);
+            }
+        }
+        unused
+    }
+
+    /// Produces indented human-readable outline of the schema, listing its
+    /// state types and operations.
+    ///
+    /// Semantic type ids which are absent in the schema type system are
+    /// marked as unknown.
+    pub fn describe(&self) -> String {
+        let mut s = String::new();
+        self.write_description(&mut s)
+            .expect("writing to string never fails");
+        s
+    }
+
+    fn write_description(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        let sem_id = |id: SemId| {
+            if self.type_system.contains_key(&id) {
+                id.to_string()
+            } else {
+                format!("{id} (unknown type)")
+            }
+        };
+        let occurrences = |occ: &Occurrences| match (occ.min_value(), occ.max_value()) {
+            (min, max) if min == max => min.to_string(),
+            (min, u16::MAX) => format!("{min}.."),
+            (min, max) => format!("{min}..={max}"),
+        };
+        let write_op = |f: &mut dyn fmt::Write, title: String, op: &dyn OpSchema| {
+            writeln!(f, "  {title}:")?;
+            writeln!(f, "    metadata: {}", sem_id(op.metadata()))?;
+            for (ty, occ) in op.globals() {
+                writeln!(f, "    global {ty}: {}", occurrences(occ))?;
+            }
+            for (ty, occ) in op.inputs().into_iter().flatten() {
+                writeln!(f, "    input {ty}: {}", occurrences(occ))?;
+            }
+            for ty in op.redeems().into_iter().flatten() {
+                writeln!(f, "    redeems {ty}")?;
+            }
+            for (ty, occ) in op.assignments() {
+                writeln!(f, "    assignment {ty}: {}", occurrences(occ))?;
+            }
+            for ty in op.valencies() {
+                writeln!(f, "    valency {ty}")?;
+            }
+            Ok::<_, fmt::Error>(())
+        };
+
+        writeln!(f, "schema {}", self.schema_id())?;
+        writeln!(f, "  ffv: {}", self.ffv)?;
+        writeln!(f, "  global types:")?;
+        for (ty, schema) in self.global_types.iter() {
+            writeln!(f, "    {ty}: {}, up to {} items", sem_id(schema.sem_id), schema.max_items)?;
+        }
+        writeln!(f, "  owned types:")?;
+        for (ty, schema) in self.owned_types.iter() {
+            match schema {
+                StateSchema::Declarative => writeln!(f, "    {ty}: {}", schema.state_type())?,
+                StateSchema::Fungible(fungible) => {
+                    writeln!(f, "    {ty}: {} ({fungible})", schema.state_type())?
+                }
+                StateSchema::Structured(id) => {
+                    writeln!(f, "    {ty}: {} ({})", schema.state_type(), sem_id(*id))?
+                }
+                StateSchema::Attachment(media) => {
+                    writeln!(f, "    {ty}: {} ({media})", schema.state_type())?
+                }
+            }
+        }
+        writeln!(f, "  valency types:")?;
+        for ty in self.valency_types.iter() {
+            writeln!(f, "    {ty}")?;
+        }
+        write_op(f, s!("genesis"), &self.genesis)?;
+        for (ty, schema) in self.transitions.iter() {
+            write_op(f, format!("transition {ty}"), schema)?;
+        }
+        for (ty, schema) in self.extensions.iter() {
+            write_op(f, format!("extension {ty}"), schema)?;
+        }
+        Ok(())
+    }
+
+    /// Measures schema size, allowing to bound it with [`SchemaLimits`].
+    pub fn metrics(&self) -> SchemaMetrics {
+        let occurrences = iter::once(&self.genesis as &dyn OpSchema)
+            .chain(self.transitions.values().map(|op| op as &dyn OpSchema))
+            .chain(self.extensions.values().map(|op| op as &dyn OpSchema))
+            .map(|op| {
+                op.globals().len() + op.inputs().map_or(0, |i| i.len()) + op.assignments().len()
+            })
+            .sum();
+        let script_len = match &self.script {
+            Script::AluVM(script) => script.libs.values().map(|lib| lib.serialize().len()).sum(),
+        };
+        SchemaMetrics {
+            global_types: self.global_types.len(),
+            owned_types: self.owned_types.len(),
+            valency_types: self.valency_types.len(),
+            transitions: self.transitions.len(),
+            extensions: self.extensions.len(),
+            occurrences,
+            script_len,
+            type_system: self.type_system.len(),
+        }
+    }
+
+    /// Compares schemata ignoring their validation scripts, i.e. checks that
+    /// both schemata define the same state and operation layout and the same
+    /// type system.
+    pub fn structurally_eq(&self, other: &Self) -> bool {
+        SchemaDiff {
+            script: false,
+            ..self.diff(other)
+        }
+        .is_empty()
+    }
+
+    /// Returns opcodes of all instructions used by the schema script.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DisasmError::Truncated`] if some of the script libraries
+    /// can't be disassembled.
+    pub fn required_opcodes(&self) -> Result<BTreeSet<u8>, DisasmError> {
+        Ok(self
+            .script
+            .instructions()?
+            .iter()
+            .map(Instr::instr_byte)
+            .collect())
+    }
+
+    /// Returns opcodes used by the schema script which are not implemented
+    /// by this version of the virtual machine.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DisasmError::Truncated`] if some of the script libraries
+    /// can't be disassembled.
+    pub fn unimplemented_opcodes(&self) -> Result<BTreeSet<u8>, DisasmError> {
+        Ok(self
+            .script
+            .instructions()?
+            .iter()
+            .filter(|instr| matches!(instr, Instr::ExtensionCodes(isa) if !isa.is_implemented()))
+            .map(Instr::instr_byte)
+            .collect())
+    }
+}
+
+/// Differences between entries of a schema collection, identified by their
+/// type ids.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct TypesDiff<Ty: Ord> {
+    /// Types present only in the other schema.
+    pub added: BTreeSet<Ty>,
+    /// Types present only in the original schema.
+    pub removed: BTreeSet<Ty>,
+    /// Types present in both schemata with different definitions.
+    pub changed: BTreeSet<Ty>,
+}
+
+impl<Ty: Ord + Copy> TypesDiff<Ty> {
+    fn with<'a, V: PartialEq + 'a>(
+        old: impl IntoIterator<Item = (&'a Ty, &'a V)>,
+        new: impl IntoIterator<Item = (&'a Ty, &'a V)>,
+    ) -> Self
+    where
+        Ty: 'a,
+    {
+        let old = old.into_iter().collect::<BTreeMap<_, _>>();
+        let new = new.into_iter().collect::<BTreeMap<_, _>>();
+        TypesDiff {
+            added: new
+                .keys()
+                .filter(|ty| !old.contains_key(*ty))
+                .map(|ty| **ty)
+                .collect(),
+            removed: old
+                .keys()
+                .filter(|ty| !new.contains_key(*ty))
+                .map(|ty| **ty)
+                .collect(),
+            changed: old
+                .iter()
+                .filter(|(ty, val)| matches!(new.get(*ty), Some(other) if other != *val))
+                .map(|(ty, _)| **ty)
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Structural differences between two schemata, produced by
+/// [`Schema::diff`].
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SchemaDiff {
+    pub ffv: bool,
+    pub subset_of: bool,
+    pub global_types: TypesDiff<GlobalStateType>,
+    pub owned_types: TypesDiff<AssignmentType>,
+    pub valency_types: TypesDiff<ValencyType>,
+    pub genesis: bool,
+    pub extensions: TypesDiff<ExtensionType>,
+    pub transitions: TypesDiff<TransitionType>,
+    pub type_system: bool,
+    pub script: bool,
+}
+
+impl SchemaDiff {
+    /// Detects whether the schemata are structurally equal.
+    pub fn is_empty(&self) -> bool {
+        !self.ffv &&
+            !self.subset_of &&
+            self.global_types.is_empty() &&
+            self.owned_types.is_empty() &&
+            self.valency_types.is_empty() &&
+            !self.genesis &&
+            self.extensions.is_empty() &&
+            self.transitions.is_empty() &&
+            !self.type_system &&
+            !self.script
+    }
+}
+
+/// State and valency types declared by a schema but not used by any of its
+/// operations, produced by [`Schema::unused_types`].
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct UnusedTypes {
+    pub global_types: BTreeSet<GlobalStateType>,
+    pub owned_types: BTreeSet<AssignmentType>,
+    pub valency_types: BTreeSet<ValencyType>,
+}
+
+impl UnusedTypes {
+    pub fn is_empty(&self) -> bool {
+        self.global_types.is_empty() && self.owned_types.is_empty() && self.valency_types.is_empty()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Root: SchemaRoot + serde::Serialize + serde::de::DeserializeOwned> Schema<Root> {
+    /// Serializes the schema into JSON string.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> { serde_json::to_string(self) }
+
+    /// Deserializes the schema from JSON string produced by
+    /// [`Schema::to_json`].
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> { serde_json::from_str(json) }
+}
+
+/// Size of a schema, produced by [`Schema::metrics`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct SchemaMetrics {
+    pub global_types: usize,
+    pub owned_types: usize,
+    pub valency_types: usize,
+    pub transitions: usize,
+    pub extensions: usize,
+    /// Total number of occurrence constraints on global state, inputs and
+    /// assignments in all schema operations.
+    pub occurrences: usize,
+    /// Total size of the compiled script libraries, in bytes.
+    pub script_len: usize,
+    /// Number of the types in the schema type system.
+    pub type_system: usize,
+}
+
+impl SchemaMetrics {
+    /// Checks that none of the metrics exceed the `limits`.
+    pub fn within(&self, limits: &SchemaLimits) -> bool {
+        self.global_types <= limits.global_types &&
+            self.owned_types <= limits.owned_types &&
+            self.valency_types <= limits.valency_types &&
+            self.transitions <= limits.transitions &&
+            self.extensions <= limits.extensions &&
+            self.occurrences <= limits.occurrences &&
+            self.script_len <= limits.script_len &&
+            self.type_system <= limits.type_system
+    }
+}
+
+/// Maximal values of [`SchemaMetrics`] of the schemata accepted by a policy.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SchemaLimits {
+    pub global_types: usize,
+    pub owned_types: usize,
+    pub valency_types: usize,
+    pub transitions: usize,
+    pub extensions: usize,
+    pub occurrences: usize,
+    pub script_len: usize,
+    pub type_system: usize,
+}
+
+impl Default for SchemaLimits {
+    fn default() -> Self {
+        SchemaLimits {
+            global_types: u8::MAX as usize,
+            owned_types: u8::MAX as usize,
+            valency_types: u8::MAX as usize,
+            transitions: u8::MAX as usize,
+            extensions: u8::MAX as usize,
+            occurrences: 4096,
+            script_len: 1 << 16,
+            type_system: 1024,
+        }
+    }
+}
+
+/// Errors happening during schema construction with [`SchemaBuilder`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum SchemaError {
+    /// global state type {0} is defined more than once.
+    DuplicateGlobalType(GlobalStateType),
+
+    /// assignment type {0} is defined more than once.
+    DuplicateAssignmentType(AssignmentType),
+
+    /// valency type {0} is defined more than once.
+    DuplicateValencyType(ValencyType),
+
+    /// state extension type {0} is defined more than once.
+    DuplicateExtensionType(ExtensionType),
+
+    /// state transition type {0} is defined more than once.
+    DuplicateTransitionType(TransitionType),
+
+    /// schema defines more than 255 types of the same kind.
+    TooManyTypes,
+}
+
+/// Builder constructing [`Schema`] with checks for duplicated type ids and
+/// the number of the defined types.
+#[derive(Clone, Debug, Default)]
+pub struct SchemaBuilder<Root: SchemaRoot> {
+    subset_of: Option<Root>,
+    global_types: Vec<(GlobalStateType, GlobalStateSchema)>,
+    owned_types: Vec<(AssignmentType, StateSchema)>,
+    valency_types: Vec<ValencyType>,
+    genesis: GenesisSchema,
+    extensions: Vec<(ExtensionType, ExtensionSchema)>,
+    transitions: Vec<(TransitionType, TransitionSchema)>,
+    type_system: TypeSystem,
+    script: Script,
+}
+
+impl<Root: SchemaRoot> SchemaBuilder<Root> {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn subset_of(mut self, root: Root) -> Self {
+        self.subset_of = Some(root);
+        self
+    }
+
+    pub fn global(mut self, ty: GlobalStateType, schema: GlobalStateSchema) -> Self {
+        self.global_types.push((ty, schema));
+        self
+    }
+
+    pub fn owned(mut self, ty: AssignmentType, schema: StateSchema) -> Self {
+        self.owned_types.push((ty, schema));
+        self
+    }
+
+    pub fn valency(mut self, ty: ValencyType) -> Self {
+        self.valency_types.push(ty);
+        self
+    }
+
+    pub fn genesis(mut self, schema: GenesisSchema) -> Self {
+        self.genesis = schema;
+        self
+    }
+
+    pub fn extension(mut self, ty: ExtensionType, schema: ExtensionSchema) -> Self {
+        self.extensions.push((ty, schema));
+        self
+    }
+
+    pub fn transition(mut self, ty: TransitionType, schema: TransitionSchema) -> Self {
+        self.transitions.push((ty, schema));
+        self
+    }
+
+    pub fn type_system(mut self, type_system: TypeSystem) -> Self {
+        self.type_system = type_system;
+        self
+    }
+
+    pub fn script(mut self, script: Script) -> Self {
+        self.script = script;
+        self
+    }
+
+    /// Constructs the schema, failing if some of the type ids were provided
+    /// more than once or if there are too many types of the same kind.
+    pub fn build(self) -> Result<Schema<Root>, SchemaError> {
+        fn insert<K: Ord + Copy, V>(
+            map: &mut TinyOrdMap<K, V>,
+            items: Vec<(K, V)>,
+            duplicate: impl Fn(K) -> SchemaError,
+        ) -> Result<(), SchemaError> {
+            for (key, value) in items {
+                if map.contains_key(&key) {
+                    return Err(duplicate(key));
+                }
+                map.insert(key, value)
+                    .map_err(|_| SchemaError::TooManyTypes)?;
+            }
+            Ok(())
+        }
+
+        let mut schema = Schema {
+            subset_of: self.subset_of,
+            genesis: self.genesis,
+            type_system: self.type_system,
+            script: self.script,
+            ..default!()
+        };
+        insert(&mut schema.global_types, self.global_types, SchemaError::DuplicateGlobalType)?;
+        insert(&mut schema.owned_types, self.owned_types, SchemaError::DuplicateAssignmentType)?;
+        for ty in self.valency_types {
+            if schema.valency_types.contains(&ty) {
+                return Err(SchemaError::DuplicateValencyType(ty));
+            }
+            schema
+                .valency_types
+                .push(ty)
+                .map_err(|_| SchemaError::TooManyTypes)?;
+        }
+        insert(&mut schema.extensions, self.extensions, SchemaError::DuplicateExtensionType)?;
+        insert(&mut schema.transitions, self.transitions, SchemaError::DuplicateTransitionType)?;
+        Ok(schema)
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use aluvm::library::{Lib, LibSite};
+    use amplify::confinement::Confined;
     use strict_encoding::StrictDumb;
+    use strict_types::stl::std_stl;
+    use strict_types::{SemId, SystemBuilder};
 
     use super::*;
+    use crate::vm::{AluScript, EntryPoint, RgbIsa};
+    use crate::FungibleType;
+
+    #[test]
+    fn equivalence_key() {
+        let schema = SubSchema::default();
+        let documented = SubSchema {
+            type_system: SystemBuilder::new()
+                .import(std_stl())
+                .unwrap()
+                .finalize()
+                .unwrap()
+                .as_types()
+                .clone(),
+            ..default!()
+        };
+        assert_ne!(schema.schema_id(), documented.schema_id());
+        assert_eq!(schema.equivalence_key(), documented.equivalence_key());
+
+        let other = SubSchema {
+            valency_types: tiny_bset! { 1 },
+            ..default!()
+        };
+        assert_ne!(schema.equivalence_key(), other.equivalence_key());
+    }
+
+    #[test]
+    fn builder() {
+        let manual = SubSchema {
+            global_types: tiny_bmap! { 1 => GlobalStateSchema::once(SemId::strict_dumb()) },
+            owned_types: tiny_bmap! { 2 => StateSchema::Declarative },
+            valency_types: tiny_bset! { 3 },
+            transitions: tiny_bmap! { 4 => TransitionSchema::default() },
+            extensions: tiny_bmap! { 5 => ExtensionSchema::default() },
+            ..default!()
+        };
+        let built = SchemaBuilder::<RootSchema>::new()
+            .global(1, GlobalStateSchema::once(SemId::strict_dumb()))
+            .owned(2, StateSchema::Declarative)
+            .valency(3)
+            .transition(4, TransitionSchema::default())
+            .extension(5, ExtensionSchema::default())
+            .build()
+            .unwrap();
+        assert_eq!(built.schema_id(), manual.schema_id());
+
+        assert_eq!(
+            SchemaBuilder::<RootSchema>::new()
+                .owned(2, StateSchema::Declarative)
+                .owned(2, StateSchema::Declarative)
+                .build(),
+            Err(SchemaError::DuplicateAssignmentType(2))
+        );
+        let overflow = (0..=u8::MAX as u16)
+            .fold(SchemaBuilder::<RootSchema>::new(), |builder, ty| builder.valency(ty));
+        assert_eq!(overflow.build(), Err(SchemaError::TooManyTypes));
+    }
+
+    #[test]
+    fn state_type() {
+        let schema = SubSchema {
+            owned_types: tiny_bmap! {
+                1 => StateSchema::Fungible(FungibleType::Unsigned64Bit),
+                2 => StateSchema::Declarative,
+            },
+            ..default!()
+        };
+        assert_eq!(schema.state_type(1), Some(StateType::Fungible));
+        assert_eq!(schema.state_type(2), Some(StateType::Void));
+        assert_eq!(schema.state_type(3), None);
+    }
+
+    #[test]
+    fn blank_transition_excluding() {
+        let schema = SubSchema {
+            owned_types: tiny_bmap! {
+                1 => StateSchema::Fungible(FungibleType::Unsigned64Bit),
+                2 => StateSchema::Declarative,
+                3 => StateSchema::Declarative,
+            },
+            ..default!()
+        };
+        let blank = schema.blank_transition_excluding(&bset! { 2 });
+        let expected = tiny_bmap! { 1 => Occurrences::NoneOrMore, 3 => Occurrences::NoneOrMore };
+        assert_eq!(blank.inputs, expected);
+        assert_eq!(blank.assignments, expected);
+        assert_eq!(schema.blank_transition_excluding(&none!()), schema.blank_transition());
+    }
+
+    #[test]
+    fn describe() {
+        let sem_id = SemId::strict_dumb();
+        let schema = SubSchema {
+            global_types: tiny_bmap! { 1 => GlobalStateSchema::once(sem_id) },
+            owned_types: tiny_bmap! {
+                2 => StateSchema::Fungible(FungibleType::Unsigned64Bit),
+                3 => StateSchema::Declarative,
+            },
+            valency_types: tiny_bset! { 5 },
+            genesis: GenesisSchema {
+                globals: tiny_bmap! { 1 => Occurrences::Once },
+                assignments: tiny_bmap! { 2 => Occurrences::OnceOrMore },
+                ..default!()
+            },
+            transitions: tiny_bmap! { 4 => TransitionSchema {
+                inputs: tiny_bmap! { 2 => Occurrences::OnceOrMore },
+                assignments: tiny_bmap! { 2 => Occurrences::NoneOrUpTo(8) },
+                valencies: tiny_bset! { 5 },
+                ..default!()
+            }},
+            ..default!()
+        };
+
+        let description = schema.describe();
+        assert!(description.starts_with(&format!("schema {}\n", schema.schema_id())));
+        for line in [
+            "  global types:",
+            format!("    1: {sem_id} (unknown type), up to 1 items").as_str(),
+            "  owned types:",
+            "    2: fungible (64bit)",
+            "    3: void",
+            "  valency types:",
+            "  genesis:",
+            "    assignment 2: 1..",
+            "  transition 4:",
+            "    input 2: 1..",
+            "    assignment 2: 0..=8",
+            "    valency 5",
+        ] {
+            assert!(description.lines().any(|l| l == line), "missing {line:?} in {description}");
+        }
+        assert!(!description.contains("extension"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn json() {
+        let lib = Lib::assemble::<Instr<RgbIsa>>(&[Instr::Nop]).unwrap();
+        let root = RootSchema {
+            global_types: tiny_bmap! { 1 => GlobalStateSchema::once(SemId::strict_dumb()) },
+            ..default!()
+        };
+        let schema = SubSchema {
+            subset_of: Some(root),
+            global_types: tiny_bmap! { 1 => GlobalStateSchema::once(SemId::strict_dumb()) },
+            owned_types: tiny_bmap! {
+                2 => StateSchema::Fungible(FungibleType::Unsigned64Bit),
+                3 => StateSchema::Structured(SemId::strict_dumb()),
+            },
+            valency_types: tiny_bset! { 5 },
+            genesis: GenesisSchema {
+                globals: tiny_bmap! { 1 => Occurrences::Once },
+                assignments: tiny_bmap! { 2 => Occurrences::OnceOrMore },
+                ..default!()
+            },
+            extensions: tiny_bmap! { 6 => ExtensionSchema {
+                redeems: tiny_bset! { 5 },
+                assignments: tiny_bmap! { 3 => Occurrences::Range(1..=4) },
+                ..default!()
+            }},
+            transitions: tiny_bmap! { 4 => TransitionSchema {
+                inputs: tiny_bmap! { 2 => Occurrences::OnceOrMore },
+                assignments: tiny_bmap! { 2 => Occurrences::NoneOrUpTo(8) },
+                ..default!()
+            }},
+            type_system: SystemBuilder::new()
+                .import(std_stl())
+                .unwrap()
+                .finalize()
+                .unwrap()
+                .as_types()
+                .clone(),
+            script: Script::AluVM(AluScript {
+                libs: Confined::try_from(bmap! { lib.id() => lib.clone() }).unwrap(),
+                entry_points: Confined::try_from(bmap! {
+                    EntryPoint::ValidateGenesis => LibSite::with(0, lib.id()),
+                })
+                .unwrap(),
+            }),
+            ..default!()
+        };
+
+        let json = schema.to_json().unwrap();
+        let decoded = SubSchema::from_json(&json).unwrap();
+        assert_eq!(decoded.schema_id(), schema.schema_id());
+        assert_eq!(decoded.type_system, schema.type_system);
+        assert_eq!(decoded.script, schema.script);
+        assert!(SubSchema::from_json("{}").is_err());
+    }
+
+    #[test]
+    fn metrics() {
+        let lib = Lib::assemble::<Instr<RgbIsa>>(&[Instr::Nop]).unwrap();
+        let script_len = lib.serialize().len();
+        let schema = SubSchema {
+            global_types: tiny_bmap! { 1 => GlobalStateSchema::once(SemId::strict_dumb()) },
+            owned_types: tiny_bmap! {
+                2 => StateSchema::Declarative,
+                3 => StateSchema::Declarative,
+            },
+            genesis: GenesisSchema {
+                globals: tiny_bmap! { 1 => Occurrences::Once },
+                assignments: tiny_bmap! { 2 => Occurrences::Once },
+                ..default!()
+            },
+            transitions: tiny_bmap! { 4 => TransitionSchema {
+                inputs: tiny_bmap! { 2 => Occurrences::Once },
+                assignments: tiny_bmap! { 2 => Occurrences::Once, 3 => Occurrences::NoneOrOnce },
+                ..default!()
+            }},
+            script: Script::AluVM(AluScript {
+                libs: Confined::try_from(bmap! { lib.id() => lib }).unwrap(),
+                entry_points: none!(),
+            }),
+            ..default!()
+        };
+
+        let metrics = schema.metrics();
+        assert_eq!(metrics, SchemaMetrics {
+            global_types: 1,
+            owned_types: 2,
+            valency_types: 0,
+            transitions: 1,
+            extensions: 0,
+            occurrences: 5,
+            script_len,
+            type_system: 0,
+        });
+        assert!(metrics.within(&SchemaLimits::default()));
+        assert!(!metrics.within(&SchemaLimits {
+            owned_types: 1,
+            ..default!()
+        }));
+    }
+
+    #[test]
+    fn mnemonic() {
+        let id = SchemaId::from_byte_array(*b"EV4350-'4vwj'4;v-w94w'e'vFVVDhpq");
+        assert_eq!(id.to_mnemonic(), "distant-thermos-arctic");
+        assert!(id.matches_mnemonic(&id.to_mnemonic()));
+        assert!(id.matches_mnemonic(" #Distant_thermos arctic\n"));
+        assert!(!id.matches_mnemonic("comedy-vega-mary"));
+        assert!(!id.matches_mnemonic("distant-thermos"));
+    }
+
+    #[test]
+    fn diff() {
+        let schema = SubSchema {
+            transitions: tiny_bmap! { 1 => TransitionSchema::default() },
+            ..default!()
+        };
+        assert!(schema.diff(&schema.clone()).is_empty());
+
+        let mut other = schema.clone();
+        other
+            .transitions
+            .insert(2, TransitionSchema::default())
+            .unwrap();
+        let diff = schema.diff(&other);
+        assert_eq!(diff, SchemaDiff {
+            transitions: TypesDiff {
+                added: bset! { 2 },
+                ..default!()
+            },
+            ..default!()
+        });
+        assert_eq!(other.diff(&schema).transitions.removed, bset! { 2 });
+    }
+
+    #[test]
+    fn unused_types() {
+        let schema = SubSchema {
+            global_types: tiny_bmap! { 1 => GlobalStateSchema::once(SemId::strict_dumb()) },
+            owned_types: tiny_bmap! { 2 => StateSchema::Declarative },
+            valency_types: tiny_bset! { 3, 4 },
+            genesis: GenesisSchema {
+                globals: tiny_bmap! { 1 => Occurrences::Once },
+                assignments: tiny_bmap! { 2 => Occurrences::Once },
+                ..default!()
+            },
+            transitions: tiny_bmap! {
+                1 => TransitionSchema {
+                    valencies: tiny_bset! { 3 },
+                    ..default!()
+                },
+            },
+            ..default!()
+        };
+        assert_eq!(schema.unused_types(), UnusedTypes {
+            valency_types: bset! { 4 },
+            ..default!()
+        });
+        assert!(SubSchema::default().unused_types().is_empty());
+    }
+
+    #[test]
+    fn op_schemata() {
+        let transition = TransitionSchema {
+            valencies: tiny_bset! { 1 },
+            ..default!()
+        };
+        let schema = SubSchema {
+            owned_types: tiny_bmap! { 2 => StateSchema::Declarative },
+            transitions: tiny_bmap! { 1 => transition.clone() },
+            extensions: tiny_bmap! { 1 => ExtensionSchema::default() },
+            ..default!()
+        };
+
+        assert!(matches!(schema.transition_schema(1), Some(Cow::Borrowed(s)) if *s == transition));
+        assert!(matches!(
+            schema.transition_schema(BLANK_TRANSITION_ID),
+            Some(Cow::Owned(s)) if s == schema.blank_transition()
+        ));
+        assert_eq!(
+            schema
+                .transition_schema(BLANK_TRANSITION_ID)
+                .unwrap()
+                .inputs
+                .len(),
+            1
+        );
+        assert_eq!(schema.transition_schema(2), None);
+        assert_eq!(schema.extension_schema(1), Some(&ExtensionSchema::default()));
+        assert_eq!(schema.extension_schema(2), None);
+    }
+
+    #[test]
+    fn structurally_eq() {
+        let lib = Lib::assemble::<Instr<RgbIsa>>(&[Instr::Nop]).unwrap();
+        let schema = SubSchema {
+            owned_types: tiny_bmap! { 1 => StateSchema::Declarative },
+            ..default!()
+        };
+        let recompiled = SubSchema {
+            script: Script::AluVM(AluScript {
+                libs: Confined::try_from(bmap! { lib.id() => lib.clone() }).unwrap(),
+                entry_points: Confined::try_from(bmap! {
+                    EntryPoint::ValidateGenesis => LibSite::with(0, lib.id()),
+                })
+                .unwrap(),
+            }),
+            ..schema.clone()
+        };
+        assert_ne!(schema, recompiled);
+        assert!(schema.structurally_eq(&recompiled));
+
+        let other = SubSchema {
+            owned_types: tiny_bmap! { 2 => StateSchema::Declarative },
+            ..default!()
+        };
+        assert!(!schema.structurally_eq(&other));
+    }
 
     #[test]
     fn display() {
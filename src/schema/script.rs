@@ -25,7 +25,10 @@
 //! Components related to the scripting system used by schema or applied at the
 //! specific contract operation level
 
-use crate::vm::AluScript;
+use aluvm::isa::Instr;
+use aluvm::library::{Lib, LibId};
+
+use crate::vm::{AluScript, DisasmError, RgbIsa};
 use crate::LIB_NAME_RGB;
 
 /// Virtual machine types.
@@ -37,6 +40,30 @@ pub enum VmType {
     AluVM,
 }
 
+/// Upper bound on the [`Script::complexity`] of the schema script, which may be
+/// enforced during the validation with
+/// [`AluRuntime::with_complexity_cap`](crate::vm::AluRuntime::with_complexity_cap).
+///
+/// The cap is not a consensus rule and is not enforced by default. It limits
+/// the size of the script code only and doesn't bound the script execution
+/// time, since the script may contain loops.
+#[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display, From)]
+#[display(inner)]
+pub struct ScriptComplexity(u64);
+
+impl Default for ScriptComplexity {
+    fn default() -> Self { ScriptComplexity::DEFAULT }
+}
+
+impl ScriptComplexity {
+    /// Default complexity cap, allowing a script to perform a few dozens of
+    /// Pedersen commitment checks.
+    pub const DEFAULT: ScriptComplexity = ScriptComplexity(0x10000);
+
+    /// Detects whether the script complexity exceeds the cap.
+    pub fn is_exceeded_by(self, complexity: u64) -> bool { complexity > self.0 }
+}
+
 /// Virtual machine and machine-specific script data.
 #[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
 #[derive(StrictType, StrictEncode, StrictDecode)]
@@ -69,4 +96,123 @@ impl Script {
             Script::AluVM(_) => VmType::AluVM,
         }
     }
+
+    /// Returns id of the AluVM library compiled into the script, computed from
+    /// the library code rather than taken from the script library index.
+    ///
+    /// For scripts consisting of multiple libraries this is the library used
+    /// by the first of the entry points (or the first library, if there are
+    /// no entry points); for scripts without libraries this is the id of an
+    /// empty library.
+    pub fn lib_id(&self) -> LibId {
+        match self {
+            Script::AluVM(script) => script
+                .entry_points
+                .values()
+                .find_map(|site| script.libs.get(&site.lib))
+                .or_else(|| script.libs.values().next())
+                .map(Lib::id)
+                .unwrap_or_else(|| {
+                    Lib::assemble::<Instr<RgbIsa>>(&[])
+                        .expect("empty library is always valid")
+                        .id()
+                }),
+        }
+    }
+
+    /// Disassembles all libraries of the script.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DisasmError::Truncated`] if some of the libraries can't be
+    /// disassembled.
+    pub fn instructions(&self) -> Result<Vec<Instr<RgbIsa>>, DisasmError> {
+        match self {
+            Script::AluVM(script) => {
+                let mut instructions = vec![];
+                for lib in script.libs.values() {
+                    instructions.extend(
+                        lib.disassemble::<Instr<RgbIsa>>()
+                            .map_err(|_| DisasmError::Truncated)?,
+                    );
+                }
+                Ok(instructions)
+            }
+        }
+    }
+
+    /// Computes script complexity as a sum of weights of all its instructions,
+    /// to be checked against [`ScriptComplexity`] cap. See
+    /// [`AluScript::complexity`] for the details.
+    pub fn complexity(&self) -> Result<u64, DisasmError> {
+        match self {
+            Script::AluVM(script) => script.complexity(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use aluvm::isa::ControlFlowOp;
+    use aluvm::library::LibSite;
+    use aluvm::reg::{Reg16, RegS};
+    use amplify::confinement::{Confined, SmallBlob};
+    use amplify::num::u4;
+
+    use super::*;
+    use crate::vm::opcodes::{INSTR_PCCS, INSTR_PCVS};
+    use crate::vm::{ContractOp, EntryPoint};
+
+    #[test]
+    fn lib_id() {
+        let lib = Lib::assemble::<Instr<RgbIsa>>(&[Instr::Nop]).unwrap();
+        let script = |id: LibId| {
+            Script::AluVM(AluScript {
+                libs: Confined::try_from(bmap! { id => lib.clone() }).unwrap(),
+                entry_points: Confined::try_from(bmap! {
+                    EntryPoint::ValidateGenesis => LibSite::with(0, id),
+                })
+                .unwrap(),
+            })
+        };
+
+        assert_eq!(script(lib.id()).lib_id(), lib.id());
+        let other = LibId::from([0xAB; 32]);
+        assert_ne!(script(other).lib_id(), other);
+
+        let empty = Lib::assemble::<Instr<RgbIsa>>(&[]).unwrap();
+        assert_eq!(Script::default().lib_id(), empty.id());
+        assert_ne!(Script::default().lib_id(), lib.id());
+    }
+
+    #[test]
+    fn complexity() {
+        let code = [
+            Instr::ExtensionCodes(RgbIsa::Contract(ContractOp::CnS(1, Reg16::Reg0))),
+            Instr::ExtensionCodes(RgbIsa::Contract(ContractOp::LdG(2, 0, RegS::from(u4::with(0))))),
+            Instr::ExtensionCodes(RgbIsa::Contract(ContractOp::PcVs(3))),
+            Instr::ExtensionCodes(RgbIsa::Contract(ContractOp::Fail(INSTR_PCCS))),
+            Instr::Nop,
+            Instr::ControlFlow(ControlFlowOp::Succ),
+        ];
+        let lib = Lib::assemble::<Instr<RgbIsa>>(&code).unwrap();
+        let script = Script::AluVM(AluScript {
+            libs: Confined::try_from(bmap! { lib.id() => lib }).unwrap(),
+            entry_points: none!(),
+        });
+        // PCCS is not implemented, so it weighs as a failing instruction
+        assert_eq!(script.complexity(), Ok(2 + 4 + 1000 + 1 + 1 + 1));
+        assert_eq!(Script::default().complexity(), Ok(0));
+        assert!(ScriptComplexity::DEFAULT.is_exceeded_by(0x10001));
+        assert!(!ScriptComplexity::from(1009).is_exceeded_by(script.complexity().unwrap()));
+
+        let mut truncated = Lib::assemble::<Instr<RgbIsa>>(&code).unwrap();
+        truncated.code = SmallBlob::try_from(vec![INSTR_PCVS]).unwrap();
+        let script = Script::AluVM(AluScript {
+            libs: Confined::try_from(bmap! { truncated.id() => truncated }).unwrap(),
+            entry_points: none!(),
+        });
+        assert_eq!(script.complexity(), Err(DisasmError::Truncated));
+        assert_eq!(script.instructions(), Err(DisasmError::Truncated));
+    }
 }
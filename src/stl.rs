@@ -20,14 +20,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::Path;
+use std::{fs, io};
+
 pub use aluvm::stl::aluvm_stl;
+use amplify::confinement::{SmallVec, U32};
+use amplify::hex::ToHex;
 pub use bp::bc::stl::bp_tx_stl;
 pub use bp::stl::bp_core_stl;
+use bp::Chain;
+use once_cell::sync::Lazy;
+use strict_encoding::{StrictDumb, StrictEncode, StrictSerialize, StrictWriter};
 use strict_types::stl::{std_stl, strict_types_stl};
 use strict_types::typelib::LibBuilder;
-use strict_types::{CompileError, TypeLib};
+use strict_types::{CompileError, SemId, TypeLib};
 
-use crate::{Extension, Genesis, SubSchema, TransitionBundle, LIB_NAME_RGB};
+use crate::schema::Occurrences;
+use crate::validation::Status;
+use crate::{
+    AssignFungible, AssignRights, AssignmentType, BlindingFactor, BundleItem, Extension,
+    ExtensionSchema, ExtensionType, FungibleType, Genesis, GenesisSchema, GenesisSeal, GlobalState,
+    GlobalStateSchema, GlobalStateType, GraphSeal, Input, Inputs, Operation, Opout, Redeemed,
+    RevealedData, RevealedValue, RootSchema, StateSchema, SubSchema, Transition, TransitionBundle,
+    TransitionSchema, TransitionType, TypedAssigns, Valencies, ValencyType, VoidState,
+    LIB_NAME_RGB,
+};
 
 /// Strict types id for the library providing data types for RGB consensus.
 pub const LIB_ID_RGB: &str =
@@ -41,23 +58,264 @@ fn _rgb_core_stl() -> Result<TypeLib, CompileError> {
         bp_core_stl().to_dependency(),
         aluvm_stl().to_dependency()
     })
+    .transpile::<RootSchema>()
     .transpile::<SubSchema>()
     .transpile::<Genesis>()
     .transpile::<TransitionBundle>()
     .transpile::<Extension>()
+    .transpile::<Status>()
     .compile()
 }
 
+static RGB_CORE_STL: Lazy<TypeLib> =
+    Lazy::new(|| _rgb_core_stl().expect("invalid strict type RGB library"));
+
 /// Generates strict type library providing data types for RGB consensus.
-pub fn rgb_core_stl() -> TypeLib { _rgb_core_stl().expect("invalid strict type RGB library") }
+///
+/// The library is compiled once; subsequent calls return its copy.
+pub fn rgb_core_stl() -> TypeLib { rgb_core_stl_ref().clone() }
+
+/// Returns reference to the strict type library providing data types for
+/// RGB consensus, compiling it on the first use.
+pub fn rgb_core_stl_ref() -> &'static TypeLib { &RGB_CORE_STL }
+
+/// Writes strict type library from [`rgb_core_stl`] into `dir` both in
+/// binary strict-encoded form (`RGB.stl` file) and as a textual type
+/// definitions (`RGB.sty` file).
+pub fn rgb_core_stl_dump(dir: &Path) -> io::Result<()> {
+    let lib = rgb_core_stl_ref();
+    lib.strict_serialize_to_file::<U32>(dir.join(format!("{LIB_NAME_RGB}.stl")))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+    fs::write(dir.join(format!("{LIB_NAME_RGB}.sty")), lib.to_string())
+}
+
+/// Constructs example instances of the RGB library data types used as test
+/// vectors: a schema with global, owned and valency types, its genesis, a
+/// bundle with a transition spending genesis and an extension redeeming
+/// genesis valency.
+fn test_vector_samples() -> (SubSchema, Genesis, TransitionBundle, Extension) {
+    const TICKER: GlobalStateType = 1;
+    const ASSET: AssignmentType = 2;
+    const RIGHTS: AssignmentType = 3;
+    const TRANSFER: TransitionType = 4;
+    const INFLATION: ValencyType = 5;
+    const ISSUE: ExtensionType = 6;
+
+    let blinding = BlindingFactor::try_from([1u8; 32]).expect("valid blinding factor");
+    let fungible = |value: u64| RevealedValue::with(value, blinding);
+    let small = |data: &[u8]| SmallVec::try_from(data.to_vec()).expect("small data");
+
+    let schema = SubSchema {
+        global_types: tiny_bmap! { TICKER => GlobalStateSchema::once(SemId::strict_dumb()) },
+        owned_types: tiny_bmap! {
+            ASSET => StateSchema::Fungible(FungibleType::Unsigned64Bit),
+            RIGHTS => StateSchema::Declarative,
+        },
+        valency_types: tiny_bset! { INFLATION },
+        genesis: GenesisSchema {
+            globals: tiny_bmap! { TICKER => Occurrences::Once },
+            assignments: tiny_bmap! {
+                ASSET => Occurrences::OnceOrMore,
+                RIGHTS => Occurrences::NoneOrOnce,
+            },
+            valencies: tiny_bset! { INFLATION },
+            ..default!()
+        },
+        extensions: tiny_bmap! { ISSUE => ExtensionSchema {
+            redeems: tiny_bset! { INFLATION },
+            assignments: tiny_bmap! { ASSET => Occurrences::OnceOrMore },
+            ..default!()
+        }},
+        transitions: tiny_bmap! { TRANSFER => TransitionSchema {
+            inputs: tiny_bmap! { ASSET => Occurrences::OnceOrMore },
+            assignments: tiny_bmap! { ASSET => Occurrences::OnceOrMore },
+            ..default!()
+        }},
+        ..default!()
+    };
+
+    let mut globals = GlobalState::default();
+    globals
+        .add_state(TICKER, RevealedData::from(small(b"RGB")))
+        .expect("single global state");
+    let genesis = Genesis {
+        schema_id: schema.schema_id(),
+        chain: Chain::Testnet3,
+        metadata: small(b"genesis"),
+        globals,
+        assignments: tiny_bmap! {
+            ASSET => TypedAssigns::Fungible(confined_vec![
+                AssignFungible::revealed(GenesisSeal::strict_dumb(), fungible(1000))
+            ]),
+            RIGHTS => TypedAssigns::Declarative(confined_vec![
+                AssignRights::revealed(GenesisSeal::strict_dumb(), VoidState::default())
+            ]),
+        }
+        .into(),
+        valencies: Valencies::from(tiny_bset! { INFLATION }),
+        ..Genesis::strict_dumb()
+    };
+
+    let transition = Transition {
+        contract_id: genesis.contract_id(),
+        transition_type: TRANSFER,
+        inputs: Inputs::from(tiny_bset! { Input::with(Opout::new(genesis.id(), ASSET, 0)) }),
+        assignments: tiny_bmap! {
+            ASSET => TypedAssigns::Fungible(confined_vec![
+                AssignFungible::revealed(GraphSeal::strict_dumb(), fungible(600)),
+                AssignFungible::revealed(GraphSeal::strict_dumb(), fungible(400))
+            ]),
+        }
+        .into(),
+        ..Transition::strict_dumb()
+    };
+    let bundle = TransitionBundle::from(tiny_bmap! { transition.id() => BundleItem {
+        inputs: tiny_bset! { 0 },
+        transition: Some(transition),
+    }});
+
+    let extension = Extension {
+        contract_id: genesis.contract_id(),
+        extension_type: ISSUE,
+        metadata: small(b"extension"),
+        assignments: tiny_bmap! {
+            ASSET => TypedAssigns::Fungible(confined_vec![
+                AssignFungible::revealed(GenesisSeal::strict_dumb(), fungible(500))
+            ]),
+        }
+        .into(),
+        redeemed: Redeemed::from(tiny_bmap! { INFLATION => genesis.id() }),
+        ..Extension::strict_dumb()
+    };
+
+    (schema, genesis, bundle, extension)
+}
+
+/// Generates test vectors pinning binary format of the RGB library data
+/// types: example instances of [`SubSchema`], [`Genesis`],
+/// [`TransitionBundle`] and [`Extension`] with non-default field values in
+/// strict encoding, each paired with the type name.
+pub fn rgb_core_test_vectors() -> [(&'static str, Vec<u8>); 4] {
+    fn encode(value: &impl StrictEncode) -> Vec<u8> {
+        let mut data = vec![];
+        value
+            .strict_encode(StrictWriter::with(u32::MAX as usize, &mut data))
+            .expect("in-memory encoding");
+        data
+    }
+
+    let (schema, genesis, bundle, extension) = test_vector_samples();
+    [
+        ("SubSchema", encode(&schema)),
+        ("Genesis", encode(&genesis)),
+        ("TransitionBundle", encode(&bundle)),
+        ("Extension", encode(&extension)),
+    ]
+}
+
+/// Writes test vectors from [`rgb_core_test_vectors`] into `dir`, one
+/// hex-encoded file named after the type per vector, to be used by other
+/// language implementations of strict types decoding.
+pub fn write_test_vectors(dir: impl AsRef<Path>) -> io::Result<()> {
+    for (name, data) in rgb_core_test_vectors() {
+        fs::write(dir.as_ref().join(format!("{name}.hex")), data.to_hex())?;
+    }
+    Ok(())
+}
 
 #[cfg(test)]
 mod test {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use strict_encoding::{StrictDecode, StrictDeserialize, StrictReader};
+
     use super::*;
 
+    /// Creates temporary directory unique for each call, such that tests
+    /// running concurrently (also from other processes) don't clash.
+    fn temp_dir(name: &str) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "rgb-core-{name}-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
     #[test]
     fn lib_id() {
         let lib = rgb_core_stl();
         assert_eq!(lib.id().to_string(), LIB_ID_RGB);
     }
+
+    #[test]
+    fn root_schema() {
+        let lib = rgb_core_stl();
+        // Root schema is named without its `()` root type parameter
+        assert!(lib.types.contains_key(&tn!("Schema")));
+        assert!(lib.types.contains_key(&tn!("SchemaSchema")));
+    }
+
+    #[test]
+    fn status() {
+        let lib = rgb_core_stl();
+        assert!(lib.types.contains_key(&tn!("Status")));
+        assert!(lib.types.contains_key(&tn!("Failure")));
+        assert!(lib.types.contains_key(&tn!("Warning")));
+        assert!(lib.types.contains_key(&tn!("Info")));
+    }
+
+    #[test]
+    fn lib_cached() {
+        assert_eq!(rgb_core_stl().id(), rgb_core_stl().id());
+        assert_eq!(rgb_core_stl_ref().id(), rgb_core_stl().id());
+        assert!(std::ptr::eq(rgb_core_stl_ref(), rgb_core_stl_ref()));
+    }
+
+    #[test]
+    fn dump() {
+        let dir = temp_dir("stl-dump");
+        rgb_core_stl_dump(&dir).unwrap();
+
+        let lib =
+            TypeLib::strict_deserialize_from_file::<U32>(dir.join(format!("{LIB_NAME_RGB}.stl")))
+                .unwrap();
+        assert_eq!(lib.id().to_string(), LIB_ID_RGB);
+        assert!(dir.join(format!("{LIB_NAME_RGB}.sty")).exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_vectors() {
+        fn decode<T: StrictDecode>(data: &[u8]) -> T {
+            T::strict_decode(&mut StrictReader::with(data.len(), io::Cursor::new(data))).unwrap()
+        }
+
+        let (schema, genesis, bundle, extension) = test_vector_samples();
+        assert_ne!(schema, SubSchema::default());
+        assert_ne!(genesis, Genesis::strict_dumb());
+        assert_ne!(bundle, TransitionBundle::strict_dumb());
+        assert_ne!(extension, Extension::strict_dumb());
+
+        let [v1, v2, v3, v4] = rgb_core_test_vectors();
+        assert_eq!(decode::<SubSchema>(&v1.1), schema);
+        assert_eq!(decode::<Genesis>(&v2.1), genesis);
+        assert_eq!(decode::<TransitionBundle>(&v3.1), bundle);
+        assert_eq!(decode::<Extension>(&v4.1), extension);
+    }
+
+    #[test]
+    fn write_vectors() {
+        let dir = temp_dir("test-vectors");
+        write_test_vectors(&dir).unwrap();
+
+        for (name, data) in rgb_core_test_vectors() {
+            let hex = fs::read_to_string(dir.join(format!("{name}.hex"))).unwrap();
+            assert_eq!(hex, data.to_hex());
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
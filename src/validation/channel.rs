@@ -0,0 +1,224 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2023 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2023 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2023 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation helpers for RGB state kept in Lightning channels.
+
+use amplify::Wrapper;
+use bp::seals::txout::TxPtr;
+
+use super::{CommitmentVerifier, Failure, ResolveTx, Status};
+use crate::{Assign, Opout, TransitionBundle};
+
+/// Validates channel state update, represented by a `new_bundle`, against
+/// the previous channel state from `prev_bundle`.
+///
+/// Checks that the previous bundle assigns the state to the channel funding
+/// output `funding`, that the new bundle spends this state and that the
+/// fungible value of the funding state type is conserved (sum of the spent
+/// inputs is equal to the sum of the new assignments of the same type), using
+/// the provided commitment `verifier`.
+///
+/// The function doesn't validate the bundles against the contract schema; it
+/// must be done as a part of the normal consignment validation.
+pub fn validate_channel_update<R: ResolveTx>(
+    prev_bundle: &TransitionBundle,
+    new_bundle: &TransitionBundle,
+    funding: Opout,
+    resolver: &R,
+    verifier: &impl CommitmentVerifier,
+) -> Status {
+    let mut status = Status::new();
+
+    let Some(prev_assigns) = prev_bundle
+        .get(&funding.op)
+        .and_then(|item| item.transition.as_ref())
+        .and_then(|transition| transition.assignments.get(&funding.ty))
+    else {
+        status.add_failure(Failure::ChannelFundingAbsent(funding));
+        return status;
+    };
+    match prev_assigns.revealed_seal_at(funding.no) {
+        Err(_) => {
+            status.add_failure(Failure::ChannelFundingAbsent(funding));
+            return status;
+        }
+        Ok(Some(seal)) => {
+            if let TxPtr::Txid(txid) = seal.txid {
+                if resolver.resolve_tx(txid).is_err() {
+                    status.unresolved_txids.push(txid);
+                }
+            }
+        }
+        Ok(None) => {
+            // Seal is concealed, so we can't check the funding transaction
+        }
+    }
+
+    let new_transitions = new_bundle
+        .values()
+        .filter_map(|item| item.transition.as_ref())
+        .collect::<Vec<_>>();
+
+    if !new_transitions
+        .iter()
+        .flat_map(|transition| transition.inputs.iter())
+        .any(|input| input.prev_out == funding)
+    {
+        status.add_failure(Failure::ChannelFundingNotSpent(funding));
+    }
+
+    if !prev_assigns.is_fungible() {
+        return status;
+    }
+
+    // [VALIDATION]: Checking value conservation for the funding state type
+    let mut inputs = vec![];
+    for input in new_transitions
+        .iter()
+        .flat_map(|transition| transition.inputs.iter())
+        .filter(|input| input.prev_out.ty == funding.ty)
+    {
+        let prev_out = input.prev_out;
+        let Some(assign) = prev_bundle
+            .get(&prev_out.op)
+            .and_then(|item| item.transition.as_ref())
+            .and_then(|transition| transition.assignments.get(&prev_out.ty))
+            .and_then(|assigns| assigns.as_fungible().get(prev_out.no as usize))
+        else {
+            status.add_failure(Failure::ChannelFundingAbsent(prev_out));
+            continue;
+        };
+        inputs.push(assign.to_confidential_state().commitment.into_inner());
+    }
+    let outputs = new_transitions
+        .iter()
+        .filter_map(|transition| transition.assignments.get(&funding.ty))
+        .flat_map(|assigns| assigns.as_fungible())
+        .map(Assign::to_confidential_state)
+        .map(|state| state.commitment.into_inner())
+        .collect::<Vec<_>>();
+
+    if !verifier.verify_commitments_sum(&inputs, &outputs) {
+        status.add_failure(Failure::ChannelValueMismatch(funding));
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::Confined;
+    use bp::{Tx, Txid};
+    use secp256k1_zkp::PedersenCommitment;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::validation::{DefaultVerifier, TxResolverError};
+    use crate::{
+        AssignFungible, BlindingFactor, BundleItem, GraphSeal, Input, Operation, RevealedValue,
+        Transition, TypedAssigns,
+    };
+
+    struct NoResolver;
+    impl ResolveTx for NoResolver {
+        fn resolve_tx(&self, txid: Txid) -> Result<Tx, TxResolverError> {
+            Err(TxResolverError::Unknown(txid))
+        }
+    }
+
+    fn transition(inputs: &[Opout], value: u64) -> Transition {
+        let blinding = BlindingFactor::try_from([1u8; 32]).unwrap();
+        let assign = AssignFungible::revealed(
+            GraphSeal::strict_dumb(),
+            RevealedValue::with(value, blinding),
+        );
+        let assignments =
+            bmap! { 0 => TypedAssigns::Fungible(Confined::try_from(vec![assign]).unwrap()) };
+        Transition {
+            inputs: Confined::try_from_iter(inputs.iter().copied().map(Input::with))
+                .unwrap()
+                .into(),
+            assignments: Confined::try_from(assignments).unwrap().into(),
+            ..Transition::strict_dumb()
+        }
+    }
+
+    fn bundle(transition: Transition) -> TransitionBundle {
+        let item = BundleItem {
+            inputs: tiny_bset! { 0 },
+            transition: Some(transition.clone()),
+        };
+        TransitionBundle::from(tiny_bmap! { transition.id() => item })
+    }
+
+    /// Verifier rejecting all commitment sums.
+    struct UnbalancedVerifier;
+    impl CommitmentVerifier for UnbalancedVerifier {
+        fn verify_commitments_sum(
+            &self,
+            _: &[PedersenCommitment],
+            _: &[PedersenCommitment],
+        ) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn channel_update() {
+        let prev = transition(&[], 100);
+        let funding = Opout::new(prev.id(), 0, 0);
+        let prev_bundle = bundle(prev);
+        let update = |new_bundle: &TransitionBundle| {
+            validate_channel_update(
+                &prev_bundle,
+                new_bundle,
+                funding,
+                &NoResolver,
+                &DefaultVerifier,
+            )
+        };
+
+        let conserving = bundle(transition(&[funding], 100));
+        let status = update(&conserving);
+        assert!(status.failures.is_empty());
+
+        let inflating = bundle(transition(&[funding], 150));
+        let status = update(&inflating);
+        assert_eq!(status.failures, vec![Failure::ChannelValueMismatch(funding)]);
+
+        let unrelated = bundle(transition(&[], 100));
+        let status = update(&unrelated);
+        assert!(status
+            .failures
+            .contains(&Failure::ChannelFundingNotSpent(funding)));
+
+        // Value conservation is checked by the provided verifier
+        let status = validate_channel_update(
+            &prev_bundle,
+            &conserving,
+            funding,
+            &NoResolver,
+            &UnbalancedVerifier,
+        );
+        assert_eq!(status.failures, vec![Failure::ChannelValueMismatch(funding)]);
+    }
+}
@@ -0,0 +1,421 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2023 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2023 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2023 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lightweight validation of the contract operations against the schema
+//! rules, skipping all cryptographic and scripting checks.
+
+use std::collections::BTreeSet;
+
+use super::{
+    CommitmentVerifier, ConsignmentApi, Failure, OpInfo, Status, UnknownTypePolicy, VirtualMachine,
+};
+use crate::{
+    ConcealedValue, Genesis, OpRef, Operation, Opout, RangeProofError, Schema, SchemaRoot,
+};
+
+/// Verifier trusting all bulletproofs without checking them. Anchor
+/// commitments are never checked by the schema-layer validation, so they
+/// keep default implementations.
+struct TrustingVerifier;
+
+impl CommitmentVerifier for TrustingVerifier {
+    fn verify_range_proof(&self, _: &ConcealedValue) -> Result<bool, RangeProofError> { Ok(true) }
+}
+
+/// Virtual machine accepting all state evolutions without running scripts.
+struct NoScripts;
+
+impl VirtualMachine for NoScripts {
+    fn validate(&self, _: OpInfo) -> Result<(), Failure> { Ok(()) }
+}
+
+/// Checks that the `genesis` declares the `schema` it is validated against.
+///
+/// This is the cheapest acceptance check for the contract, preceding any
+/// other validation.
+pub fn verify_genesis_schema<Root: SchemaRoot>(
+    genesis: &Genesis,
+    schema: &Schema<Root>,
+) -> Result<(), Failure> {
+    let expected = genesis.declared_schema_id();
+    let actual = schema.schema_id();
+    if expected != actual {
+        return Err(Failure::SchemaMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+/// Validates conformance of all consignment operations to the `schema`
+/// rules: known operation and state types, state type and value matches and
+/// occurrences of the state, inputs and valencies.
+///
+/// Unlike [`super::Validator`], the function doesn't verify seals, anchors,
+/// witness transactions, bulletproofs and doesn't run the schema scripts, so
+/// the returned status doesn't tell whether the consignment is valid. It is
+/// intended for schema developers checking sample operations.
+///
+/// Operations checked are genesis, all revealed transitions from the
+/// anchored bundles and extensions spent by these transitions.
+pub fn validate_schema_conformance<C: ConsignmentApi, Root: SchemaRoot>(
+    consignment: &C,
+    schema: &Schema<Root>,
+) -> Status {
+    let genesis = consignment.genesis();
+    if let Err(failure) = verify_genesis_schema(genesis, schema) {
+        return Status::with_failure(failure);
+    }
+
+    let mut ops = vec![OpRef::Genesis(genesis)];
+    let mut extensions = vec![];
+    for anchored_bundle in consignment.anchored_bundles() {
+        for transition in anchored_bundle
+            .bundle
+            .values()
+            .filter_map(|item| item.transition.as_ref())
+        {
+            ops.push(OpRef::Transition(transition));
+            extensions.extend(
+                transition
+                    .inputs
+                    .iter()
+                    .filter_map(|input| consignment.extension(input.prev_out.op)),
+            );
+        }
+    }
+    ops.extend(extensions.into_iter().map(OpRef::Extension));
+
+    let mut status = Status::new();
+    let mut checked = BTreeSet::new();
+    for op in ops {
+        if checked.insert(op.id()) {
+            status += schema.validate(
+                consignment,
+                op,
+                &NoScripts,
+                &TrustingVerifier,
+                UnknownTypePolicy::Fail,
+            );
+        }
+    }
+    status
+}
+
+/// Verifies issuance rights of the state extensions: each valency redeemed by
+/// an extension must be defined by the schema of the extension and must be
+/// granted by its parent operation, which must be allowed by the schema to
+/// grant it.
+///
+/// Checks all extensions present in the consignment. Redemption of a valency
+/// not granted by the parent operation, either because the operation doesn't
+/// declare it or because the schema doesn't allow the operation to grant it,
+/// produces [`Failure::NoPrevValency`].
+pub fn verify_issuance_rights<C: ConsignmentApi, Root: SchemaRoot>(
+    consignment: &C,
+    schema: &Schema<Root>,
+) -> Status {
+    let mut status = Status::new();
+    for opid in consignment.op_ids_except(&none!()) {
+        let Some(extension) = consignment.extension(opid) else {
+            continue;
+        };
+        let Some(extension_schema) = schema.extensions.get(&extension.extension_type) else {
+            status.add_failure(Failure::SchemaUnknownExtensionType(opid, extension.extension_type));
+            continue;
+        };
+        for (valency, prev_id) in &extension.redeemed {
+            if !extension_schema.redeems.contains(valency) {
+                status.add_failure(Failure::SchemaUnknownValencyType(opid, *valency));
+            }
+            let Some(prev_op) = consignment.operation(*prev_id) else {
+                status.add_failure(Failure::ValencyNoParent {
+                    opid,
+                    prev_id: *prev_id,
+                    valency: *valency,
+                });
+                continue;
+            };
+            let granted = match (prev_op.transition_type(), prev_op.extension_type()) {
+                (None, None) => Some(&schema.genesis.valencies),
+                (Some(ty), _) => schema.transitions.get(&ty).map(|s| &s.valencies),
+                (None, Some(ty)) => schema.extensions.get(&ty).map(|s| &s.valencies),
+            };
+            if !prev_op.valencies().contains(valency) ||
+                !matches!(granted, Some(granted) if granted.contains(valency))
+            {
+                status.add_failure(Failure::NoPrevValency {
+                    opid,
+                    prev_id: *prev_id,
+                    valency: *valency,
+                });
+            }
+        }
+    }
+    status
+}
+
+/// Checks structural consistency of the consignment, reporting only the
+/// consignment consistency failures: invalid bundles and transition inputs
+/// referencing absent operations, state types or outputs.
+///
+/// Doesn't access anchors, witness transactions or the schema, so it is
+/// cheap and allows rejecting broken consignments before making any network
+/// requests. The full validation reports the same failures (and also
+/// [`Failure::TransitionAbsent`] for the missing parent operations, which are
+/// reported here only as [`Failure::OperationAbsent`]).
+pub fn precheck_consistency<C: ConsignmentApi>(consignment: &C) -> Status {
+    let mut status = Status::new();
+    for anchored_bundle in consignment.anchored_bundles() {
+        let bundle = &anchored_bundle.bundle;
+        if !bundle.validate() {
+            status.add_failure(Failure::BundleInvalid(bundle.bundle_id()));
+        }
+        for transition in bundle.values().filter_map(|item| item.transition.as_ref()) {
+            let opid = transition.id();
+            for input in &transition.inputs {
+                let Opout { op, ty, no } = input.prev_out;
+                let Some(prev_op) = consignment.operation(op) else {
+                    status.add_failure(Failure::OperationAbsent(op));
+                    continue;
+                };
+                let Some(variant) = prev_op.assignments_by_type(ty) else {
+                    status.add_failure(Failure::NoPrevState {
+                        opid,
+                        prev_id: op,
+                        state_type: ty,
+                    });
+                    continue;
+                };
+                if variant.revealed_seal_at(no).is_err() {
+                    status.add_failure(Failure::NoPrevOut(opid, input.prev_out));
+                }
+            }
+        }
+    }
+    for (bundle_id, _) in consignment.terminals() {
+        if consignment
+            .known_transitions_by_bundle_id(bundle_id)
+            .is_none()
+        {
+            status.add_failure(Failure::BundleInvalid(bundle_id));
+        }
+    }
+    status
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::Confined;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::schema::Occurrences;
+    use crate::validation::consignment::test::Mock;
+    use crate::validation::AnchoredBundle;
+    use crate::{
+        BundleItem, Extension, ExtensionSchema, GlobalStateSchema, Input, Inputs, OpId, Redeemed,
+        SchemaId, SubSchema, Transition, TransitionBundle, Valencies,
+    };
+
+    #[test]
+    fn genesis_schema() {
+        let schema = SubSchema::default();
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        assert_eq!(genesis.declared_schema_id(), schema.schema_id());
+        assert_eq!(verify_genesis_schema(&genesis, &schema), Ok(()));
+
+        let genesis = Genesis {
+            schema_id: SchemaId::from([0xCD; 32]),
+            ..genesis
+        };
+        assert_eq!(
+            verify_genesis_schema(&genesis, &schema),
+            Err(Failure::SchemaMismatch {
+                expected: SchemaId::from([0xCD; 32]),
+                actual: schema.schema_id(),
+            })
+        );
+    }
+
+    #[test]
+    fn ungranted_valency() {
+        const INFLATION: u16 = 1;
+        let schema = SubSchema {
+            valency_types: tiny_bset! { INFLATION },
+            extensions: tiny_bmap! {
+                0 => ExtensionSchema {
+                    redeems: tiny_bset! { INFLATION },
+                    ..default!()
+                },
+            },
+            ..default!()
+        };
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        let extension = Extension {
+            redeemed: Redeemed::from(
+                Confined::try_from(bmap! { INFLATION => genesis.id() }).unwrap(),
+            ),
+            ..Extension::strict_dumb()
+        };
+        let transition = Transition {
+            inputs: Inputs::from(
+                Confined::try_from(bset! { Input::with(Opout::new(extension.id(), 0, 0)) })
+                    .unwrap(),
+            ),
+            ..Transition::strict_dumb()
+        };
+        let item = BundleItem {
+            inputs: none!(),
+            transition: Some(transition.clone()),
+        };
+        let consignment = Mock {
+            schema: schema.clone(),
+            genesis: genesis.clone(),
+            bundles: vec![AnchoredBundle {
+                anchor: strict_dumb!(),
+                bundle: TransitionBundle::from(tiny_bmap! { transition.id() => item }),
+            }],
+            extensions: vec![extension.clone()],
+        };
+
+        let status = verify_issuance_rights(&consignment, &schema);
+        assert_eq!(status.failures, vec![Failure::NoPrevValency {
+            opid: extension.id(),
+            prev_id: genesis.id(),
+            valency: INFLATION,
+        }]);
+
+        // Extensions are checked even if they are not spent by any transition,
+        // and the valency must be granted by the schema as well
+        let genesis = Genesis {
+            valencies: Valencies::from(tiny_bset! { INFLATION }),
+            ..genesis
+        };
+        let extension = Extension {
+            redeemed: Redeemed::from(
+                Confined::try_from(bmap! { INFLATION => genesis.id() }).unwrap(),
+            ),
+            ..Extension::strict_dumb()
+        };
+        let consignment = Mock {
+            schema: schema.clone(),
+            genesis: genesis.clone(),
+            bundles: vec![],
+            extensions: vec![extension.clone()],
+        };
+        let status = verify_issuance_rights(&consignment, &schema);
+        assert_eq!(status.failures, vec![Failure::NoPrevValency {
+            opid: extension.id(),
+            prev_id: genesis.id(),
+            valency: INFLATION,
+        }]);
+
+        let mut schema = schema;
+        schema.genesis.valencies = tiny_bset! { INFLATION };
+        assert!(verify_issuance_rights(&consignment, &schema)
+            .failures
+            .is_empty());
+    }
+
+    #[test]
+    fn occurrences_without_witness() {
+        let mut schema = SubSchema {
+            global_types: tiny_bmap! { 0 => GlobalStateSchema::once(default!()) },
+            ..default!()
+        };
+        schema.genesis.globals = tiny_bmap! { 0 => Occurrences::Once };
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        let transition = Transition::strict_dumb();
+        let item = BundleItem {
+            inputs: none!(),
+            transition: Some(transition.clone()),
+        };
+        let anchored_bundle = AnchoredBundle {
+            anchor: strict_dumb!(),
+            bundle: TransitionBundle::from(tiny_bmap! { transition.id() => item }),
+        };
+        let consignment = Mock {
+            schema: schema.clone(),
+            genesis: genesis.clone(),
+            bundles: vec![anchored_bundle],
+            extensions: vec![],
+        };
+
+        let status = validate_schema_conformance(&consignment, &schema);
+        assert!(status.failures.iter().any(|f| matches!(
+            f,
+            Failure::SchemaGlobalStateOccurrences(opid, 0, _) if *opid == genesis.id()
+        )));
+        assert!(!status.failures.iter().any(|f| matches!(
+            f,
+            Failure::SealNoWitnessTx(_) | Failure::NotAnchored(_) | Failure::NotInAnchor(..)
+        )));
+    }
+
+    #[test]
+    fn consistency_precheck() {
+        let genesis = Genesis::strict_dumb();
+        let missing = OpId::from([0x01; 32]);
+        let transition = Transition {
+            inputs: Inputs::from(
+                Confined::try_from(bset! {
+                    Input::with(Opout::new(missing, 0, 0)),
+                    Input::with(Opout::new(genesis.id(), 1, 0)),
+                })
+                .unwrap(),
+            ),
+            ..Transition::strict_dumb()
+        };
+        let item = BundleItem {
+            inputs: none!(),
+            transition: Some(transition.clone()),
+        };
+        let consignment = Mock {
+            schema: SubSchema::default(),
+            genesis: genesis.clone(),
+            bundles: vec![AnchoredBundle {
+                anchor: strict_dumb!(),
+                bundle: TransitionBundle::from(tiny_bmap! { transition.id() => item }),
+            }],
+            extensions: vec![],
+        };
+
+        let status = precheck_consistency(&consignment);
+        assert_eq!(status.failures.len(), 2);
+        assert!(status.failures.contains(&Failure::OperationAbsent(missing)));
+        assert!(status.failures.contains(&Failure::NoPrevState {
+            opid: transition.id(),
+            prev_id: genesis.id(),
+            state_type: 1,
+        }));
+        assert!(status.warnings.is_empty());
+        assert!(status.unresolved_txids.is_empty());
+    }
+}
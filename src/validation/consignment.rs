@@ -24,13 +24,17 @@
 //! state transitions, extensions, genesis, outputs, assignments &
 //! single-use-seal data.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
-use commit_verify::mpc;
+use baid58::ToBaid58;
+use bp::Txid;
+use commit_verify::{mpc, Digest, Sha256};
+use strict_encoding::{StrictEncode, StrictWriter};
 
+use super::{Failure, Status};
 use crate::{
-    Anchor, BundleId, Extension, Genesis, OpId, OpRef, SecretSeal, SubSchema, Transition,
-    TransitionBundle, LIB_NAME_RGB,
+    Anchor, BundleId, ContractId, Extension, Genesis, OpId, OpRef, OpType, Operation, SchemaId,
+    SecretSeal, SubSchema, Transition, TransitionBundle, LIB_NAME_RGB,
 };
 
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -46,6 +50,283 @@ pub struct AnchoredBundle {
     pub bundle: TransitionBundle,
 }
 
+impl AnchoredBundle {
+    /// Merges revealed transitions from other copy of the same anchored bundle.
+    ///
+    /// Both bundles must have the same [`BundleId`]; since the id commits to
+    /// the concealed bundle data, the bundles may differ only in which of the
+    /// transitions are revealed.
+    ///
+    /// # Errors
+    ///
+    /// If the bundles have different ids, or if they have different anchors,
+    /// meaning the same bundle is claimed to be committed into different
+    /// witness transactions.
+    pub fn merge_reveal(mut self, other: Self) -> Result<Self, MergeConflict> {
+        let bundle_id = self.bundle.bundle_id();
+        let other_id = other.bundle.bundle_id();
+        if bundle_id != other_id {
+            return Err(MergeConflict::BundleMismatch(bundle_id, other_id));
+        }
+        if self.anchor != other.anchor {
+            return Err(MergeConflict::AnchorMismatch(
+                bundle_id,
+                self.anchor.txid,
+                other.anchor.txid,
+            ));
+        }
+        for (opid, item) in other.bundle.into_inner() {
+            if let Some(existing) = self.bundle.get_mut(&opid) {
+                if existing.transition.is_none() {
+                    existing.transition = item.transition;
+                }
+            }
+        }
+        Ok(self)
+    }
+}
+
+impl Extension {
+    /// Merges revealed seals and state from other copy of the same extension.
+    ///
+    /// Both extensions must have the same [`OpId`]; since the id commits to
+    /// the concealed extension data, the extensions may differ only in which
+    /// of the assigned seals and state are revealed.
+    ///
+    /// # Errors
+    ///
+    /// If the extensions have different ids.
+    pub fn merge_reveal(self, other: Self) -> Result<Self, MergeConflict> {
+        let opid = self.id();
+        let other_id = other.id();
+        if opid != other_id {
+            return Err(MergeConflict::ExtensionMismatch(opid, other_id));
+        }
+        let assignments = self
+            .assignments
+            .merge_reveal(other.assignments)
+            .expect("extensions with the same id have the same concealed assignments");
+        Ok(Extension {
+            assignments,
+            ..self
+        })
+    }
+}
+
+/// Statistics on the number of state transitions in the consignment bundles,
+/// returned by [`ConsignmentApi::bundle_stats`].
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct BundleStats {
+    /// Total number of anchored bundles.
+    pub count: usize,
+    /// Minimal number of transitions in a bundle.
+    pub min: usize,
+    /// Maximal number of transitions in a bundle.
+    pub max: usize,
+    /// Average number of transitions per bundle.
+    pub mean: f64,
+}
+
+/// Graph of dependencies between the consignment operations, returned by
+/// [`ConsignmentApi::op_graph`].
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct OpGraph {
+    /// Ids of all operations known to the consignment, sorted.
+    pub nodes: Vec<OpId>,
+    /// Dependencies of the operations, as pairs of the dependent operation id
+    /// and the id of the operation whose outputs it spends or valencies it
+    /// redeems. The dependency may be absent from the consignment and thus
+    /// from the graph nodes.
+    pub edges: Vec<(OpId, OpId)>,
+}
+
+impl OpGraph {
+    /// Checks that the graph has no cycles, reporting [`Failure::Custom`] for
+    /// each operation which is a part of a cycle or depends on it.
+    pub fn verify_acyclic(&self) -> Status {
+        let mut dependents = BTreeMap::<OpId, usize>::new();
+        let mut dependencies = BTreeMap::<OpId, Vec<OpId>>::new();
+        for (opid, dependency) in &self.edges {
+            *dependents.entry(*dependency).or_default() += 1;
+            dependencies.entry(*opid).or_default().push(*dependency);
+        }
+
+        // Removing operations nothing depends on, one by one; all operations
+        // which remain are parts of cycles or depend on them
+        let mut queue = self
+            .nodes
+            .iter()
+            .filter(|opid| !dependents.contains_key(opid))
+            .copied()
+            .collect::<VecDeque<_>>();
+        while let Some(opid) = queue.pop_front() {
+            for dependency in dependencies.remove(&opid).unwrap_or_default() {
+                let Some(count) = dependents.get_mut(&dependency) else {
+                    continue;
+                };
+                *count -= 1;
+                if *count == 0 {
+                    dependents.remove(&dependency);
+                    queue.push_back(dependency);
+                }
+            }
+        }
+
+        dependents
+            .into_keys()
+            .map(|opid| {
+                Failure::Custom(format!("operation {opid} is a part of a dependency cycle"))
+            })
+            .collect()
+    }
+
+    /// Exports graph in Graphviz DOT format, with edges directed along the
+    /// state consumption: from an operation to the ones spending its outputs
+    /// or redeeming its valencies.
+    ///
+    /// Nodes are labeled with the first 8 characters of the Baid58 form of the
+    /// operation ids; operations without dependencies (i.e. genesis) are drawn
+    /// as double circles.
+    pub fn to_dot(&self) -> String {
+        let mut dot = s!("digraph operations {\n");
+        for opid in &self.nodes {
+            let label = &opid.to_baid58_string()[..8];
+            let shape = match self.edges.iter().any(|(dependent, _)| dependent == opid) {
+                true => "circle",
+                false => "doublecircle",
+            };
+            dot.push_str(&format!("    \"{opid}\" [label=\"{label}\", shape={shape}];\n"));
+        }
+        for (dependent, dependency) in &self.edges {
+            dot.push_str(&format!("    \"{dependency}\" -> \"{dependent}\";\n"));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Conflicts preventing merge of two consignments.
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum MergeConflict {
+    /// consignments belong to different contracts {0} and {1}.
+    GenesisMismatch(ContractId, ContractId),
+
+    /// consignments use different schemata {0} and {1}.
+    SchemaMismatch(SchemaId, SchemaId),
+
+    /// bundles {0} and {1} can't be merged since they have different ids.
+    BundleMismatch(BundleId, BundleId),
+
+    /// bundle {0} is anchored to two different witness transactions {1} and
+    /// {2}.
+    AnchorMismatch(BundleId, Txid, Txid),
+
+    /// extensions {0} and {1} can't be merged since they have different ids.
+    ExtensionMismatch(OpId, OpId),
+}
+
+/// Consignment produced by merging two consignments of the same contract with
+/// [`ConsignmentApi::try_merge`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MergedConsignment {
+    schema: SubSchema,
+    genesis: Genesis,
+    bundles: Vec<AnchoredBundle>,
+    extensions: BTreeMap<OpId, Extension>,
+    terminals: BTreeSet<(BundleId, SecretSeal)>,
+    transition_index: BTreeMap<OpId, usize>,
+}
+
+impl MergedConsignment {
+    fn with(
+        schema: SubSchema,
+        genesis: Genesis,
+        bundles: Vec<AnchoredBundle>,
+        extensions: BTreeMap<OpId, Extension>,
+        terminals: BTreeSet<(BundleId, SecretSeal)>,
+    ) -> Self {
+        let transition_index = bundles
+            .iter()
+            .enumerate()
+            .flat_map(|(pos, ab)| {
+                ab.bundle
+                    .values()
+                    .filter_map(|item| item.transition.as_ref())
+                    .map(move |transition| (transition.id(), pos))
+            })
+            .collect();
+        MergedConsignment {
+            schema,
+            genesis,
+            bundles,
+            extensions,
+            terminals,
+            transition_index,
+        }
+    }
+}
+
+impl ConsignmentApi for MergedConsignment {
+    type BundleIter<'container> = std::slice::Iter<'container, AnchoredBundle>;
+
+    fn schema(&self) -> &SubSchema { &self.schema }
+
+    fn operation(&self, opid: OpId) -> Option<OpRef> {
+        if opid == self.genesis.id() {
+            return Some(OpRef::Genesis(&self.genesis));
+        }
+        self.transition(opid)
+            .map(OpRef::Transition)
+            .or_else(|| self.extension(opid).map(OpRef::Extension))
+    }
+
+    fn genesis(&self) -> &Genesis { &self.genesis }
+
+    fn transition(&self, opid: OpId) -> Option<&Transition> {
+        let pos = *self.transition_index.get(&opid)?;
+        self.bundles[pos]
+            .bundle
+            .get(&opid)
+            .and_then(|item| item.transition.as_ref())
+    }
+
+    fn extension(&self, opid: OpId) -> Option<&Extension> { self.extensions.get(&opid) }
+
+    fn terminals(&self) -> BTreeSet<(BundleId, SecretSeal)> { self.terminals.clone() }
+
+    fn anchored_bundles(&self) -> Self::BundleIter<'_> { self.bundles.iter() }
+
+    fn bundle_by_id(&self, bundle_id: BundleId) -> Option<&TransitionBundle> {
+        self.bundles
+            .iter()
+            .map(|ab| &ab.bundle)
+            .find(|bundle| bundle.bundle_id() == bundle_id)
+    }
+
+    fn op_ids_except(&self, ids: &BTreeSet<OpId>) -> BTreeSet<OpId> {
+        self.transition_index
+            .keys()
+            .chain(self.extensions.keys())
+            .copied()
+            .chain([self.genesis.id()])
+            .filter(|opid| !ids.contains(opid))
+            .collect()
+    }
+
+    fn has_operation(&self, opid: OpId) -> bool { self.operation(opid).is_some() }
+
+    fn known_transitions_by_bundle_id(&self, bundle_id: BundleId) -> Option<Vec<&Transition>> {
+        let bundle = self.bundle_by_id(bundle_id)?;
+        Some(
+            bundle
+                .values()
+                .filter_map(|item| item.transition.as_ref())
+                .collect(),
+        )
+    }
+}
+
 /// Trait defining common data access API for all storage-related RGB structures
 ///
 /// # Verification
@@ -66,6 +347,10 @@ pub trait ConsignmentApi {
     /// extension) matching the provided id, or `None` otherwise
     fn operation(&self, opid: OpId) -> Option<OpRef>;
 
+    /// Detects type of the operation with the provided id, if the operation
+    /// is known to the consignment.
+    fn op_type(&self, opid: OpId) -> Option<OpType> { self.operation(opid).map(|op| op.op_type()) }
+
     /// Contract genesis.
     fn genesis(&self) -> &Genesis;
 
@@ -114,4 +399,652 @@ pub trait ConsignmentApi {
     fn has_operation(&self, opid: OpId) -> bool;
 
     fn known_transitions_by_bundle_id(&self, bundle_id: BundleId) -> Option<Vec<&Transition>>;
+
+    /// Checks that all revealed transitions from the anchored bundles belong
+    /// to the same contract as the consignment genesis, protecting from
+    /// bundles spliced from other contracts.
+    ///
+    /// Each bundle containing transitions of a foreign contract is reported
+    /// once with [`Failure::BundleForeignContract`].
+    fn verify_single_contract(&self) -> Status {
+        let mut status = Status::new();
+        let contract_id = self.genesis().contract_id();
+        for anchored_bundle in self.anchored_bundles() {
+            if let Some(foreign) = anchored_bundle
+                .bundle
+                .values()
+                .filter_map(|item| item.transition.as_ref())
+                .map(|transition| transition.contract_id)
+                .find(|id| *id != contract_id)
+            {
+                status.add_failure(Failure::BundleForeignContract(
+                    anchored_bundle.bundle.bundle_id(),
+                    foreign,
+                ));
+            }
+        }
+        status
+    }
+
+    /// Returns mapping of all anchored bundles to their witness transaction
+    /// ids and anchors.
+    fn anchor_map(&self) -> BTreeMap<BundleId, (Txid, Anchor<mpc::MerkleProof>)> {
+        self.anchored_bundles()
+            .map(|ab| (ab.bundle.bundle_id(), (ab.anchor.txid, ab.anchor.clone())))
+            .collect()
+    }
+
+    /// Extracts graph of dependencies between the consignment operations,
+    /// which can be checked for cycles with [`OpGraph::verify_acyclic`].
+    fn op_graph(&self) -> OpGraph {
+        let mut nodes = BTreeSet::new();
+        let mut edges = BTreeSet::new();
+        let mut queue = self
+            .anchored_bundles()
+            .flat_map(|ab| ab.bundle.values())
+            .filter_map(|item| item.transition.as_ref())
+            .map(OpRef::Transition)
+            .chain(
+                self.op_ids_except(&none!())
+                    .into_iter()
+                    .filter_map(|opid| self.operation(opid)),
+            )
+            .chain([OpRef::Genesis(self.genesis())])
+            .collect::<VecDeque<_>>();
+        while let Some(op) = queue.pop_front() {
+            let opid = op.id();
+            if !nodes.insert(opid) {
+                continue;
+            }
+            let dependencies = match op {
+                OpRef::Genesis(_) => vec![],
+                OpRef::Transition(transition) => transition
+                    .inputs
+                    .iter()
+                    .map(|input| input.prev_out.op)
+                    .collect(),
+                OpRef::Extension(extension) => extension
+                    .redeemed
+                    .iter()
+                    .map(|(_, prev_id)| *prev_id)
+                    .collect(),
+            };
+            for dependency in dependencies {
+                edges.insert((opid, dependency));
+                queue.extend(self.operation(dependency));
+            }
+        }
+        OpGraph {
+            nodes: nodes.into_iter().collect(),
+            edges: edges.into_iter().collect(),
+        }
+    }
+
+    /// Collects statistics on the number of transitions (both revealed and
+    /// concealed) per anchored bundle. For consignments without bundles all
+    /// the values are zero.
+    fn bundle_stats(&self) -> BundleStats {
+        let sizes = self
+            .anchored_bundles()
+            .map(|ab| ab.bundle.len())
+            .collect::<Vec<_>>();
+        if sizes.is_empty() {
+            return BundleStats::default();
+        }
+        BundleStats {
+            count: sizes.len(),
+            min: sizes.iter().copied().min().unwrap_or_default(),
+            max: sizes.iter().copied().max().unwrap_or_default(),
+            mean: sizes.iter().sum::<usize>() as f64 / sizes.len() as f64,
+        }
+    }
+
+    /// Merges this consignment with other consignment of the same contract,
+    /// producing a consignment containing operations, anchors and terminals
+    /// of both of them.
+    ///
+    /// Bundles and extensions present in both consignments are merged with
+    /// [`AnchoredBundle::merge_reveal`] and [`Extension::merge_reveal`]. Since
+    /// the merge only unions the contract history, the merged consignment
+    /// validates whenever both of the original consignments do.
+    ///
+    /// # Errors
+    ///
+    /// If the consignments belong to different contracts or schemata, or if
+    /// some bundle is anchored differently in the consignments.
+    fn try_merge(&self, other: &impl ConsignmentApi) -> Result<MergedConsignment, MergeConflict> {
+        let (ours, theirs) = (self.genesis().contract_id(), other.genesis().contract_id());
+        if ours != theirs {
+            return Err(MergeConflict::GenesisMismatch(ours, theirs));
+        }
+        let (ours, theirs) = (self.schema().schema_id(), other.schema().schema_id());
+        if ours != theirs {
+            return Err(MergeConflict::SchemaMismatch(ours, theirs));
+        }
+
+        let mut bundles = BTreeMap::<BundleId, AnchoredBundle>::new();
+        for anchored_bundle in self.anchored_bundles().chain(other.anchored_bundles()) {
+            let bundle_id = anchored_bundle.bundle.bundle_id();
+            let merged = match bundles.remove(&bundle_id) {
+                Some(existing) => existing.merge_reveal(anchored_bundle.clone())?,
+                None => anchored_bundle.clone(),
+            };
+            bundles.insert(bundle_id, merged);
+        }
+
+        let mut extensions = BTreeMap::<OpId, Extension>::new();
+        let ours = self
+            .op_ids_except(&none!())
+            .into_iter()
+            .filter_map(|opid| self.extension(opid));
+        let theirs = other
+            .op_ids_except(&none!())
+            .into_iter()
+            .filter_map(|opid| other.extension(opid));
+        for extension in ours.chain(theirs) {
+            let opid = extension.id();
+            let merged = match extensions.remove(&opid) {
+                Some(existing) => existing.merge_reveal(extension.clone())?,
+                None => extension.clone(),
+            };
+            extensions.insert(opid, merged);
+        }
+
+        let mut terminals = self.terminals();
+        terminals.extend(other.terminals());
+
+        Ok(MergedConsignment::with(
+            self.schema().clone(),
+            self.genesis().clone(),
+            bundles.into_values().collect(),
+            extensions,
+            terminals,
+        ))
+    }
+
+    /// Computes fingerprint of the whole consignment, committing to the
+    /// schema, genesis, terminals, all state extensions and all anchored
+    /// bundles.
+    ///
+    /// Unlike [`crate::ContractId`], the hash changes whenever any of the
+    /// consignment data are changed, so it can be used for cache invalidation
+    /// and tamper detection. Extensions are committed in the order of their
+    /// operation ids and anchored bundles in the order of their bundle ids,
+    /// such that the hash does not depend on the order in which the container
+    /// keeps them. Each copy of a bundle present in the container more than
+    /// once is committed as well.
+    fn integrity_hash(&self) -> [u8; 32] {
+        let mut data = Vec::new();
+        let w = StrictWriter::with(u32::MAX as usize, &mut data);
+        let w = self
+            .schema()
+            .schema_id()
+            .strict_encode(w)
+            .expect("in-memory encoding");
+        let w = self.genesis().strict_encode(w).expect("in-memory encoding");
+        let terminals = self.terminals();
+        let mut w = (terminals.len() as u32)
+            .strict_encode(w)
+            .expect("in-memory encoding");
+        for (bundle_id, seal) in terminals {
+            w = bundle_id.strict_encode(w).expect("in-memory encoding");
+            w = seal.strict_encode(w).expect("in-memory encoding");
+        }
+
+        // Operation ids are returned as an ordered set
+        let extensions = self
+            .op_ids_except(&none!())
+            .into_iter()
+            .filter_map(|opid| self.extension(opid))
+            .collect::<Vec<_>>();
+        w = (extensions.len() as u32)
+            .strict_encode(w)
+            .expect("in-memory encoding");
+        for extension in extensions {
+            w = extension.strict_encode(w).expect("in-memory encoding");
+        }
+
+        // Copies of a bundle with the same id are ordered by their encoding
+        let mut bundles = self
+            .anchored_bundles()
+            .map(|ab| {
+                let mut bundle_data = Vec::new();
+                ab.strict_encode(StrictWriter::with(u32::MAX as usize, &mut bundle_data))
+                    .expect("in-memory encoding");
+                (ab.bundle.bundle_id(), bundle_data)
+            })
+            .collect::<Vec<_>>();
+        bundles.sort();
+        (bundles.len() as u32)
+            .strict_encode(w)
+            .expect("in-memory encoding");
+        for (_, bundle_data) in bundles {
+            data.extend(bundle_data);
+        }
+
+        let mut engine = Sha256::new_with_prefix(CONSIGNMENT_HASH_TAG);
+        engine.update(&data);
+        engine.finalize().into()
+    }
+}
+
+/// Tag used in computing [`ConsignmentApi::integrity_hash`].
+const CONSIGNMENT_HASH_TAG: &[u8] = b"urn:lnpbp:rgb:consignment:integrity:v01";
+
+#[cfg(test)]
+pub(crate) mod test {
+    use amplify::confinement::Confined;
+    use commit_verify::Conceal;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::{
+        Assign, AssignRights, BundleItem, GenesisSeal, Input, Inputs, Opout, TypedAssigns,
+        VoidState,
+    };
+
+    /// Consignment keeping all operations in memory, used by tests.
+    pub(crate) struct Mock {
+        pub schema: SubSchema,
+        pub genesis: Genesis,
+        pub bundles: Vec<AnchoredBundle>,
+        pub extensions: Vec<Extension>,
+    }
+
+    impl ConsignmentApi for Mock {
+        type BundleIter<'container> = std::slice::Iter<'container, AnchoredBundle>;
+
+        fn schema(&self) -> &SubSchema { &self.schema }
+        fn operation(&self, opid: OpId) -> Option<OpRef> {
+            if opid == self.genesis.id() {
+                return Some(OpRef::Genesis(&self.genesis));
+            }
+            self.bundles
+                .iter()
+                .flat_map(|ab| ab.bundle.values())
+                .filter_map(|item| item.transition.as_ref())
+                .find(|transition| transition.id() == opid)
+                .map(OpRef::Transition)
+                .or_else(|| self.extension(opid).map(OpRef::Extension))
+        }
+        fn genesis(&self) -> &Genesis { &self.genesis }
+        fn transition(&self, _: OpId) -> Option<&Transition> { None }
+        fn extension(&self, opid: OpId) -> Option<&Extension> {
+            self.extensions
+                .iter()
+                .find(|extension| extension.id() == opid)
+        }
+        fn terminals(&self) -> BTreeSet<(BundleId, SecretSeal)> { empty!() }
+        fn anchored_bundles(&self) -> Self::BundleIter<'_> { self.bundles.iter() }
+        fn bundle_by_id(&self, bundle_id: BundleId) -> Option<&TransitionBundle> {
+            self.bundles
+                .iter()
+                .map(|ab| &ab.bundle)
+                .find(|bundle| bundle.bundle_id() == bundle_id)
+        }
+        fn op_ids_except(&self, ids: &BTreeSet<OpId>) -> BTreeSet<OpId> {
+            self.bundles
+                .iter()
+                .flat_map(|ab| ab.bundle.values())
+                .filter_map(|item| item.transition.as_ref())
+                .map(|transition| transition.id())
+                .chain(self.extensions.iter().map(|extension| extension.id()))
+                .chain([self.genesis.id()])
+                .filter(|opid| !ids.contains(opid))
+                .collect()
+        }
+        fn has_operation(&self, opid: OpId) -> bool { opid == self.genesis.id() }
+        fn known_transitions_by_bundle_id(&self, _: BundleId) -> Option<Vec<&Transition>> { None }
+    }
+
+    fn anchored_bundle(opid: u8, input: u16) -> AnchoredBundle {
+        let item = BundleItem {
+            inputs: tiny_bset! { input },
+            transition: None,
+        };
+        AnchoredBundle {
+            anchor: strict_dumb!(),
+            bundle: TransitionBundle::from(tiny_bmap! { OpId::from([opid; 32]) => item }),
+        }
+    }
+
+    #[test]
+    fn integrity_hash_canonical() {
+        let a = anchored_bundle(1, 0);
+        let b = anchored_bundle(2, 1);
+        let mock1 = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![a.clone(), b.clone()],
+            extensions: vec![],
+        };
+        let mock2 = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![b, a.clone()],
+            extensions: vec![],
+        };
+        let mock3 = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![a],
+            extensions: vec![],
+        };
+        assert_eq!(mock1.integrity_hash(), mock2.integrity_hash());
+        assert_ne!(mock1.integrity_hash(), mock3.integrity_hash());
+    }
+
+    #[test]
+    fn integrity_hash_extensions() {
+        let extension = Extension::strict_dumb();
+        let tampered = Extension {
+            extension_type: 1,
+            ..extension.clone()
+        };
+        let mock = |extensions| Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![],
+            extensions,
+        };
+        assert_ne!(mock(vec![]).integrity_hash(), mock(vec![extension.clone()]).integrity_hash());
+        assert_ne!(
+            mock(vec![extension.clone()]).integrity_hash(),
+            mock(vec![tampered.clone()]).integrity_hash()
+        );
+        assert_eq!(
+            mock(vec![extension.clone(), tampered.clone()]).integrity_hash(),
+            mock(vec![tampered, extension]).integrity_hash()
+        );
+    }
+
+    #[test]
+    fn integrity_hash_duplicate_bundles() {
+        let a = anchored_bundle(1, 0);
+        let mut reanchored = a.clone();
+        reanchored.anchor.txid = Txid::from([0xAB; 32]);
+        let mock = |bundles| Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles,
+            extensions: vec![],
+        };
+        assert_ne!(
+            mock(vec![a.clone()]).integrity_hash(),
+            mock(vec![a.clone(), reanchored.clone()]).integrity_hash()
+        );
+        assert_eq!(
+            mock(vec![a.clone(), reanchored.clone()]).integrity_hash(),
+            mock(vec![reanchored, a]).integrity_hash()
+        );
+    }
+
+    #[test]
+    fn try_merge() {
+        let mock = |bundles| Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles,
+            extensions: vec![],
+        };
+        let a = anchored_bundle(1, 0);
+        let b = anchored_bundle(2, 1);
+        let c = anchored_bundle(3, 2);
+        let extension = Extension::strict_dumb();
+        let other_extension = Extension {
+            extension_type: 1,
+            ..Extension::strict_dumb()
+        };
+        let mock1 = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![a.clone(), b.clone()],
+            extensions: vec![extension.clone()],
+        };
+        let mock2 = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![c.clone(), b.clone()],
+            extensions: vec![extension.clone(), other_extension.clone()],
+        };
+        let mut expected = vec![a.clone(), b.clone(), c];
+        expected.sort_by_key(|ab| ab.bundle.bundle_id());
+        let merged = mock1.try_merge(&mock2).unwrap();
+        assert_eq!(merged.bundles, expected);
+        assert_eq!(merged.extension(extension.id()), Some(&extension));
+        assert_eq!(merged.extension(other_extension.id()), Some(&other_extension));
+        assert_eq!(
+            merged.op_ids_except(&none!()),
+            mock1
+                .op_ids_except(&none!())
+                .union(&mock2.op_ids_except(&none!()))
+                .copied()
+                .collect()
+        );
+        assert_eq!(merged.try_merge(&mock1), Ok(merged.clone()));
+
+        // Extension with the same id, but with its seal concealed
+        let seal = GenesisSeal::strict_dumb();
+        let revealed = Extension {
+            assignments: Confined::try_from(bmap! {
+                1 => TypedAssigns::Declarative(Confined::try_from(vec![
+                    AssignRights::revealed(seal, VoidState::default())
+                ]).unwrap()),
+            })
+            .unwrap()
+            .into(),
+            ..Extension::strict_dumb()
+        };
+        let concealed = Extension {
+            assignments: Confined::try_from(bmap! {
+                1 => TypedAssigns::Declarative(Confined::try_from(vec![
+                    Assign::ConfidentialSeal { seal: seal.conceal(), state: VoidState::default() }
+                ]).unwrap()),
+            })
+            .unwrap()
+            .into(),
+            ..Extension::strict_dumb()
+        };
+        assert_eq!(revealed.id(), concealed.id());
+        let mock4 = Mock {
+            extensions: vec![revealed.clone()],
+            ..mock(vec![])
+        };
+        let mock5 = Mock {
+            extensions: vec![concealed.clone()],
+            ..mock(vec![])
+        };
+        for merged in [mock4.try_merge(&mock5).unwrap(), mock5.try_merge(&mock4).unwrap()] {
+            let extension = merged.extension(revealed.id()).unwrap();
+            let assigns = extension.assignments.get(&1).unwrap();
+            assert_eq!(assigns.revealed_seal_at(0).unwrap(), Some(seal));
+        }
+        assert_eq!(
+            concealed.clone().merge_reveal(Extension::strict_dumb()),
+            Err(MergeConflict::ExtensionMismatch(concealed.id(), Extension::strict_dumb().id()))
+        );
+
+        let mut conflicting = b.clone();
+        conflicting.anchor.txid = Txid::from([0xAB; 32]);
+        let mock3 = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![conflicting],
+            extensions: vec![],
+        };
+        assert_eq!(
+            mock1.try_merge(&mock3),
+            Err(MergeConflict::AnchorMismatch(
+                b.bundle.bundle_id(),
+                b.anchor.txid,
+                Txid::from([0xAB; 32])
+            ))
+        );
+        assert_eq!(
+            a.clone().merge_reveal(b.clone()),
+            Err(MergeConflict::BundleMismatch(a.bundle.bundle_id(), b.bundle.bundle_id()))
+        );
+    }
+
+    #[test]
+    fn op_graph() {
+        let genesis = Genesis::strict_dumb();
+        let spending = |prev_id: OpId| Transition {
+            inputs: Inputs::from(
+                Confined::try_from(bset! { Input::with(Opout::new(prev_id, 0, 0)) }).unwrap(),
+            ),
+            ..Transition::strict_dumb()
+        };
+        let first = spending(genesis.id());
+        let second = spending(first.id());
+        let bundles = [&first, &second]
+            .map(|transition| AnchoredBundle {
+                anchor: strict_dumb!(),
+                bundle: TransitionBundle::from(tiny_bmap! { transition.id() => BundleItem {
+                    inputs: none!(),
+                    transition: Some(transition.clone()),
+                }}),
+            })
+            .to_vec();
+        let mock = Mock {
+            schema: SubSchema::default(),
+            genesis: genesis.clone(),
+            bundles,
+            extensions: vec![],
+        };
+
+        let graph = mock.op_graph();
+        let mut nodes = vec![genesis.id(), first.id(), second.id()];
+        nodes.sort();
+        assert_eq!(graph.nodes, nodes);
+        let mut edges = vec![(first.id(), genesis.id()), (second.id(), first.id())];
+        edges.sort();
+        assert_eq!(graph.edges, edges);
+        assert_eq!(graph.verify_acyclic(), Status::new());
+
+        let cyclic = OpGraph {
+            edges: vec![(genesis.id(), second.id())]
+                .into_iter()
+                .chain(graph.edges)
+                .collect(),
+            ..graph
+        };
+        assert_eq!(cyclic.verify_acyclic().failures.len(), 3);
+    }
+
+    #[test]
+    fn op_graph_dot() {
+        let genesis = OpId::from([0xAB; 32]);
+        let transition = OpId::from([0xCD; 32]);
+        let graph = OpGraph {
+            nodes: vec![genesis, transition],
+            edges: vec![(transition, genesis)],
+        };
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph operations {\n"));
+        assert!(
+            dot.contains(&format!("    \"{genesis}\" [label=\"CZ8YUVdk\", shape=doublecircle];\n"))
+        );
+        assert!(
+            dot.contains(&format!("    \"{transition}\" [label=\"ErNbLjU6\", shape=circle];\n"))
+        );
+        assert!(dot.contains(&format!("    \"{genesis}\" -> \"{transition}\";\n")));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn bundle_stats() {
+        let mut a = anchored_bundle(1, 0);
+        let b = anchored_bundle(2, 1);
+        for opid in 3..6u8 {
+            let item = BundleItem {
+                inputs: tiny_bset! { opid as u16 },
+                transition: None,
+            };
+            a.bundle.insert(OpId::from([opid; 32]), item).unwrap();
+        }
+        let mock = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![a, b],
+            extensions: vec![],
+        };
+        assert_eq!(mock.bundle_stats(), BundleStats {
+            count: 2,
+            min: 1,
+            max: 4,
+            mean: 2.5,
+        });
+    }
+
+    #[test]
+    fn anchor_map() {
+        let a = anchored_bundle(1, 0);
+        let mut b = anchored_bundle(2, 1);
+        b.anchor.txid = Txid::from([0xAB; 32]);
+        let mock = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![a.clone(), b.clone()],
+            extensions: vec![],
+        };
+        let map = mock.anchor_map();
+        assert_eq!(map.len(), mock.bundles.len());
+        for ab in [a, b] {
+            assert_eq!(map[&ab.bundle.bundle_id()], (ab.anchor.txid, ab.anchor.clone()));
+        }
+    }
+
+    #[test]
+    fn op_type() {
+        let transition = Transition::strict_dumb();
+        let item = BundleItem {
+            inputs: none!(),
+            transition: Some(transition.clone()),
+        };
+        let mock = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![AnchoredBundle {
+                anchor: strict_dumb!(),
+                bundle: TransitionBundle::from(tiny_bmap! { transition.id() => item }),
+            }],
+            extensions: vec![],
+        };
+        assert_eq!(mock.op_type(mock.genesis.id()), Some(OpType::Genesis));
+        assert_eq!(mock.op_type(transition.id()), Some(OpType::StateTransition));
+        assert_eq!(mock.op_type(OpId::from([0xAB; 32])), None);
+    }
+
+    #[test]
+    fn foreign_bundle() {
+        let genesis = Genesis::strict_dumb();
+        let foreign_id = ContractId::from([0xAB; 32]);
+        let bundle = |contract_id| {
+            let transition = Transition {
+                contract_id,
+                ..Transition::strict_dumb()
+            };
+            let item = BundleItem {
+                inputs: none!(),
+                transition: Some(transition.clone()),
+            };
+            AnchoredBundle {
+                anchor: strict_dumb!(),
+                bundle: TransitionBundle::from(tiny_bmap! { transition.id() => item }),
+            }
+        };
+        let own = bundle(genesis.contract_id());
+        let spliced = bundle(foreign_id);
+        let mock = Mock {
+            schema: SubSchema::default(),
+            genesis,
+            bundles: vec![own, spliced.clone()],
+            extensions: vec![],
+        };
+        assert_eq!(mock.verify_single_contract().failures, vec![Failure::BundleForeignContract(
+            spliced.bundle.bundle_id(),
+            foreign_id
+        )]);
+    }
 }
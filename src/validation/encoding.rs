@@ -0,0 +1,837 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2023 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2023 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2023 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Strict encoding of the validation [`Status`] and its entries.
+//!
+//! The status keeps its entries in unbounded vectors and strings, and some of
+//! the failures carry errors defined by the bitcoin libraries, none of which
+//! are strict types. Thus, the status types are encoded through their
+//! representations defined in this module, which bound the number of entries
+//! and the length of the messages and carry the foreign errors as their
+//! descriptions. Since the errors can't be restored from their descriptions,
+//! [`Failure::SealInvalid`] and [`Failure::AnchorInvalid`] can be encoded, but
+//! not decoded.
+
+use std::io;
+
+use amplify::confinement::{Confined, U16, U24};
+use bp::{Chain, Txid};
+use strict_encoding::{
+    DecodeError, StrictDecode, StrictDeserialize, StrictDumb, StrictEncode, StrictSerialize,
+    StrictType, TypeName, TypedRead, TypedWrite,
+};
+use strict_types::SemId;
+
+use super::status::{CheckKind, CustomCode, Failure, Info, ScriptError, Status, Warning};
+use crate::contract::Opout;
+use crate::schema::{self, SchemaId};
+use crate::{
+    AssignmentType, BundleId, ContractId, Ffv, OccurrencesMismatch, OpFullType, OpId, SecretSeal,
+    StateType, LIB_NAME_RGB,
+};
+
+type Message = Confined<String, 0, U16>;
+type Entries<T> = Confined<Vec<T>, 0, U24>;
+
+fn message(msg: impl ToString) -> io::Result<Message> {
+    Confined::try_from(msg.to_string())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))
+}
+
+fn entries<T, R>(items: &[T], f: impl Fn(&T) -> io::Result<R>) -> io::Result<Entries<R>> {
+    let items = items.iter().map(f).collect::<io::Result<Vec<_>>>()?;
+    Confined::try_from(items)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))
+}
+
+fn decode_entries<R, T>(items: Entries<R>) -> Result<Vec<T>, DecodeError>
+where T: TryFrom<R, Error = DecodeError> {
+    items.into_iter().map(T::try_from).collect()
+}
+
+/// Implements strict encoding of a status type through its representation.
+macro_rules! strict_repr {
+    ($ty:ident, $repr:ident, $name:literal, $dumb:expr) => {
+        impl StrictType for $ty {
+            const STRICT_LIB_NAME: &'static str = LIB_NAME_RGB;
+            fn strict_name() -> Option<TypeName> { Some(tn!($name)) }
+        }
+        impl StrictDumb for $ty {
+            fn strict_dumb() -> Self { $dumb }
+        }
+        impl StrictEncode for $ty {
+            fn strict_encode<W: TypedWrite>(&self, writer: W) -> io::Result<W> {
+                $repr::with(self)?.strict_encode(writer)
+            }
+        }
+        impl StrictDecode for $ty {
+            fn strict_decode(reader: &mut impl TypedRead) -> Result<Self, DecodeError> {
+                $repr::strict_decode(reader)?.try_into()
+            }
+        }
+    };
+}
+
+impl StrictSerialize for Status {}
+impl StrictDeserialize for Status {}
+
+strict_repr!(Status, StatusRepr, "Status", Status::default());
+strict_repr!(Failure, FailureRepr, "Failure", Failure::SchemaBlankTransitionRedefined);
+strict_repr!(Warning, WarningRepr, "Warning", Warning::ExcessiveOperation(strict_dumb!()));
+strict_repr!(Info, InfoRepr, "Info", Info::CheckPassed {
+    op: strict_dumb!(),
+    check: strict_dumb!(),
+});
+
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB, rename = "Status")]
+struct StatusRepr {
+    unresolved_txids: Entries<Txid>,
+    unmined_terminals: Entries<Txid>,
+    failures: Entries<FailureRepr>,
+    warnings: Entries<WarningRepr>,
+    info: Entries<InfoRepr>,
+}
+
+impl StatusRepr {
+    fn with(status: &Status) -> io::Result<Self> {
+        Ok(StatusRepr {
+            unresolved_txids: entries(&status.unresolved_txids, |txid| Ok(*txid))?,
+            unmined_terminals: entries(&status.unmined_terminals, |txid| Ok(*txid))?,
+            failures: entries(&status.failures, FailureRepr::with)?,
+            warnings: entries(&status.warnings, WarningRepr::with)?,
+            info: entries(&status.info, InfoRepr::with)?,
+        })
+    }
+}
+
+impl TryFrom<StatusRepr> for Status {
+    type Error = DecodeError;
+
+    fn try_from(repr: StatusRepr) -> Result<Self, Self::Error> {
+        Ok(Status {
+            unresolved_txids: repr.unresolved_txids.into_inner(),
+            unmined_terminals: repr.unmined_terminals.into_inner(),
+            failures: decode_entries(repr.failures)?,
+            warnings: decode_entries(repr.warnings)?,
+            info: decode_entries(repr.info)?,
+        })
+    }
+}
+
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB, rename = "ScriptError")]
+struct ScriptErrorRepr {
+    op: OpId,
+    entry_offset: u16,
+    isa_error: Message,
+    exit_code: Option<u8>,
+}
+
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB, tags = order, rename = "Failure")]
+enum FailureRepr {
+    SchemaMismatch {
+        expected: SchemaId,
+        actual: SchemaId,
+    },
+    SchemaUnknown(SchemaId),
+    #[strict_type(dumb)]
+    SchemaBlankTransitionRedefined,
+    SchemaUnimplementedOpcode(u8),
+    UnsupportedFfv {
+        schema: Ffv,
+        max_supported: Ffv,
+    },
+    SchemaGlobalSemIdUnknown(schema::GlobalStateType, SemId),
+    SchemaOwnedSemIdUnknown(schema::AssignmentType, SemId),
+    SchemaOpMetaSemIdUnknown(OpFullType, SemId),
+    TypeLibMismatch(SemId),
+    SchemaOpEmptyInputs(OpFullType),
+    SchemaOpGlobalTypeUnknown(OpFullType, schema::GlobalStateType),
+    SchemaOpAssignmentTypeUnknown(OpFullType, schema::AssignmentType),
+    SchemaOpValencyTypeUnknown(OpFullType, schema::ValencyType),
+    SubschemaGlobalStateMismatch(schema::GlobalStateType),
+    SubschemaAssignmentTypeMismatch(schema::AssignmentType),
+    SubschemaValencyTypeMismatch(schema::ValencyType),
+    SubschemaTransitionTypeMismatch(schema::TransitionType),
+    SubschemaExtensionTypeMismatch(schema::ExtensionType),
+    SubschemaOpMetaMismatch {
+        op_type: OpFullType,
+        expected: SemId,
+        actual: SemId,
+    },
+    SubschemaOpGlobalStateMismatch(OpFullType, schema::GlobalStateType),
+    SubschemaOpInputMismatch(OpFullType, schema::AssignmentType),
+    SubschemaOpRedeemMismatch(OpFullType, schema::ValencyType),
+    SubschemaOpAssignmentsMismatch(OpFullType, schema::AssignmentType),
+    SubschemaOpValencyMismatch(OpFullType, schema::ValencyType),
+    SchemaUnknownExtensionType(OpId, schema::ExtensionType),
+    SchemaUnknownTransitionType(OpId, schema::TransitionType),
+    SchemaUnknownGlobalStateType(OpId, schema::GlobalStateType),
+    SchemaUnknownAssignmentType(OpId, schema::AssignmentType),
+    SchemaUnknownValencyType(OpId, schema::ValencyType),
+    SchemaGlobalStateOccurrences(OpId, schema::GlobalStateType, OccurrencesMismatch),
+    SchemaGlobalStateLimit(OpId, schema::GlobalStateType, u16, u16),
+    SchemaInvalidMetadata(OpId, SemId),
+    SchemaInvalidGlobalValue(OpId, schema::GlobalStateType, SemId),
+    SchemaInvalidOwnedValue(OpId, schema::AssignmentType, SemId),
+    SchemaInputOccurrences(OpId, schema::AssignmentType, OccurrencesMismatch),
+    SchemaAssignmentOccurrences(OpId, schema::AssignmentType, OccurrencesMismatch),
+    OperationAbsent(OpId),
+    TransitionAbsent(OpId),
+    BundleInvalid(BundleId),
+    MetadataCommitmentMismatch(OpId),
+    BundleForeignContract(BundleId, ContractId),
+    ChannelFundingAbsent(Opout),
+    ChannelFundingNotSpent(Opout),
+    ChannelValueMismatch(Opout),
+    NotAnchored(OpId),
+    NetworkMismatch(OpId, Chain, Chain),
+    NotInAnchor(OpId, Txid),
+    NoPrevState {
+        opid: OpId,
+        prev_id: OpId,
+        state_type: schema::AssignmentType,
+    },
+    NoPrevOut(OpId, Opout),
+    LookbackExceeded(OpId),
+    ConfidentialSeal(Opout),
+    MpcInvalid(OpId, Txid),
+    SealNoWitnessTx(Txid),
+    ResolverError(Txid, Message),
+    SealInvalid(OpId, Txid, Message),
+    AnchorInvalid(OpId, Txid, Message),
+    ValencyNoParent {
+        opid: OpId,
+        prev_id: OpId,
+        valency: schema::ValencyType,
+    },
+    NoPrevValency {
+        opid: OpId,
+        prev_id: OpId,
+        valency: schema::ValencyType,
+    },
+    StateTypeMismatch {
+        opid: OpId,
+        state_type: schema::AssignmentType,
+        expected: StateType,
+        found: StateType,
+    },
+    MediaTypeMismatch {
+        opid: OpId,
+        state_type: schema::AssignmentType,
+        expected: schema::MediaType,
+        found: schema::MediaType,
+    },
+    FungibleTypeMismatch {
+        opid: OpId,
+        state_type: schema::AssignmentType,
+        expected: schema::FungibleType,
+        found: schema::FungibleType,
+    },
+    ZeroValueAssignment(OpId, schema::AssignmentType),
+    BulletproofsInvalid(OpId, u16, Message),
+    ScriptFailure(ScriptErrorRepr),
+    Custom(Message),
+    CustomCoded {
+        code: u16,
+        message: Message,
+    },
+}
+
+impl FailureRepr {
+    fn with(failure: &Failure) -> io::Result<Self> {
+        Ok(match failure.clone() {
+            Failure::SchemaMismatch { expected, actual } => {
+                FailureRepr::SchemaMismatch { expected, actual }
+            }
+            Failure::SchemaUnknown(id) => FailureRepr::SchemaUnknown(id),
+            Failure::SchemaBlankTransitionRedefined => FailureRepr::SchemaBlankTransitionRedefined,
+            Failure::SchemaUnimplementedOpcode(code) => {
+                FailureRepr::SchemaUnimplementedOpcode(code)
+            }
+            Failure::UnsupportedFfv {
+                schema,
+                max_supported,
+            } => FailureRepr::UnsupportedFfv {
+                schema,
+                max_supported,
+            },
+            Failure::SchemaGlobalSemIdUnknown(ty, sem_id) => {
+                FailureRepr::SchemaGlobalSemIdUnknown(ty, sem_id)
+            }
+            Failure::SchemaOwnedSemIdUnknown(ty, sem_id) => {
+                FailureRepr::SchemaOwnedSemIdUnknown(ty, sem_id)
+            }
+            Failure::SchemaOpMetaSemIdUnknown(op_type, sem_id) => {
+                FailureRepr::SchemaOpMetaSemIdUnknown(op_type, sem_id)
+            }
+            Failure::TypeLibMismatch(sem_id) => FailureRepr::TypeLibMismatch(sem_id),
+            Failure::SchemaOpEmptyInputs(op_type) => FailureRepr::SchemaOpEmptyInputs(op_type),
+            Failure::SchemaOpGlobalTypeUnknown(op_type, ty) => {
+                FailureRepr::SchemaOpGlobalTypeUnknown(op_type, ty)
+            }
+            Failure::SchemaOpAssignmentTypeUnknown(op_type, ty) => {
+                FailureRepr::SchemaOpAssignmentTypeUnknown(op_type, ty)
+            }
+            Failure::SchemaOpValencyTypeUnknown(op_type, ty) => {
+                FailureRepr::SchemaOpValencyTypeUnknown(op_type, ty)
+            }
+            Failure::SubschemaGlobalStateMismatch(ty) => {
+                FailureRepr::SubschemaGlobalStateMismatch(ty)
+            }
+            Failure::SubschemaAssignmentTypeMismatch(ty) => {
+                FailureRepr::SubschemaAssignmentTypeMismatch(ty)
+            }
+            Failure::SubschemaValencyTypeMismatch(ty) => {
+                FailureRepr::SubschemaValencyTypeMismatch(ty)
+            }
+            Failure::SubschemaTransitionTypeMismatch(ty) => {
+                FailureRepr::SubschemaTransitionTypeMismatch(ty)
+            }
+            Failure::SubschemaExtensionTypeMismatch(ty) => {
+                FailureRepr::SubschemaExtensionTypeMismatch(ty)
+            }
+            Failure::SubschemaOpMetaMismatch {
+                op_type,
+                expected,
+                actual,
+            } => FailureRepr::SubschemaOpMetaMismatch {
+                op_type,
+                expected,
+                actual,
+            },
+            Failure::SubschemaOpGlobalStateMismatch(op_type, ty) => {
+                FailureRepr::SubschemaOpGlobalStateMismatch(op_type, ty)
+            }
+            Failure::SubschemaOpInputMismatch(op_type, ty) => {
+                FailureRepr::SubschemaOpInputMismatch(op_type, ty)
+            }
+            Failure::SubschemaOpRedeemMismatch(op_type, ty) => {
+                FailureRepr::SubschemaOpRedeemMismatch(op_type, ty)
+            }
+            Failure::SubschemaOpAssignmentsMismatch(op_type, ty) => {
+                FailureRepr::SubschemaOpAssignmentsMismatch(op_type, ty)
+            }
+            Failure::SubschemaOpValencyMismatch(op_type, ty) => {
+                FailureRepr::SubschemaOpValencyMismatch(op_type, ty)
+            }
+            Failure::SchemaUnknownExtensionType(opid, ty) => {
+                FailureRepr::SchemaUnknownExtensionType(opid, ty)
+            }
+            Failure::SchemaUnknownTransitionType(opid, ty) => {
+                FailureRepr::SchemaUnknownTransitionType(opid, ty)
+            }
+            Failure::SchemaUnknownGlobalStateType(opid, ty) => {
+                FailureRepr::SchemaUnknownGlobalStateType(opid, ty)
+            }
+            Failure::SchemaUnknownAssignmentType(opid, ty) => {
+                FailureRepr::SchemaUnknownAssignmentType(opid, ty)
+            }
+            Failure::SchemaUnknownValencyType(opid, ty) => {
+                FailureRepr::SchemaUnknownValencyType(opid, ty)
+            }
+            Failure::SchemaGlobalStateOccurrences(opid, ty, mismatch) => {
+                FailureRepr::SchemaGlobalStateOccurrences(opid, ty, mismatch)
+            }
+            Failure::SchemaGlobalStateLimit(opid, ty, count, max) => {
+                FailureRepr::SchemaGlobalStateLimit(opid, ty, count, max)
+            }
+            Failure::SchemaInvalidMetadata(opid, sem_id) => {
+                FailureRepr::SchemaInvalidMetadata(opid, sem_id)
+            }
+            Failure::SchemaInvalidGlobalValue(opid, ty, sem_id) => {
+                FailureRepr::SchemaInvalidGlobalValue(opid, ty, sem_id)
+            }
+            Failure::SchemaInvalidOwnedValue(opid, ty, sem_id) => {
+                FailureRepr::SchemaInvalidOwnedValue(opid, ty, sem_id)
+            }
+            Failure::SchemaInputOccurrences(opid, ty, mismatch) => {
+                FailureRepr::SchemaInputOccurrences(opid, ty, mismatch)
+            }
+            Failure::SchemaAssignmentOccurrences(opid, ty, mismatch) => {
+                FailureRepr::SchemaAssignmentOccurrences(opid, ty, mismatch)
+            }
+            Failure::OperationAbsent(opid) => FailureRepr::OperationAbsent(opid),
+            Failure::TransitionAbsent(opid) => FailureRepr::TransitionAbsent(opid),
+            Failure::BundleInvalid(bundle_id) => FailureRepr::BundleInvalid(bundle_id),
+            Failure::MetadataCommitmentMismatch(opid) => {
+                FailureRepr::MetadataCommitmentMismatch(opid)
+            }
+            Failure::BundleForeignContract(bundle_id, contract_id) => {
+                FailureRepr::BundleForeignContract(bundle_id, contract_id)
+            }
+            Failure::ChannelFundingAbsent(opout) => FailureRepr::ChannelFundingAbsent(opout),
+            Failure::ChannelFundingNotSpent(opout) => FailureRepr::ChannelFundingNotSpent(opout),
+            Failure::ChannelValueMismatch(opout) => FailureRepr::ChannelValueMismatch(opout),
+            Failure::NotAnchored(opid) => FailureRepr::NotAnchored(opid),
+            Failure::NetworkMismatch(opid, expected, actual) => {
+                FailureRepr::NetworkMismatch(opid, expected, actual)
+            }
+            Failure::NotInAnchor(opid, txid) => FailureRepr::NotInAnchor(opid, txid),
+            Failure::NoPrevState {
+                opid,
+                prev_id,
+                state_type,
+            } => FailureRepr::NoPrevState {
+                opid,
+                prev_id,
+                state_type,
+            },
+            Failure::NoPrevOut(opid, opout) => FailureRepr::NoPrevOut(opid, opout),
+            Failure::LookbackExceeded(opid) => FailureRepr::LookbackExceeded(opid),
+            Failure::ConfidentialSeal(opout) => FailureRepr::ConfidentialSeal(opout),
+            Failure::MpcInvalid(opid, txid) => FailureRepr::MpcInvalid(opid, txid),
+            Failure::SealNoWitnessTx(txid) => FailureRepr::SealNoWitnessTx(txid),
+            Failure::ResolverError(txid, msg) => FailureRepr::ResolverError(txid, message(msg)?),
+            Failure::SealInvalid(opid, txid, err) => {
+                FailureRepr::SealInvalid(opid, txid, message(err)?)
+            }
+            Failure::AnchorInvalid(opid, txid, err) => {
+                FailureRepr::AnchorInvalid(opid, txid, message(err)?)
+            }
+            Failure::ValencyNoParent {
+                opid,
+                prev_id,
+                valency,
+            } => FailureRepr::ValencyNoParent {
+                opid,
+                prev_id,
+                valency,
+            },
+            Failure::NoPrevValency {
+                opid,
+                prev_id,
+                valency,
+            } => FailureRepr::NoPrevValency {
+                opid,
+                prev_id,
+                valency,
+            },
+            Failure::StateTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            } => FailureRepr::StateTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            },
+            Failure::MediaTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            } => FailureRepr::MediaTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            },
+            Failure::FungibleTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            } => FailureRepr::FungibleTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            },
+            Failure::ZeroValueAssignment(opid, ty) => FailureRepr::ZeroValueAssignment(opid, ty),
+            Failure::BulletproofsInvalid(opid, no, msg) => {
+                FailureRepr::BulletproofsInvalid(opid, no, message(msg)?)
+            }
+            Failure::ScriptFailure(err) => FailureRepr::ScriptFailure(ScriptErrorRepr {
+                op: err.op,
+                entry_offset: err.entry_offset,
+                isa_error: message(err.isa_error)?,
+                exit_code: err.exit_code,
+            }),
+            Failure::Custom(msg) => FailureRepr::Custom(message(msg)?),
+            Failure::CustomCoded { code, message: msg } => FailureRepr::CustomCoded {
+                code: code.to_u16(),
+                message: message(msg)?,
+            },
+        })
+    }
+}
+
+impl TryFrom<FailureRepr> for Failure {
+    type Error = DecodeError;
+
+    fn try_from(repr: FailureRepr) -> Result<Self, Self::Error> {
+        Ok(match repr {
+            FailureRepr::SchemaMismatch { expected, actual } => {
+                Failure::SchemaMismatch { expected, actual }
+            }
+            FailureRepr::SchemaUnknown(id) => Failure::SchemaUnknown(id),
+            FailureRepr::SchemaBlankTransitionRedefined => Failure::SchemaBlankTransitionRedefined,
+            FailureRepr::SchemaUnimplementedOpcode(code) => {
+                Failure::SchemaUnimplementedOpcode(code)
+            }
+            FailureRepr::UnsupportedFfv {
+                schema,
+                max_supported,
+            } => Failure::UnsupportedFfv {
+                schema,
+                max_supported,
+            },
+            FailureRepr::SchemaGlobalSemIdUnknown(ty, sem_id) => {
+                Failure::SchemaGlobalSemIdUnknown(ty, sem_id)
+            }
+            FailureRepr::SchemaOwnedSemIdUnknown(ty, sem_id) => {
+                Failure::SchemaOwnedSemIdUnknown(ty, sem_id)
+            }
+            FailureRepr::SchemaOpMetaSemIdUnknown(op_type, sem_id) => {
+                Failure::SchemaOpMetaSemIdUnknown(op_type, sem_id)
+            }
+            FailureRepr::TypeLibMismatch(sem_id) => Failure::TypeLibMismatch(sem_id),
+            FailureRepr::SchemaOpEmptyInputs(op_type) => Failure::SchemaOpEmptyInputs(op_type),
+            FailureRepr::SchemaOpGlobalTypeUnknown(op_type, ty) => {
+                Failure::SchemaOpGlobalTypeUnknown(op_type, ty)
+            }
+            FailureRepr::SchemaOpAssignmentTypeUnknown(op_type, ty) => {
+                Failure::SchemaOpAssignmentTypeUnknown(op_type, ty)
+            }
+            FailureRepr::SchemaOpValencyTypeUnknown(op_type, ty) => {
+                Failure::SchemaOpValencyTypeUnknown(op_type, ty)
+            }
+            FailureRepr::SubschemaGlobalStateMismatch(ty) => {
+                Failure::SubschemaGlobalStateMismatch(ty)
+            }
+            FailureRepr::SubschemaAssignmentTypeMismatch(ty) => {
+                Failure::SubschemaAssignmentTypeMismatch(ty)
+            }
+            FailureRepr::SubschemaValencyTypeMismatch(ty) => {
+                Failure::SubschemaValencyTypeMismatch(ty)
+            }
+            FailureRepr::SubschemaTransitionTypeMismatch(ty) => {
+                Failure::SubschemaTransitionTypeMismatch(ty)
+            }
+            FailureRepr::SubschemaExtensionTypeMismatch(ty) => {
+                Failure::SubschemaExtensionTypeMismatch(ty)
+            }
+            FailureRepr::SubschemaOpMetaMismatch {
+                op_type,
+                expected,
+                actual,
+            } => Failure::SubschemaOpMetaMismatch {
+                op_type,
+                expected,
+                actual,
+            },
+            FailureRepr::SubschemaOpGlobalStateMismatch(op_type, ty) => {
+                Failure::SubschemaOpGlobalStateMismatch(op_type, ty)
+            }
+            FailureRepr::SubschemaOpInputMismatch(op_type, ty) => {
+                Failure::SubschemaOpInputMismatch(op_type, ty)
+            }
+            FailureRepr::SubschemaOpRedeemMismatch(op_type, ty) => {
+                Failure::SubschemaOpRedeemMismatch(op_type, ty)
+            }
+            FailureRepr::SubschemaOpAssignmentsMismatch(op_type, ty) => {
+                Failure::SubschemaOpAssignmentsMismatch(op_type, ty)
+            }
+            FailureRepr::SubschemaOpValencyMismatch(op_type, ty) => {
+                Failure::SubschemaOpValencyMismatch(op_type, ty)
+            }
+            FailureRepr::SchemaUnknownExtensionType(opid, ty) => {
+                Failure::SchemaUnknownExtensionType(opid, ty)
+            }
+            FailureRepr::SchemaUnknownTransitionType(opid, ty) => {
+                Failure::SchemaUnknownTransitionType(opid, ty)
+            }
+            FailureRepr::SchemaUnknownGlobalStateType(opid, ty) => {
+                Failure::SchemaUnknownGlobalStateType(opid, ty)
+            }
+            FailureRepr::SchemaUnknownAssignmentType(opid, ty) => {
+                Failure::SchemaUnknownAssignmentType(opid, ty)
+            }
+            FailureRepr::SchemaUnknownValencyType(opid, ty) => {
+                Failure::SchemaUnknownValencyType(opid, ty)
+            }
+            FailureRepr::SchemaGlobalStateOccurrences(opid, ty, mismatch) => {
+                Failure::SchemaGlobalStateOccurrences(opid, ty, mismatch)
+            }
+            FailureRepr::SchemaGlobalStateLimit(opid, ty, count, max) => {
+                Failure::SchemaGlobalStateLimit(opid, ty, count, max)
+            }
+            FailureRepr::SchemaInvalidMetadata(opid, sem_id) => {
+                Failure::SchemaInvalidMetadata(opid, sem_id)
+            }
+            FailureRepr::SchemaInvalidGlobalValue(opid, ty, sem_id) => {
+                Failure::SchemaInvalidGlobalValue(opid, ty, sem_id)
+            }
+            FailureRepr::SchemaInvalidOwnedValue(opid, ty, sem_id) => {
+                Failure::SchemaInvalidOwnedValue(opid, ty, sem_id)
+            }
+            FailureRepr::SchemaInputOccurrences(opid, ty, mismatch) => {
+                Failure::SchemaInputOccurrences(opid, ty, mismatch)
+            }
+            FailureRepr::SchemaAssignmentOccurrences(opid, ty, mismatch) => {
+                Failure::SchemaAssignmentOccurrences(opid, ty, mismatch)
+            }
+            FailureRepr::OperationAbsent(opid) => Failure::OperationAbsent(opid),
+            FailureRepr::TransitionAbsent(opid) => Failure::TransitionAbsent(opid),
+            FailureRepr::BundleInvalid(bundle_id) => Failure::BundleInvalid(bundle_id),
+            FailureRepr::MetadataCommitmentMismatch(opid) => {
+                Failure::MetadataCommitmentMismatch(opid)
+            }
+            FailureRepr::BundleForeignContract(bundle_id, contract_id) => {
+                Failure::BundleForeignContract(bundle_id, contract_id)
+            }
+            FailureRepr::ChannelFundingAbsent(opout) => Failure::ChannelFundingAbsent(opout),
+            FailureRepr::ChannelFundingNotSpent(opout) => Failure::ChannelFundingNotSpent(opout),
+            FailureRepr::ChannelValueMismatch(opout) => Failure::ChannelValueMismatch(opout),
+            FailureRepr::NotAnchored(opid) => Failure::NotAnchored(opid),
+            FailureRepr::NetworkMismatch(opid, expected, actual) => {
+                Failure::NetworkMismatch(opid, expected, actual)
+            }
+            FailureRepr::NotInAnchor(opid, txid) => Failure::NotInAnchor(opid, txid),
+            FailureRepr::NoPrevState {
+                opid,
+                prev_id,
+                state_type,
+            } => Failure::NoPrevState {
+                opid,
+                prev_id,
+                state_type,
+            },
+            FailureRepr::NoPrevOut(opid, opout) => Failure::NoPrevOut(opid, opout),
+            FailureRepr::LookbackExceeded(opid) => Failure::LookbackExceeded(opid),
+            FailureRepr::ConfidentialSeal(opout) => Failure::ConfidentialSeal(opout),
+            FailureRepr::MpcInvalid(opid, txid) => Failure::MpcInvalid(opid, txid),
+            FailureRepr::SealNoWitnessTx(txid) => Failure::SealNoWitnessTx(txid),
+            FailureRepr::ResolverError(txid, msg) => Failure::ResolverError(txid, msg.into_inner()),
+            FailureRepr::SealInvalid(opid, _, msg) | FailureRepr::AnchorInvalid(opid, _, msg) => {
+                return Err(DecodeError::DataIntegrityError(format!(
+                    "validation failure of operation {opid} carries error '{msg}' which can't be \
+                     decoded"
+                )));
+            }
+            FailureRepr::ValencyNoParent {
+                opid,
+                prev_id,
+                valency,
+            } => Failure::ValencyNoParent {
+                opid,
+                prev_id,
+                valency,
+            },
+            FailureRepr::NoPrevValency {
+                opid,
+                prev_id,
+                valency,
+            } => Failure::NoPrevValency {
+                opid,
+                prev_id,
+                valency,
+            },
+            FailureRepr::StateTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            } => Failure::StateTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            },
+            FailureRepr::MediaTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            } => Failure::MediaTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            },
+            FailureRepr::FungibleTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            } => Failure::FungibleTypeMismatch {
+                opid,
+                state_type,
+                expected,
+                found,
+            },
+            FailureRepr::ZeroValueAssignment(opid, ty) => Failure::ZeroValueAssignment(opid, ty),
+            FailureRepr::BulletproofsInvalid(opid, no, msg) => {
+                Failure::BulletproofsInvalid(opid, no, msg.into_inner())
+            }
+            FailureRepr::ScriptFailure(err) => Failure::ScriptFailure(ScriptError {
+                op: err.op,
+                entry_offset: err.entry_offset,
+                isa_error: err.isa_error.into_inner(),
+                exit_code: err.exit_code,
+            }),
+            FailureRepr::Custom(msg) => Failure::Custom(msg.into_inner()),
+            FailureRepr::CustomCoded { code, message } => Failure::CustomCoded {
+                code: CustomCode::try_from(code)
+                    .map_err(|err| DecodeError::DataIntegrityError(err.to_string()))?,
+                message: message.into_inner(),
+            },
+        })
+    }
+}
+
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB, tags = order, rename = "Warning")]
+enum WarningRepr {
+    TerminalSealAbsent(OpId, SecretSeal),
+    #[strict_type(dumb)]
+    ExcessiveOperation(OpId),
+    TerminalWitnessNotMined(Txid),
+    SealOnSpentUtxo(Opout, Txid),
+    UnknownGlobalStateType(OpId, schema::GlobalStateType),
+    UnknownAssignmentType(OpId, AssignmentType),
+    Custom(Message),
+}
+
+impl WarningRepr {
+    fn with(warning: &Warning) -> io::Result<Self> {
+        Ok(match warning.clone() {
+            Warning::TerminalSealAbsent(opid, seal) => WarningRepr::TerminalSealAbsent(opid, seal),
+            Warning::ExcessiveOperation(opid) => WarningRepr::ExcessiveOperation(opid),
+            Warning::TerminalWitnessNotMined(txid) => WarningRepr::TerminalWitnessNotMined(txid),
+            Warning::SealOnSpentUtxo(opout, txid) => WarningRepr::SealOnSpentUtxo(opout, txid),
+            Warning::UnknownGlobalStateType(opid, ty) => {
+                WarningRepr::UnknownGlobalStateType(opid, ty)
+            }
+            Warning::UnknownAssignmentType(opid, ty) => {
+                WarningRepr::UnknownAssignmentType(opid, ty)
+            }
+            Warning::Custom(msg) => WarningRepr::Custom(message(msg)?),
+        })
+    }
+}
+
+impl TryFrom<WarningRepr> for Warning {
+    type Error = DecodeError;
+
+    fn try_from(repr: WarningRepr) -> Result<Self, Self::Error> {
+        Ok(match repr {
+            WarningRepr::TerminalSealAbsent(opid, seal) => Warning::TerminalSealAbsent(opid, seal),
+            WarningRepr::ExcessiveOperation(opid) => Warning::ExcessiveOperation(opid),
+            WarningRepr::TerminalWitnessNotMined(txid) => Warning::TerminalWitnessNotMined(txid),
+            WarningRepr::SealOnSpentUtxo(opout, txid) => Warning::SealOnSpentUtxo(opout, txid),
+            WarningRepr::UnknownGlobalStateType(opid, ty) => {
+                Warning::UnknownGlobalStateType(opid, ty)
+            }
+            WarningRepr::UnknownAssignmentType(opid, ty) => {
+                Warning::UnknownAssignmentType(opid, ty)
+            }
+            WarningRepr::Custom(msg) => Warning::Custom(msg.into_inner()),
+        })
+    }
+}
+
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB, tags = order, rename = "Info")]
+enum InfoRepr {
+    UncheckableConfidentialState(OpId, AssignmentType),
+    #[strict_type(dumb)]
+    CheckPassed {
+        op: OpId,
+        check: CheckKind,
+    },
+    TerminalPending {
+        opid: OpId,
+        txid: Txid,
+    },
+    Custom(Message),
+}
+
+impl InfoRepr {
+    fn with(info: &Info) -> io::Result<Self> {
+        Ok(match info.clone() {
+            Info::UncheckableConfidentialState(opid, ty) => {
+                InfoRepr::UncheckableConfidentialState(opid, ty)
+            }
+            Info::CheckPassed { op, check } => InfoRepr::CheckPassed { op, check },
+            Info::TerminalPending { opid, txid } => InfoRepr::TerminalPending { opid, txid },
+            Info::Custom(msg) => InfoRepr::Custom(message(msg)?),
+        })
+    }
+}
+
+impl TryFrom<InfoRepr> for Info {
+    type Error = DecodeError;
+
+    fn try_from(repr: InfoRepr) -> Result<Self, Self::Error> {
+        Ok(match repr {
+            InfoRepr::UncheckableConfidentialState(opid, ty) => {
+                Info::UncheckableConfidentialState(opid, ty)
+            }
+            InfoRepr::CheckPassed { op, check } => Info::CheckPassed { op, check },
+            InfoRepr::TerminalPending { opid, txid } => Info::TerminalPending { opid, txid },
+            InfoRepr::Custom(msg) => Info::Custom(msg.into_inner()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn status_roundtrip() {
+        let opid = OpId::from([0xEF; 32]);
+        let txid = Txid::from([0xAB; 32]);
+        let mut status = Status::with_failure(Failure::SchemaMismatch {
+            expected: SchemaId::from([0xCD; 32]),
+            actual: SchemaId::from([0xDC; 32]),
+        });
+        status.add_failure(Failure::NoPrevOut(opid, Opout::new(opid, 1, 0)));
+        status.add_failure(Failure::ScriptFailure(ScriptError {
+            op: opid,
+            entry_offset: 4,
+            isa_error: s!("script error"),
+            exit_code: Some(1),
+        }));
+        status.add_failure(Failure::CustomCoded {
+            code: CustomCode::with(7).unwrap(),
+            message: s!("custom failure"),
+        });
+        status.add_warning(Warning::TerminalWitnessNotMined(txid));
+        status.add_warning(Warning::Custom(s!("custom warning")));
+        status.add_info(Info::CheckPassed {
+            op: opid,
+            check: CheckKind::Anchor,
+        });
+        status.add_info(Info::Custom(s!("custom info")));
+        status.unresolved_txids.push(txid);
+
+        let data = status.to_strict_serialized::<U24>().unwrap();
+        assert_eq!(Status::from_strict_serialized::<U24>(data).unwrap(), status);
+    }
+}
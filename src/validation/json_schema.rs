@@ -0,0 +1,523 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2023 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2023 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2023 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! JSON schema of the serde representation of the validation status.
+
+use serde_json::{json, Map, Value};
+
+/// Schema of a variant with data, which serde represents as an object with a
+/// single property named after the variant.
+fn variant(name: &str, payload: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": { name: payload },
+        "required": [name],
+        "additionalProperties": false
+    })
+}
+
+/// Schema of a tuple payload, represented as a fixed-length array.
+fn tuple(items: &[Value]) -> Value {
+    json!({
+        "type": "array",
+        "items": items,
+        "minItems": items.len(),
+        "maxItems": items.len()
+    })
+}
+
+/// Schema of a struct payload, represented as an object with all fields
+/// required.
+fn fields(list: &[(&str, Value)]) -> Value {
+    let properties = list
+        .iter()
+        .map(|(name, schema)| (s!(*name), schema.clone()))
+        .collect::<Map<_, _>>();
+    let required = list.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false
+    })
+}
+
+/// Schema of an enum with the given unit variants and variants with data.
+fn tagged(description: &str, units: &[&str], variants: Vec<(&str, Value)>) -> Value {
+    let mut one_of = vec![];
+    if !units.is_empty() {
+        one_of.push(json!({ "type": "string", "enum": units }));
+    }
+    one_of.extend(
+        variants
+            .into_iter()
+            .map(|(name, payload)| variant(name, payload)),
+    );
+    json!({ "description": description, "oneOf": one_of })
+}
+
+fn def(name: &str) -> Value { json!({ "$ref": format!("#/definitions/{name}") }) }
+
+/// Returns JSON schema (draft 7) describing serde representation of
+/// [`super::Status`], [`super::Failure`], [`super::Warning`],
+/// [`super::Info`] and [`super::Validity`].
+///
+/// Failures, warnings and info use serde external tagging: unit variants are
+/// represented by a string with the camel-cased variant name and variants
+/// with data by an object with a single property named after the variant.
+/// Every variant is listed together with the shape of its data; types defined
+/// outside of this library (semantic type ids, chains, concealed seals and
+/// seal or anchor verification errors) are not constrained.
+pub fn status_json_schema() -> Value {
+    let txids = json!({
+        "type": "array",
+        "items": def("txid")
+    });
+    let (id, txid, uint8, uint16, string) =
+        (def("id"), def("txid"), def("u8"), def("u16"), def("string"));
+    let (opid, op_type, opout, sem_id) =
+        (def("opId"), def("opFullType"), def("opout"), def("semId"));
+    let occurrences = def("occurrencesMismatch");
+    let any = |description: &str| json!({ "description": description });
+
+    let failure = tagged(
+        "validation failure making the consignment invalid",
+        &["schemaBlankTransitionRedefined"],
+        vec![
+            ("schemaMismatch", fields(&[("expected", id.clone()), ("actual", id.clone())])),
+            ("schemaUnknown", id.clone()),
+            ("schemaUnimplementedOpcode", uint8),
+            (
+                "unsupportedFfv",
+                fields(&[("schema", uint16.clone()), ("max_supported", uint16.clone())]),
+            ),
+            ("schemaGlobalSemIdUnknown", tuple(&[uint16.clone(), sem_id.clone()])),
+            ("schemaOwnedSemIdUnknown", tuple(&[uint16.clone(), sem_id.clone()])),
+            ("schemaOpMetaSemIdUnknown", tuple(&[op_type.clone(), sem_id.clone()])),
+            ("typeLibMismatch", sem_id.clone()),
+            ("schemaOpEmptyInputs", op_type.clone()),
+            ("schemaOpGlobalTypeUnknown", tuple(&[op_type.clone(), uint16.clone()])),
+            ("schemaOpAssignmentTypeUnknown", tuple(&[op_type.clone(), uint16.clone()])),
+            ("schemaOpValencyTypeUnknown", tuple(&[op_type.clone(), uint16.clone()])),
+            ("subschemaGlobalStateMismatch", uint16.clone()),
+            ("subschemaAssignmentTypeMismatch", uint16.clone()),
+            ("subschemaValencyTypeMismatch", uint16.clone()),
+            ("subschemaTransitionTypeMismatch", uint16.clone()),
+            ("subschemaExtensionTypeMismatch", uint16.clone()),
+            (
+                "subschemaOpMetaMismatch",
+                fields(&[
+                    ("op_type", op_type.clone()),
+                    ("expected", sem_id.clone()),
+                    ("actual", sem_id.clone()),
+                ]),
+            ),
+            ("subschemaOpGlobalStateMismatch", tuple(&[op_type.clone(), uint16.clone()])),
+            ("subschemaOpInputMismatch", tuple(&[op_type.clone(), uint16.clone()])),
+            ("subschemaOpRedeemMismatch", tuple(&[op_type.clone(), uint16.clone()])),
+            ("subschemaOpAssignmentsMismatch", tuple(&[op_type.clone(), uint16.clone()])),
+            ("subschemaOpValencyMismatch", tuple(&[op_type, uint16.clone()])),
+            ("schemaUnknownExtensionType", tuple(&[opid.clone(), uint16.clone()])),
+            ("schemaUnknownTransitionType", tuple(&[opid.clone(), uint16.clone()])),
+            ("schemaUnknownGlobalStateType", tuple(&[opid.clone(), uint16.clone()])),
+            ("schemaUnknownAssignmentType", tuple(&[opid.clone(), uint16.clone()])),
+            ("schemaUnknownValencyType", tuple(&[opid.clone(), uint16.clone()])),
+            (
+                "schemaGlobalStateOccurrences",
+                tuple(&[opid.clone(), uint16.clone(), occurrences.clone()]),
+            ),
+            (
+                "schemaGlobalStateLimit",
+                tuple(&[opid.clone(), uint16.clone(), uint16.clone(), uint16.clone()]),
+            ),
+            ("schemaInvalidMetadata", tuple(&[opid.clone(), sem_id.clone()])),
+            ("schemaInvalidGlobalValue", tuple(&[opid.clone(), uint16.clone(), sem_id.clone()])),
+            ("schemaInvalidOwnedValue", tuple(&[opid.clone(), uint16.clone(), sem_id])),
+            ("schemaInputOccurrences", tuple(&[opid.clone(), uint16.clone(), occurrences.clone()])),
+            ("schemaAssignmentOccurrences", tuple(&[opid.clone(), uint16.clone(), occurrences])),
+            ("operationAbsent", opid.clone()),
+            ("transitionAbsent", opid.clone()),
+            ("bundleInvalid", id.clone()),
+            ("metadataCommitmentMismatch", opid.clone()),
+            ("bundleForeignContract", tuple(&[id.clone(), id])),
+            ("channelFundingAbsent", opout.clone()),
+            ("channelFundingNotSpent", opout.clone()),
+            ("channelValueMismatch", opout.clone()),
+            ("notAnchored", opid.clone()),
+            ("networkMismatch", tuple(&[opid.clone(), def("chain"), def("chain")])),
+            ("notInAnchor", tuple(&[opid.clone(), txid.clone()])),
+            (
+                "noPrevState",
+                fields(&[
+                    ("opid", opid.clone()),
+                    ("prev_id", opid.clone()),
+                    ("state_type", uint16.clone()),
+                ]),
+            ),
+            ("noPrevOut", tuple(&[opid.clone(), opout.clone()])),
+            ("lookbackExceeded", opid.clone()),
+            ("confidentialSeal", opout.clone()),
+            ("mpcInvalid", tuple(&[opid.clone(), txid.clone()])),
+            ("sealNoWitnessTx", txid.clone()),
+            ("resolverError", tuple(&[txid.clone(), string.clone()])),
+            ("sealInvalid", tuple(&[opid.clone(), txid.clone(), any("seal verification error")])),
+            (
+                "anchorInvalid",
+                tuple(&[opid.clone(), txid.clone(), any("anchor verification error")]),
+            ),
+            (
+                "valencyNoParent",
+                fields(&[
+                    ("opid", opid.clone()),
+                    ("prev_id", opid.clone()),
+                    ("valency", uint16.clone()),
+                ]),
+            ),
+            (
+                "noPrevValency",
+                fields(&[
+                    ("opid", opid.clone()),
+                    ("prev_id", opid.clone()),
+                    ("valency", uint16.clone()),
+                ]),
+            ),
+            (
+                "stateTypeMismatch",
+                fields(&[
+                    ("opid", opid.clone()),
+                    ("state_type", uint16.clone()),
+                    ("expected", def("stateType")),
+                    ("found", def("stateType")),
+                ]),
+            ),
+            (
+                "mediaTypeMismatch",
+                fields(&[
+                    ("opid", opid.clone()),
+                    ("state_type", uint16.clone()),
+                    ("expected", def("mediaType")),
+                    ("found", def("mediaType")),
+                ]),
+            ),
+            (
+                "fungibleTypeMismatch",
+                fields(&[
+                    ("opid", opid.clone()),
+                    ("state_type", uint16.clone()),
+                    ("expected", def("fungibleType")),
+                    ("found", def("fungibleType")),
+                ]),
+            ),
+            ("zeroValueAssignment", tuple(&[opid.clone(), uint16.clone()])),
+            ("bulletproofsInvalid", tuple(&[opid.clone(), uint16.clone(), string.clone()])),
+            ("scriptFailure", def("scriptError")),
+            ("custom", string.clone()),
+            ("customCoded", fields(&[("code", def("customCode")), ("message", string.clone())])),
+        ],
+    );
+    let warning = tagged("validation warning", &[], vec![
+        ("terminalSealAbsent", tuple(&[opid.clone(), any("concealed seal")])),
+        ("excessiveOperation", opid.clone()),
+        ("terminalWitnessNotMined", txid.clone()),
+        ("sealOnSpentUtxo", tuple(&[opout, txid.clone()])),
+        ("unknownGlobalStateType", tuple(&[opid.clone(), uint16.clone()])),
+        ("unknownAssignmentType", tuple(&[opid.clone(), uint16.clone()])),
+        ("custom", string.clone()),
+    ]);
+    let info = tagged("additional information about the validation", &[], vec![
+        ("uncheckableConfidentialState", tuple(&[opid.clone(), uint16.clone()])),
+        ("checkPassed", fields(&[("op", opid.clone()), ("check", def("checkKind"))])),
+        ("terminalPending", fields(&[("opid", opid.clone()), ("txid", txid)])),
+        ("custom", string.clone()),
+    ]);
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Status",
+        "description": "RGB consignment validation status",
+        "type": "object",
+        "properties": {
+            "unresolvedTxids": txids,
+            "unminedTerminals": txids,
+            "failures": {
+                "type": "array",
+                "items": def("failure")
+            },
+            "warnings": {
+                "type": "array",
+                "items": def("warning")
+            },
+            "info": {
+                "type": "array",
+                "items": def("info")
+            }
+        },
+        "required": ["unresolvedTxids", "unminedTerminals", "failures", "warnings", "info"],
+        "additionalProperties": false,
+        "definitions": {
+            "id": {
+                "description": "32-byte identifier in hexadecimal encoding",
+                "type": "string",
+                "pattern": "^[0-9a-f]{64}$"
+            },
+            "txid": {
+                "description": "bitcoin transaction id in hexadecimal encoding",
+                "type": "string",
+                "pattern": "^[0-9a-f]{64}$"
+            },
+            "opId": {
+                "description": "contract operation id in hexadecimal encoding",
+                "type": "string",
+                "pattern": "^[0-9a-f]{64}$"
+            },
+            "u8": { "type": "integer", "minimum": 0, "maximum": 0xFF },
+            "u16": { "type": "integer", "minimum": 0, "maximum": 0xFFFF },
+            "string": { "type": "string" },
+            "semId": any("semantic type id"),
+            "chain": any("bitcoin chain"),
+            "opFullType": {
+                "description": "operation type",
+                "oneOf": [
+                    { "type": "string", "enum": ["genesis"] },
+                    variant("stateTransition", uint16.clone()),
+                    variant("stateExtension", uint16.clone())
+                ]
+            },
+            "opout": fields(&[("op", opid), ("ty", uint16.clone()), ("no", uint16.clone())]),
+            "occurrencesMismatch": fields(&[
+                ("min", uint16.clone()),
+                ("max", uint16.clone()),
+                ("found", uint16.clone())
+            ]),
+            "stateType": {
+                "type": "string",
+                "enum": ["void", "fungible", "structured", "attachment"]
+            },
+            "mediaType": { "type": "string", "enum": ["any"] },
+            "fungibleType": { "type": "string", "enum": ["unsigned64Bit"] },
+            "checkKind": {
+                "type": "string",
+                "enum": ["schema", "anchor", "seals", "dbc"]
+            },
+            "customCode": {
+                "type": "integer",
+                "minimum": 0,
+                "maximum": super::CustomCode::MAX
+            },
+            "scriptError": fields(&[
+                ("op", def("opId")),
+                ("entryOffset", uint16),
+                ("isaError", string),
+                ("exitCode", json!({ "oneOf": [{ "type": "null" }, def("u8")] }))
+            ]),
+            "validity": {
+                "description": "validity of the consignment",
+                "type": "string",
+                "enum": ["valid", "unminedTerminals", "unresolvedTransactions", "invalid"]
+            },
+            "failure": failure,
+            "warning": warning,
+            "info": info
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::validation::{
+        CheckKind, CustomCode, Failure, Info, ScriptError, Status, Validity, Warning,
+    };
+    use crate::{OccurrencesMismatch, OpFullType, OpId, Opout, SchemaId};
+
+    /// Checks `value` against the subset of JSON schema keywords used by
+    /// [`status_json_schema`].
+    fn conforms(root: &Value, schema: &Value, value: &Value) -> bool {
+        if let Some(path) = schema["$ref"].as_str() {
+            let name = path.trim_start_matches("#/definitions/");
+            return conforms(root, &root["definitions"][name], value);
+        }
+        if let Some(variants) = schema["oneOf"].as_array() {
+            return variants
+                .iter()
+                .filter(|variant| conforms(root, variant, value))
+                .count() ==
+                1;
+        }
+        let type_ok = match schema["type"].as_str() {
+            Some("object") => value.is_object(),
+            Some("array") => value.is_array(),
+            Some("string") => value.is_string(),
+            Some("integer") => value.is_u64() || value.is_i64(),
+            Some("null") => value.is_null(),
+            _ => true,
+        };
+        if !type_ok {
+            return false;
+        }
+        if let Some(variants) = schema["enum"].as_array() {
+            if !variants.contains(value) {
+                return false;
+            }
+        }
+        if let Some(pattern) = schema["pattern"].as_str() {
+            // The only pattern used is the one for hex-encoded 32-byte values
+            assert_eq!(pattern, "^[0-9a-f]{64}$");
+            let s = value.as_str().unwrap();
+            if s.len() != 64 || !s.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')) {
+                return false;
+            }
+        }
+        if let Some(n) = value.as_i64() {
+            if schema["minimum"].as_i64().map_or(false, |min| n < min) ||
+                schema["maximum"].as_i64().map_or(false, |max| n > max)
+            {
+                return false;
+            }
+        }
+        if let Some(items) = value.as_array() {
+            let len = items.len() as u64;
+            if schema["minItems"].as_u64().map_or(false, |min| len < min) ||
+                schema["maxItems"].as_u64().map_or(false, |max| len > max)
+            {
+                return false;
+            }
+            let item_ok = |(no, item): (usize, &Value)| match schema["items"].as_array() {
+                Some(tuple) => conforms(root, &tuple[no], item),
+                None => conforms(root, &schema["items"], item),
+            };
+            if !items.iter().enumerate().all(item_ok) {
+                return false;
+            }
+        }
+        if let Some(object) = value.as_object() {
+            let len = object.len() as u64;
+            if schema["minProperties"]
+                .as_u64()
+                .map_or(false, |min| len < min) ||
+                schema["maxProperties"]
+                    .as_u64()
+                    .map_or(false, |max| len > max)
+            {
+                return false;
+            }
+            if let Some(required) = schema["required"].as_array() {
+                if !required
+                    .iter()
+                    .all(|key| object.contains_key(key.as_str().unwrap()))
+                {
+                    return false;
+                }
+            }
+            for (key, item) in object {
+                match schema["properties"].get(key) {
+                    Some(property) if !conforms(root, property, item) => return false,
+                    None if schema["additionalProperties"] == false => return false,
+                    _ => {}
+                }
+            }
+        }
+        true
+    }
+
+    fn status() -> Status {
+        let opid = OpId::from([0xEF; 32]);
+        let txid = bp::Txid::from([0x11; 32]);
+        let mut status = Status::with_failure(Failure::SchemaMismatch {
+            expected: SchemaId::from([0xCD; 32]),
+            actual: SchemaId::from([0xAB; 32]),
+        });
+        status.add_failure(Failure::SchemaBlankTransitionRedefined);
+        status.add_failure(Failure::SchemaOpEmptyInputs(OpFullType::StateTransition(1)));
+        status.add_failure(Failure::SchemaGlobalStateOccurrences(opid, 2, OccurrencesMismatch {
+            min: 1,
+            max: 1,
+            found: 0,
+        }));
+        status.add_failure(Failure::OperationAbsent(opid));
+        status.add_failure(Failure::ChannelValueMismatch(Opout::new(opid, 3, 0)));
+        status.add_failure(Failure::NoPrevState {
+            opid,
+            prev_id: OpId::from([0xAA; 32]),
+            state_type: 4,
+        });
+        status.add_failure(Failure::ScriptFailure(ScriptError {
+            op: opid,
+            entry_offset: 0,
+            isa_error: s!("script failure"),
+            exit_code: Some(1),
+        }));
+        status.add_failure(Failure::CustomCoded {
+            code: CustomCode::with(CustomCode::MAX).unwrap(),
+            message: s!("custom"),
+        });
+        status.add_warning(Warning::TerminalWitnessNotMined(txid));
+        status.add_warning(Warning::Custom(s!("warning")));
+        status.add_info(Info::CheckPassed {
+            op: opid,
+            check: CheckKind::Seals,
+        });
+        status.add_info(Info::Custom(s!("info")));
+        status.unresolved_txids.push(txid);
+        status
+    }
+
+    #[test]
+    fn status_conforms() {
+        let schema = status_json_schema();
+        let value = serde_json::to_value(status()).unwrap();
+        assert!(conforms(&schema, &schema, &value));
+
+        let validity = serde_json::to_value(Validity::UnminedTerminals).unwrap();
+        assert!(conforms(&schema, &schema["definitions"]["validity"], &validity));
+    }
+
+    #[test]
+    fn malformed_rejected() {
+        let schema = status_json_schema();
+        let failure = &schema["definitions"]["failure"];
+        let opid = "ef".repeat(32);
+        let valid = json!({ "operationAbsent": opid });
+        assert!(conforms(&schema, failure, &valid));
+
+        for broken in [
+            json!({ "a": 1, "b": 2 }),
+            json!("unknownVariant"),
+            json!({ "unknownVariant": opid }),
+            json!("operationAbsent"),
+            json!({ "operationAbsent": 5 }),
+            json!({ "operationAbsent": "ef" }),
+            json!({ "zeroValueAssignment": [opid] }),
+            json!({ "zeroValueAssignment": [opid, 1, 2] }),
+            json!({ "zeroValueAssignment": [opid, 0x10000] }),
+            json!({ "noPrevValency": { "opid": opid, "prev_id": opid } }),
+            json!({ "noPrevValency": { "opid": opid, "prevId": opid, "valency": 1 } }),
+            json!({ "customCoded": { "code": 0x8000, "message": "" } }),
+        ] {
+            assert!(!conforms(&schema, failure, &broken), "{broken} accepted");
+        }
+
+        let mut value = serde_json::to_value(status()).unwrap();
+        value["warnings"] = json!(["schemaBlankTransitionRedefined"]);
+        assert!(!conforms(&schema, &schema, &value));
+    }
+}
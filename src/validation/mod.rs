@@ -27,9 +27,41 @@ mod state;
 mod validator;
 mod consignment;
 mod status;
+mod validity;
+mod encoding;
+mod channel;
+mod verify;
+mod conformance;
+mod streaming;
+mod resolver;
+#[cfg(feature = "serde")]
+mod json_schema;
 
-pub use consignment::{AnchoredBundle, ConsignmentApi};
+pub use channel::validate_channel_update;
+pub use conformance::{
+    precheck_consistency, validate_schema_conformance, verify_genesis_schema,
+    verify_issuance_rights,
+};
+pub use consignment::{
+    AnchoredBundle, BundleStats, ConsignmentApi, MergeConflict, MergedConsignment, OpGraph,
+};
+#[cfg(feature = "serde")]
+pub use json_schema::status_json_schema;
 pub(crate) use model::OpInfo;
+pub use model::{validate_non_zero_values, UnknownTypePolicy};
+pub use resolver::CachingResolver;
+pub use schema::SchemaSubsetView;
 pub use script::VirtualMachine;
-pub use status::{Failure, Info, Status, Validity, Warning};
-pub use validator::{ResolveTx, TxResolverError, Validator};
+#[cfg(any(test, feature = "test-helpers"))]
+pub use status::StatusBuilder;
+pub use status::{
+    AnchorErrorKind, CheckKind, CustomCode, CustomCodeOverflow, Failure, Info, LabeledStatus,
+    Severity, Status, StatusSummary, Warning,
+};
+pub use streaming::{LookbackValidator, StateCache, StreamingValidator};
+pub use validator::{
+    CountingObserver, ResolveTx, SchemaResolver, TxResolverError, ValidationCache,
+    ValidationObserver, ValidationOptions, Validator,
+};
+pub use validity::{StatusCore, Validity};
+pub use verify::{verify_anchors_batched, CommitmentVerifier, DefaultVerifier};
@@ -26,20 +26,64 @@ use amplify::confinement::{Confined, SmallBlob};
 use amplify::Wrapper;
 use strict_types::SemId;
 
-use crate::schema::{AssignmentsSchema, GlobalSchema, ValencySchema};
-use crate::validation::{ConsignmentApi, VirtualMachine};
+use crate::schema::{AssignmentsSchema, GlobalSchema, ValencySchema, ValencyType};
+use crate::validation::{CommitmentVerifier, ConsignmentApi, DefaultVerifier, VirtualMachine};
 use crate::{
-    validation, Assignments, AssignmentsRef, ExposedSeal, GlobalState, GlobalStateSchema,
-    GlobalValues, GraphSeal, Inputs, OpFullType, OpId, OpRef, Operation, Opout, Redeemed, Schema,
-    SchemaRoot, TypedAssigns, Valencies, BLANK_TRANSITION_ID,
+    validation, Assign, AssignmentType, Assignments, AssignmentsRef, ExposedSeal, Extension,
+    Genesis, GlobalState, GlobalStateSchema, GlobalValues, GraphSeal, Inputs, OpFullType, OpId,
+    OpRef, Operation, Opout, Redeemed, Schema, SchemaRoot, TypedAssigns, Valencies,
+    BLANK_TRANSITION_ID,
 };
 
+/// Policy for handling global and owned state of types not defined by the
+/// schema.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum UnknownTypePolicy {
+    /// State of unknown types is a validation failure.
+    #[default]
+    Fail,
+
+    /// State of unknown types is ignored, producing a warning. Intended for
+    /// forward-compatible deployments accepting contracts which use
+    /// optional state from the future schema versions.
+    Warn,
+}
+
+/// Checks that `op` doesn't assign zero fungible value to the state of any
+/// of the `non_zero` types, preventing dust outputs.
+///
+/// Only revealed values are checked, since concealed values are validated by
+/// their bulletproofs, which allow zero.
+pub fn validate_non_zero_values(
+    op: OpRef,
+    non_zero: &BTreeSet<AssignmentType>,
+) -> validation::Status {
+    let mut status = validation::Status::new();
+    let assignments = op.assignments();
+    for ty in non_zero {
+        let Some(assigns) = assignments.get(*ty) else {
+            continue;
+        };
+        if assigns
+            .as_fungible()
+            .iter()
+            .filter_map(Assign::as_revealed_state)
+            .any(|state| state.value.as_u64() == 0)
+        {
+            status.add_failure(validation::Failure::ZeroValueAssignment(op.id(), *ty));
+        }
+    }
+    status
+}
+
 impl<Root: SchemaRoot> Schema<Root> {
     pub fn validate<C: ConsignmentApi>(
         &self,
         consignment: &C,
         op: OpRef,
         vm: &dyn VirtualMachine,
+        verifier: &dyn CommitmentVerifier,
+        policy: UnknownTypePolicy,
     ) -> validation::Status {
         let id = op.id();
 
@@ -138,7 +182,7 @@ impl<Root: SchemaRoot> Schema<Root> {
         // Validate type system
         status += self.validate_type_system();
         status += self.validate_metadata(id, *metadata_schema, op.metadata());
-        status += self.validate_global_state(id, op.globals(), global_schema);
+        status += self.validate_global_state(id, op.globals(), global_schema, policy);
         let prev_state = if let OpRef::Transition(transition) = op {
             let prev_state = extract_prev_state(consignment, id, &transition.inputs, &mut status);
             status += self.validate_prev_state(id, &prev_state, owned_schema);
@@ -156,16 +200,17 @@ impl<Root: SchemaRoot> Schema<Root> {
         };
         status += match op.assignments() {
             AssignmentsRef::Genesis(assignments) => {
-                self.validate_owned_state(id, assignments, assign_schema)
+                self.validate_owned_state(id, assignments, assign_schema, verifier, policy)
             }
             AssignmentsRef::Graph(assignments) => {
-                self.validate_owned_state(id, assignments, assign_schema)
+                self.validate_owned_state(id, assignments, assign_schema, verifier, policy)
             }
         };
 
         status += self.validate_valencies(id, op.valencies(), valency_schema);
 
-        let op_info = OpInfo::with(id, self.subset_of.is_some(), &op, &prev_state, &redeemed);
+        let op_info =
+            OpInfo::with(id, self.subset_of.is_some(), &op, &prev_state, &redeemed, verifier);
 
         // We need to run scripts as the very last step, since before that
         // we need to make sure that the operation data match the schema, so
@@ -210,6 +255,7 @@ impl<Root: SchemaRoot> Schema<Root> {
         opid: OpId,
         global: &GlobalState,
         global_schema: &GlobalSchema,
+        policy: UnknownTypePolicy,
     ) -> validation::Status {
         let mut status = validation::Status::new();
 
@@ -217,10 +263,16 @@ impl<Root: SchemaRoot> Schema<Root> {
             .keys()
             .collect::<BTreeSet<_>>()
             .difference(&global_schema.keys().collect())
-            .for_each(|field_id| {
-                status.add_failure(validation::Failure::SchemaUnknownGlobalStateType(
-                    opid, **field_id,
-                ));
+            .for_each(|field_id| match policy {
+                UnknownTypePolicy::Fail => {
+                    status.add_failure(validation::Failure::SchemaUnknownGlobalStateType(
+                        opid, **field_id,
+                    ));
+                }
+                UnknownTypePolicy::Warn => {
+                    status
+                        .add_warning(validation::Warning::UnknownGlobalStateType(opid, **field_id));
+                }
             });
 
         for (type_id, occ) in global_schema {
@@ -329,6 +381,8 @@ impl<Root: SchemaRoot> Schema<Root> {
         id: OpId,
         owned_state: &Assignments<Seal>,
         assign_schema: &AssignmentsSchema,
+        verifier: &dyn CommitmentVerifier,
+        policy: UnknownTypePolicy,
     ) -> validation::Status {
         let mut status = validation::Status::new();
 
@@ -336,11 +390,19 @@ impl<Root: SchemaRoot> Schema<Root> {
             .keys()
             .collect::<BTreeSet<_>>()
             .difference(&assign_schema.keys().collect())
-            .for_each(|assignment_type_id| {
-                status.add_failure(validation::Failure::SchemaUnknownAssignmentType(
-                    id,
-                    **assignment_type_id,
-                ));
+            .for_each(|assignment_type_id| match policy {
+                UnknownTypePolicy::Fail => {
+                    status.add_failure(validation::Failure::SchemaUnknownAssignmentType(
+                        id,
+                        **assignment_type_id,
+                    ));
+                }
+                UnknownTypePolicy::Warn => {
+                    status.add_warning(validation::Warning::UnknownAssignmentType(
+                        id,
+                        **assignment_type_id,
+                    ));
+                }
             });
 
         for (state_id, occ) in assign_schema {
@@ -364,16 +426,16 @@ impl<Root: SchemaRoot> Schema<Root> {
             match owned_state.get(state_id) {
                 None => {}
                 Some(TypedAssigns::Declarative(set)) => set.iter().for_each(|data| {
-                    status += assignment.validate(&self.type_system, &id, *state_id, data)
+                    status += assignment.validate(&self.type_system, &id, *state_id, data, verifier)
                 }),
                 Some(TypedAssigns::Fungible(set)) => set.iter().for_each(|data| {
-                    status += assignment.validate(&self.type_system, &id, *state_id, data)
+                    status += assignment.validate(&self.type_system, &id, *state_id, data, verifier)
                 }),
                 Some(TypedAssigns::Structured(set)) => set.iter().for_each(|data| {
-                    status += assignment.validate(&self.type_system, &id, *state_id, data)
+                    status += assignment.validate(&self.type_system, &id, *state_id, data, verifier)
                 }),
                 Some(TypedAssigns::Attachment(set)) => set.iter().for_each(|data| {
-                    status += assignment.validate(&self.type_system, &id, *state_id, data)
+                    status += assignment.validate(&self.type_system, &id, *state_id, data, verifier)
                 }),
             };
         }
@@ -419,6 +481,87 @@ impl<Root: SchemaRoot> Schema<Root> {
     }
 }
 
+impl Genesis {
+    /// Validates genesis against the rules of the `schema` genesis: its
+    /// metadata, global state, owned state and valencies types, occurrences
+    /// and values.
+    ///
+    /// Unlike the full validation with [`Schema::validate`] doesn't run the
+    /// schema scripts, allowing issuers to check the genesis before
+    /// publishing the contract.
+    pub fn validate_against<Root: SchemaRoot>(&self, schema: &Schema<Root>) -> validation::Status {
+        let id = self.id();
+        let genesis_schema = &schema.genesis;
+        let mut status = validation::Status::new();
+        status += schema.validate_metadata(id, genesis_schema.metadata, &self.metadata);
+        status += schema.validate_global_state(
+            id,
+            &self.globals,
+            &genesis_schema.globals,
+            UnknownTypePolicy::Fail,
+        );
+        status += schema.validate_owned_state(
+            id,
+            &self.assignments,
+            &genesis_schema.assignments,
+            &DefaultVerifier,
+            UnknownTypePolicy::Fail,
+        );
+        status += schema.validate_valencies(id, &self.valencies, &genesis_schema.valencies);
+        status
+    }
+}
+
+impl Extension {
+    /// Verifies issuance rights of the extension without access to the
+    /// consignment: each redeemed valency must be defined by the `schema`
+    /// for the extension type and must be present in `parent_valencies`,
+    /// listing valencies of the parent operations as pairs of the parent
+    /// operation id and valency type.
+    ///
+    /// Parents absent in `parent_valencies` produce
+    /// [`validation::Failure::ValencyNoParent`]; valencies absent in the known
+    /// parents produce [`validation::Failure::NoPrevValency`].
+    pub fn validate_against<Root: SchemaRoot>(
+        &self,
+        schema: &Schema<Root>,
+        parent_valencies: &BTreeSet<(OpId, ValencyType)>,
+    ) -> validation::Status {
+        let opid = self.id();
+        let Some(extension_schema) = schema.extensions.get(&self.extension_type) else {
+            return validation::Status::with_failure(
+                validation::Failure::SchemaUnknownExtensionType(opid, self.extension_type),
+            );
+        };
+
+        let mut status = validation::Status::new();
+        for (valency, prev_id) in &self.redeemed {
+            if !extension_schema.redeems.contains(valency) {
+                status.add_failure(validation::Failure::SchemaUnknownValencyType(opid, *valency));
+            }
+            if parent_valencies.contains(&(*prev_id, *valency)) {
+                continue;
+            }
+            let prev_id = *prev_id;
+            let valency = *valency;
+            if parent_valencies.iter().any(|(id, _)| *id == prev_id) {
+                status.add_failure(validation::Failure::NoPrevValency {
+                    opid,
+                    prev_id,
+                    valency,
+                });
+            } else {
+                status.add_failure(validation::Failure::ValencyNoParent {
+                    opid,
+                    prev_id,
+                    valency,
+                });
+            }
+        }
+        status
+    }
+}
+
 pub struct OpInfo<'op> {
     pub subschema: bool,
     pub id: OpId,
@@ -429,6 +572,7 @@ pub struct OpInfo<'op> {
     pub redeemed: &'op Valencies,
     pub valencies: &'op Valencies,
     pub global: &'op GlobalState,
+    pub verifier: &'op dyn CommitmentVerifier,
 }
 
 impl<'op> OpInfo<'op> {
@@ -438,6 +582,7 @@ impl<'op> OpInfo<'op> {
         op: &'op OpRef<'op>,
         prev_state: &'op Assignments<GraphSeal>,
         redeemed: &'op Valencies,
+        verifier: &'op dyn CommitmentVerifier,
     ) -> Self {
         OpInfo {
             id,
@@ -449,6 +594,7 @@ impl<'op> OpInfo<'op> {
             redeemed,
             valencies: op.valencies(),
             global: op.globals(),
+            verifier,
         }
     }
 }
@@ -552,3 +698,141 @@ fn extract_redeemed_valencies<C: ConsignmentApi>(
     }
     public_rights
 }
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::Confined;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::schema::Occurrences;
+    use crate::validation::Failure;
+    use crate::{
+        AssignFungible, AssignRights, BlindingFactor, ExtensionSchema, GenesisSchema, GenesisSeal,
+        OccurrencesMismatch, RevealedValue, StateSchema, SubSchema, Transition, VoidState,
+    };
+
+    #[test]
+    fn zero_value_assignment() {
+        let blinding = BlindingFactor::try_from([1u8; 32]).unwrap();
+        let assigns = [0u64, 10]
+            .into_iter()
+            .map(|value| {
+                AssignFungible::revealed(
+                    GraphSeal::strict_dumb(),
+                    RevealedValue::with(value, blinding),
+                )
+            })
+            .collect::<Vec<_>>();
+        let transition = Transition {
+            assignments: Confined::try_from(bmap! {
+                1 => TypedAssigns::Fungible(Confined::try_from(assigns).unwrap()),
+            })
+            .unwrap()
+            .into(),
+            ..Transition::strict_dumb()
+        };
+        let op = OpRef::Transition(&transition);
+
+        assert!(validate_non_zero_values(op, &none!()).failures.is_empty());
+        assert_eq!(validate_non_zero_values(op, &bset! { 1 }).failures, vec![
+            validation::Failure::ZeroValueAssignment(transition.id(), 1)
+        ]);
+    }
+
+    #[test]
+    fn genesis_against_schema() {
+        let schema = SubSchema {
+            global_types: tiny_bmap! { 1 => GlobalStateSchema::once(default!()) },
+            owned_types: tiny_bmap! { 2 => StateSchema::Declarative },
+            genesis: GenesisSchema {
+                globals: tiny_bmap! { 1 => Occurrences::NoneOrOnce },
+                assignments: tiny_bmap! { 2 => Occurrences::NoneOrMore },
+                ..default!()
+            },
+            ..default!()
+        };
+        let is_structural = |f: &Failure| {
+            matches!(
+                f,
+                Failure::SchemaGlobalStateOccurrences(..) |
+                    Failure::SchemaUnknownGlobalStateType(..) |
+                    Failure::SchemaAssignmentOccurrences(..) |
+                    Failure::SchemaUnknownAssignmentType(..) |
+                    Failure::SchemaUnknownValencyType(..)
+            )
+        };
+
+        let genesis = Genesis::strict_dumb();
+        let status = genesis.validate_against(&schema);
+        assert!(!status.failures.iter().any(is_structural));
+
+        let rights = AssignRights::revealed(GenesisSeal::strict_dumb(), VoidState::default());
+        let genesis = Genesis {
+            assignments: Confined::try_from(bmap! {
+                3 => TypedAssigns::Declarative(Confined::try_from(vec![rights]).unwrap()),
+            })
+            .unwrap()
+            .into(),
+            ..Genesis::strict_dumb()
+        };
+        let mut schema = schema;
+        schema.genesis.globals = tiny_bmap! { 1 => Occurrences::Once };
+        let status = genesis.validate_against(&schema);
+        let failures = status
+            .failures
+            .into_iter()
+            .filter(is_structural)
+            .collect::<Vec<_>>();
+        assert_eq!(failures, vec![
+            Failure::SchemaGlobalStateOccurrences(genesis.id(), 1, OccurrencesMismatch {
+                min: 1,
+                max: 1,
+                found: 0,
+            }),
+            Failure::SchemaUnknownAssignmentType(genesis.id(), 3),
+        ]);
+    }
+
+    #[test]
+    fn extension_against_parents() {
+        let schema = SubSchema {
+            valency_types: tiny_bset! { 1, 2 },
+            extensions: tiny_bmap! {
+                0 => ExtensionSchema {
+                    redeems: tiny_bset! { 1, 2 },
+                    ..default!()
+                },
+            },
+            ..default!()
+        };
+        let parent = OpId::from([0x01; 32]);
+        let unknown = OpId::from([0x02; 32]);
+        let extension = Extension {
+            redeemed: Redeemed::from(
+                Confined::try_from(bmap! { 1 => parent, 2 => unknown }).unwrap(),
+            ),
+            ..Extension::strict_dumb()
+        };
+
+        let parents = bset! { (parent, 1), (unknown, 2) };
+        assert!(extension
+            .validate_against(&schema, &parents)
+            .failures
+            .is_empty());
+
+        let parents = bset! { (parent, 2) };
+        assert_eq!(extension.validate_against(&schema, &parents).failures, vec![
+            Failure::NoPrevValency {
+                opid: extension.id(),
+                prev_id: parent,
+                valency: 1,
+            },
+            Failure::ValencyNoParent {
+                opid: extension.id(),
+                prev_id: unknown,
+                valency: 2,
+            },
+        ]);
+    }
+}
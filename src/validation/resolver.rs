@@ -0,0 +1,191 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2023 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2023 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2023 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decorators for the transaction resolvers.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use bp::{Chain, Outpoint, Tx, Txid};
+
+use super::{ResolveTx, TxResolverError};
+
+#[derive(Debug, Default)]
+struct TxCache {
+    txs: BTreeMap<Txid, Tx>,
+    /// Cached transaction ids, from the least to the most recently used.
+    order: VecDeque<Txid>,
+}
+
+/// Resolver memoizing transactions successfully retrieved by the wrapped
+/// resolver, such that each witness transaction shared by multiple
+/// operations is requested from the backend only once.
+///
+/// Failed resolutions and [`ResolveTx::is_spent`] requests are not cached.
+#[derive(Debug)]
+pub struct CachingResolver<R: ResolveTx> {
+    resolver: R,
+    capacity: usize,
+    lru: bool,
+    cache: Mutex<TxCache>,
+}
+
+impl<R: ResolveTx> CachingResolver<R> {
+    /// Constructs resolver caching all the transactions without a limit.
+    pub fn new(resolver: R) -> Self {
+        CachingResolver {
+            resolver,
+            capacity: usize::MAX,
+            lru: false,
+            cache: default!(),
+        }
+    }
+
+    /// Constructs resolver caching at most `capacity` transactions. Once the
+    /// cache is full, new transactions are not cached anymore.
+    pub fn with_capacity(resolver: R, capacity: usize) -> Self {
+        CachingResolver {
+            capacity,
+            ..Self::new(resolver)
+        }
+    }
+
+    /// Constructs resolver caching at most `capacity` transactions. Once the
+    /// cache is full, the least recently used transaction gets evicted from
+    /// it.
+    pub fn with_lru(resolver: R, capacity: usize) -> Self {
+        CachingResolver {
+            capacity,
+            lru: true,
+            ..Self::new(resolver)
+        }
+    }
+
+    /// Returns number of the transactions in the cache.
+    pub fn cached_count(&self) -> usize { self.cache().txs.len() }
+
+    /// Releases the wrapped resolver, dropping the cache.
+    pub fn into_inner(self) -> R { self.resolver }
+
+    fn cache(&self) -> MutexGuard<'_, TxCache> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<R: ResolveTx> ResolveTx for CachingResolver<R> {
+    fn resolve_tx(&self, txid: Txid) -> Result<Tx, TxResolverError> {
+        {
+            let mut cache = self.cache();
+            if let Some(tx) = cache.txs.get(&txid).cloned() {
+                if self.lru {
+                    cache.order.retain(|id| *id != txid);
+                    cache.order.push_back(txid);
+                }
+                return Ok(tx);
+            }
+        }
+
+        let tx = self.resolver.resolve_tx(txid)?;
+
+        let mut cache = self.cache();
+        if cache.txs.len() >= self.capacity {
+            if !self.lru {
+                return Ok(tx);
+            }
+            if let Some(evicted) = cache.order.pop_front() {
+                cache.txs.remove(&evicted);
+            }
+        }
+        if self.capacity > 0 && cache.txs.insert(txid, tx.clone()).is_none() {
+            cache.order.push_back(txid);
+        }
+        Ok(tx)
+    }
+
+    fn is_spent(&self, outpoint: Outpoint) -> Result<bool, TxResolverError> {
+        self.resolver.is_spent(outpoint)
+    }
+
+    fn chain(&self) -> Option<Chain> { self.resolver.chain() }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct CountingResolver(AtomicUsize);
+
+    impl ResolveTx for CountingResolver {
+        fn resolve_tx(&self, _: Txid) -> Result<Tx, TxResolverError> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(Tx::strict_dumb())
+        }
+    }
+
+    impl CountingResolver {
+        fn count(&self) -> usize { self.0.load(Ordering::Relaxed) }
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn caching() {
+        assert_send_sync::<CachingResolver<CountingResolver>>();
+
+        let a = Txid::from([0xAA; 32]);
+        let b = Txid::from([0xBB; 32]);
+
+        let resolver = CachingResolver::new(CountingResolver::default());
+        resolver.resolve_tx(a).unwrap();
+        resolver.resolve_tx(a).unwrap();
+        resolver.resolve_tx(b).unwrap();
+        assert_eq!(resolver.cached_count(), 2);
+        assert_eq!(resolver.into_inner().count(), 2);
+
+        let resolver = CachingResolver::with_capacity(CountingResolver::default(), 1);
+        for txid in [a, b, a, b] {
+            resolver.resolve_tx(txid).unwrap();
+        }
+        assert_eq!(resolver.cached_count(), 1);
+        assert_eq!(resolver.into_inner().count(), 3);
+    }
+
+    #[test]
+    fn lru_eviction() {
+        let a = Txid::from([0xAA; 32]);
+        let b = Txid::from([0xBB; 32]);
+        let c = Txid::from([0xCC; 32]);
+
+        let resolver = CachingResolver::with_lru(CountingResolver::default(), 2);
+        // `b` is evicted by `c` since `a` was used more recently
+        for txid in [a, b, a, c, a, b] {
+            resolver.resolve_tx(txid).unwrap();
+        }
+        assert_eq!(resolver.cached_count(), 2);
+        assert_eq!(resolver.into_inner().count(), 4);
+    }
+}
@@ -20,25 +20,222 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::validation::Status;
+use std::collections::BTreeSet;
+use std::iter;
+
+use amplify::confinement::Confined;
+use strict_types::{SystemBuilder, TypeLib};
+
+use crate::validation::{ConsignmentApi, Status};
+use crate::vm::validate_isae;
 use crate::{
-    validation, OpFullType, OpSchema, Schema, StateSchema, SubSchema, BLANK_TRANSITION_ID,
+    validation, Ffv, OpFullType, OpSchema, Schema, SchemaId, SchemaRoot, Script, StateSchema,
+    SubSchema, BLANK_TRANSITION_ID,
 };
 
+/// Schema reduced to the operation types used by a specific consignment,
+/// produced by [`Schema::minimal_for`].
+///
+/// Reduced schema has a different id than the original one, so it can't be
+/// used in place of the original schema for the consignment validation;
+/// instead it may be used for validating individual operations with
+/// [`Schema::validate`].
+#[derive(Clone, Debug)]
+pub struct SchemaSubsetView<Root: SchemaRoot> {
+    /// Id of the schema from which the view was derived.
+    pub origin: SchemaId,
+    /// The reduced schema.
+    pub schema: Schema<Root>,
+}
+
+impl<Root: SchemaRoot> Schema<Root> {
+    /// Derives schema containing only the state transition and extension
+    /// types used by the consignment operations. State types and the rest of
+    /// the schema data are kept unchanged.
+    ///
+    /// Operations used are all revealed transitions from the anchored bundles
+    /// and all extensions contained in the consignment, including the ones
+    /// not spent by any transition.
+    pub fn minimal_for<C: ConsignmentApi>(&self, consignment: &C) -> SchemaSubsetView<Root> {
+        let transition_types = consignment
+            .anchored_bundles()
+            .flat_map(|ab| ab.bundle.values())
+            .filter_map(|item| item.transition.as_ref())
+            .map(|transition| transition.transition_type)
+            .collect::<BTreeSet<_>>();
+        let extension_types = consignment
+            .op_ids_except(&none!())
+            .into_iter()
+            .filter_map(|opid| consignment.extension(opid))
+            .map(|extension| extension.extension_type)
+            .collect::<BTreeSet<_>>();
+
+        let mut schema = self.clone();
+        schema.transitions = Confined::try_from_iter(
+            self.transitions
+                .iter()
+                .filter(|(ty, _)| transition_types.contains(ty))
+                .map(|(ty, op_schema)| (*ty, op_schema.clone())),
+        )
+        .expect("subset of a confined collection");
+        schema.extensions = Confined::try_from_iter(
+            self.extensions
+                .iter()
+                .filter(|(ty, _)| extension_types.contains(ty))
+                .map(|(ty, op_schema)| (*ty, op_schema.clone())),
+        )
+        .expect("subset of a confined collection");
+
+        SchemaSubsetView {
+            origin: self.schema_id(),
+            schema,
+        }
+    }
+
+    /// Checks that all types from the schema type system are resolvable
+    /// against the provided type libraries, i.e. that the schema was compiled
+    /// with compatible versions of the libraries it imports types from.
+    ///
+    /// Each unresolved type results in
+    /// [`validation::Failure::TypeLibMismatch`].
+    pub fn verify_type_imports(&self, available: &[TypeLib]) -> validation::Status {
+        let mut status = validation::Status::new();
+
+        let sys = available
+            .iter()
+            .cloned()
+            .try_fold(SystemBuilder::new(), |builder, lib| builder.import(lib))
+            .ok()
+            .and_then(|builder| builder.finalize().ok());
+
+        for sem_id in self.type_system.keys() {
+            let resolved = sys
+                .as_ref()
+                .map(|sys| sys.as_types().contains_key(sem_id))
+                .unwrap_or_default();
+            if !resolved {
+                status.add_failure(validation::Failure::TypeLibMismatch(*sem_id));
+            }
+        }
+
+        status
+    }
+
+    /// Checks that the schema fast-forward version does not exceed the
+    /// `supported` one, allowing to reject schemata from the future RGB
+    /// versions instead of misinterpreting them.
+    pub fn check_ffv(&self, supported: Ffv) -> Result<(), validation::Failure> {
+        if self.ffv > supported {
+            return Err(validation::Failure::UnsupportedFfv {
+                schema: self.ffv,
+                max_supported: supported,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that occurrences of the global state, inputs and assignments of
+    /// all schema operations do not have their minimum exceeding the
+    /// maximum. Each inverted bound results in [`validation::Failure::Custom`]
+    /// describing it.
+    pub fn check_occurrences(&self) -> validation::Status {
+        let mut status = validation::Status::new();
+
+        let ops = iter::once((OpFullType::Genesis, &self.genesis as &dyn OpSchema))
+            .chain(
+                self.transitions
+                    .iter()
+                    .map(|(ty, op)| (OpFullType::StateTransition(*ty), op as &dyn OpSchema)),
+            )
+            .chain(
+                self.extensions
+                    .iter()
+                    .map(|(ty, op)| (OpFullType::StateExtension(*ty), op as &dyn OpSchema)),
+            );
+        for (op_type, op) in ops {
+            let occurrences = op
+                .globals()
+                .iter()
+                .map(|(ty, occ)| ("global state", ty, occ))
+                .chain(
+                    op.inputs()
+                        .into_iter()
+                        .flatten()
+                        .map(|(ty, occ)| ("input", ty, occ)),
+                )
+                .chain(
+                    op.assignments()
+                        .iter()
+                        .map(|(ty, occ)| ("assignment", ty, occ)),
+                );
+            for (kind, ty, occ) in occurrences {
+                let (min, max) = (occ.min_value(), occ.max_value());
+                if min > max {
+                    status.add_failure(validation::Failure::Custom(format!(
+                        "{kind} #{ty} of {op_type} expects from {min} to {max} elements, while \
+                         the minimum exceeds the maximum"
+                    )));
+                }
+            }
+        }
+
+        status
+    }
+}
+
 impl SubSchema {
     pub fn verify(&self) -> validation::Status {
         let mut status = validation::Status::new();
 
-        if let Some(ref root) = self.subset_of {
-            status += self.verify_subschema(root);
+        // Inheritance chains can't be cyclic: `RootSchema` can't have a root of its
+        // own, so the chain is at most one level deep, and a schema can't be
+        // its own root since its id commits to the root schema.
+        if self.subset_of.is_some() {
+            status += self.verify_against_root();
         }
 
         // Validate internal schema consistency
         status += self.verify_consistency();
 
+        // Detect scripts using opcodes reserved for the future versions of the VM
+        match self.unimplemented_opcodes() {
+            Ok(opcodes) => {
+                for opcode in opcodes {
+                    status.add_failure(validation::Failure::SchemaUnimplementedOpcode(opcode));
+                }
+            }
+            Err(err) => {
+                status.add_failure(validation::Failure::Custom(format!("schema script: {err}")))
+            }
+        }
+
+        // Only RGB instruction set extension is allowed for now
+        let Script::AluVM(ref script) = self.script;
+        for lib in script.libs.values() {
+            if let Err(err) = validate_isae(lib.code.as_ref()) {
+                status.add_failure(validation::Failure::Custom(format!(
+                    "schema script library {}: {err}",
+                    lib.id()
+                )));
+            }
+        }
+
         status
     }
 
+    /// Verifies that the subschema conforms to its root schema: all global,
+    /// owned, valency, transition and extension types used by the subschema
+    /// must be present in the root schema with the same definitions.
+    ///
+    /// Returns empty status for schemata which are not a subset of other
+    /// schema.
+    pub fn verify_against_root(&self) -> validation::Status {
+        match self.subset_of {
+            Some(ref root) => self.verify_subschema(root),
+            None => validation::Status::new(),
+        }
+    }
+
     fn verify_consistency(&self) -> validation::Status {
         let mut status = validation::Status::new();
 
@@ -263,3 +460,336 @@ where T: OpSchema
         status
     }
 }
+
+#[cfg(test)]
+mod test {
+    use aluvm::isa::Instr;
+    use aluvm::library::Lib;
+    use strict_encoding::StrictDumb;
+    use strict_types::stl::std_stl;
+
+    use super::*;
+    use crate::validation::consignment::test::Mock;
+    use crate::validation::{AnchoredBundle, DefaultVerifier, UnknownTypePolicy};
+    use crate::vm::opcodes::{INSTR_PCCS, INSTR_PCVS};
+    use crate::vm::{AluScript, ContractOp, RgbIsa};
+    use crate::{
+        BundleItem, Extension, ExtensionSchema, Genesis, GenesisSchema, GenesisSeal, Occurrences,
+        OpId, OpRef, Redeemed, RevealedData, RootSchema, Script, Transition, TransitionBundle,
+        TransitionSchema, TypedAssigns,
+    };
+
+    #[test]
+    fn unimplemented_opcodes() {
+        let code = [
+            Instr::ExtensionCodes(RgbIsa::Contract(ContractOp::PcVs(0))),
+            Instr::ExtensionCodes(RgbIsa::Contract(ContractOp::Fail(INSTR_PCCS))),
+        ];
+        let lib = Lib::assemble(&code).unwrap();
+        let schema = SubSchema {
+            script: Script::AluVM(AluScript {
+                libs: Confined::try_from(bmap! { lib.id() => lib }).unwrap(),
+                entry_points: none!(),
+            }),
+            ..default!()
+        };
+        assert_eq!(schema.required_opcodes(), Ok(bset! { INSTR_PCCS, INSTR_PCVS }));
+        assert_eq!(schema.unimplemented_opcodes(), Ok(bset! { INSTR_PCCS }));
+        assert!(schema
+            .verify()
+            .failures
+            .contains(&validation::Failure::SchemaUnimplementedOpcode(INSTR_PCCS)));
+    }
+
+    #[test]
+    fn prohibited_isae() {
+        let schema = |code: &[Instr<RgbIsa>]| {
+            let lib = Lib::assemble(code).unwrap();
+            SubSchema {
+                script: Script::AluVM(AluScript {
+                    libs: Confined::try_from(bmap! { lib.id() => lib }).unwrap(),
+                    entry_points: none!(),
+                }),
+                ..default!()
+            }
+        };
+        let is_isae_failure = |f: &validation::Failure| matches!(f, validation::Failure::Custom(msg) if msg.contains("ISA extension"));
+
+        let permitted = schema(&[Instr::ExtensionCodes(RgbIsa::Contract(ContractOp::PcVs(0)))]);
+        assert!(!permitted.verify().failures.iter().any(is_isae_failure));
+
+        let prohibited = schema(&[Instr::ExtensionCodes(RgbIsa::Fail(0b10_000_000))]);
+        assert!(prohibited.verify().failures.iter().any(is_isae_failure));
+    }
+
+    #[test]
+    fn minimal_schema() {
+        let schema = SubSchema {
+            transitions: tiny_bmap! {
+                0 => TransitionSchema::default(),
+                1 => TransitionSchema::default(),
+            },
+            ..default!()
+        };
+        let transition = Transition::strict_dumb();
+        let item = BundleItem {
+            inputs: none!(),
+            transition: Some(transition.clone()),
+        };
+        let consignment = Mock {
+            schema: schema.clone(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![AnchoredBundle {
+                anchor: strict_dumb!(),
+                bundle: TransitionBundle::from(tiny_bmap! { transition.id() => item }),
+            }],
+            extensions: vec![],
+        };
+
+        let view = schema.minimal_for(&consignment);
+        assert_eq!(view.origin, schema.schema_id());
+        assert_eq!(view.schema.transitions.keys().copied().collect::<Vec<_>>(), vec![
+            transition.transition_type
+        ]);
+
+        let op = OpRef::Transition(&transition);
+        let full = schema.validate(
+            &consignment,
+            op,
+            &schema.script,
+            &DefaultVerifier,
+            UnknownTypePolicy::Fail,
+        );
+        let minimal = view.schema.validate(
+            &consignment,
+            op,
+            &view.schema.script,
+            &DefaultVerifier,
+            UnknownTypePolicy::Fail,
+        );
+        assert_eq!(full.failures, minimal.failures);
+    }
+
+    #[test]
+    fn minimal_schema_unspent_extension() {
+        let schema = SubSchema {
+            extensions: tiny_bmap! {
+                0 => ExtensionSchema::default(),
+                1 => ExtensionSchema::default(),
+            },
+            ..default!()
+        };
+        // Extension which is not spent by any of the transitions
+        let extension = Extension {
+            extension_type: 1,
+            ..Extension::strict_dumb()
+        };
+        let consignment = Mock {
+            schema: schema.clone(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![],
+            extensions: vec![extension.clone()],
+        };
+
+        let view = schema.minimal_for(&consignment);
+        assert_eq!(view.schema.extensions.keys().copied().collect::<Vec<_>>(), vec![1]);
+
+        let op = OpRef::Extension(&extension);
+        let full = schema.validate(
+            &consignment,
+            op,
+            &schema.script,
+            &DefaultVerifier,
+            UnknownTypePolicy::Fail,
+        );
+        let minimal = view.schema.validate(
+            &consignment,
+            op,
+            &view.schema.script,
+            &DefaultVerifier,
+            UnknownTypePolicy::Fail,
+        );
+        assert_eq!(full.failures, minimal.failures);
+        assert!(!minimal
+            .failures
+            .iter()
+            .any(|f| matches!(f, validation::Failure::SchemaUnknownExtensionType(..))));
+    }
+
+    #[test]
+    fn unknown_type_policy() {
+        let schema = SubSchema::default();
+        let mut genesis = Genesis::strict_dumb();
+        genesis
+            .globals
+            .add_state(5, RevealedData::strict_dumb())
+            .unwrap();
+        let consignment = Mock {
+            schema: schema.clone(),
+            genesis: genesis.clone(),
+            bundles: vec![],
+            extensions: vec![],
+        };
+        let op = OpRef::Genesis(&genesis);
+        let unknown = validation::Failure::SchemaUnknownGlobalStateType(genesis.id(), 5);
+
+        let status = schema.validate(
+            &consignment,
+            op,
+            &schema.script,
+            &DefaultVerifier,
+            UnknownTypePolicy::Fail,
+        );
+        assert!(status.failures.contains(&unknown));
+
+        let status = schema.validate(
+            &consignment,
+            op,
+            &schema.script,
+            &DefaultVerifier,
+            UnknownTypePolicy::Warn,
+        );
+        assert!(!status.failures.contains(&unknown));
+        assert!(status
+            .warnings
+            .contains(&validation::Warning::UnknownGlobalStateType(genesis.id(), 5)));
+    }
+
+    #[test]
+    fn extension_state_and_valencies() {
+        let schema = SubSchema {
+            extensions: tiny_bmap! { 0 => ExtensionSchema::default() },
+            ..default!()
+        };
+        let extension = Extension {
+            assignments: Confined::try_from(
+                bmap! { 1 => TypedAssigns::<GenesisSeal>::strict_dumb() },
+            )
+            .unwrap()
+            .into(),
+            redeemed: Redeemed::from(
+                Confined::try_from(bmap! { 2 => OpId::from([0xEF; 32]) }).unwrap(),
+            ),
+            ..Extension::strict_dumb()
+        };
+        let consignment = Mock {
+            schema: schema.clone(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![],
+            extensions: vec![extension.clone()],
+        };
+
+        let id = extension.id();
+        let status = schema.validate(
+            &consignment,
+            OpRef::Extension(&extension),
+            &schema.script,
+            &DefaultVerifier,
+            UnknownTypePolicy::Fail,
+        );
+        assert!(status
+            .failures
+            .contains(&validation::Failure::SchemaUnknownAssignmentType(id, 1)));
+        assert!(status
+            .failures
+            .contains(&validation::Failure::SchemaUnknownValencyType(id, 2)));
+    }
+
+    #[test]
+    fn verify_against_root() {
+        let root = RootSchema {
+            owned_types: tiny_bmap! { 1 => StateSchema::Declarative },
+            valency_types: tiny_bset! { 1 },
+            ..default!()
+        };
+        let conforming = SubSchema {
+            subset_of: Some(root.clone()),
+            owned_types: tiny_bmap! { 1 => StateSchema::Declarative },
+            valency_types: tiny_bset! { 1 },
+            ..default!()
+        };
+        assert!(conforming.verify_against_root().failures.is_empty());
+
+        let non_conforming = SubSchema {
+            subset_of: Some(root),
+            owned_types: tiny_bmap! { 1 => StateSchema::Declarative, 2 => StateSchema::Declarative },
+            valency_types: tiny_bset! { 2 },
+            transitions: tiny_bmap! { 1 => TransitionSchema::default() },
+            ..default!()
+        };
+        assert_eq!(non_conforming.verify_against_root().failures, vec![
+            validation::Failure::SubschemaAssignmentTypeMismatch(2),
+            validation::Failure::SubschemaValencyTypeMismatch(2),
+            validation::Failure::SubschemaTransitionTypeMismatch(1),
+        ]);
+
+        assert!(SubSchema::default()
+            .verify_against_root()
+            .failures
+            .is_empty());
+    }
+
+    #[test]
+    fn occurrences() {
+        let mut schema = SubSchema {
+            genesis: GenesisSchema {
+                assignments: tiny_bmap! { 1 => Occurrences::OnceOrUpTo(4) },
+                ..default!()
+            },
+            transitions: tiny_bmap! {
+                1 => TransitionSchema {
+                    inputs: tiny_bmap! { 1 => Occurrences::Range(2..=3) },
+                    ..default!()
+                },
+            },
+            ..default!()
+        };
+        assert!(schema.check_occurrences().failures.is_empty());
+
+        schema
+            .transitions
+            .get_mut(&1)
+            .unwrap()
+            .inputs
+            .insert(2, Occurrences::Range(3..=2))
+            .unwrap();
+        let failures = schema.check_occurrences().failures;
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(&failures[0], validation::Failure::Custom(msg)
+            if msg.starts_with("input #2 of ") && msg.contains("from 3 to 2 elements")));
+    }
+
+    #[test]
+    fn ffv() {
+        let schema = SubSchema::default();
+        assert_eq!(schema.check_ffv(Ffv::default()), Ok(()));
+
+        let future = SubSchema {
+            ffv: Ffv::with(1),
+            ..default!()
+        };
+        assert_eq!(future.check_ffv(Ffv::with(1)), Ok(()));
+        assert_eq!(
+            future.check_ffv(Ffv::default()),
+            Err(validation::Failure::UnsupportedFfv {
+                schema: Ffv::with(1),
+                max_supported: Ffv::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn type_imports() {
+        let sys = SystemBuilder::new()
+            .import(std_stl())
+            .unwrap()
+            .finalize()
+            .unwrap();
+        let schema = SubSchema {
+            type_system: sys.as_types().clone(),
+            ..default!()
+        };
+        assert!(schema.verify_type_imports(&[std_stl()]).failures.is_empty());
+        assert_eq!(schema.verify_type_imports(&[]).failures.len(), schema.type_system.len());
+    }
+}
@@ -22,6 +22,8 @@
 
 use crate::validation::OpInfo;
 use crate::vm::AluRuntime;
+#[cfg(feature = "vm-debug")]
+use crate::vm::RegisterTrace;
 use crate::{validation, Script};
 
 /// Trait for concrete types wrapping virtual machines to be used from inside
@@ -30,6 +32,12 @@ pub trait VirtualMachine {
     /// Validates state change in a contract operation.
     #[allow(clippy::result_large_err)]
     fn validate(&self, info: OpInfo) -> Result<(), validation::Failure>;
+
+    /// Returns final state of VM registers collected for each of the
+    /// validated operations, clearing the collected data. VMs not supporting
+    /// register inspection return empty trace.
+    #[cfg(feature = "vm-debug")]
+    fn take_registers(&self) -> RegisterTrace { none!() }
 }
 
 impl VirtualMachine for Script {
@@ -42,8 +50,10 @@ impl VirtualMachine for Script {
 
 impl<'script> VirtualMachine for AluRuntime<'script> {
     fn validate(&self, info: OpInfo) -> Result<(), validation::Failure> {
-        let id = info.id;
         self.run_validations(&info)
-            .map_err(|msg| validation::Failure::ScriptFailure(id, msg))
+            .map_err(validation::Failure::ScriptFailure)
     }
+
+    #[cfg(feature = "vm-debug")]
+    fn take_registers(&self) -> RegisterTrace { AluRuntime::take_registers(self) }
 }
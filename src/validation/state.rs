@@ -23,6 +23,7 @@
 use strict_types::TypeSystem;
 
 use crate::schema::AssignmentType;
+use crate::validation::CommitmentVerifier;
 use crate::{
     validation, Assign, ConfidentialState, ExposedSeal, ExposedState, OpId, StateCommitment,
     StateData, StateSchema,
@@ -35,6 +36,7 @@ impl StateSchema {
         opid: &OpId,
         state_type: AssignmentType,
         data: &Assign<State, Seal>,
+        verifier: &dyn CommitmentVerifier,
     ) -> validation::Status {
         let mut status = validation::Status::new();
         match data {
@@ -43,7 +45,7 @@ impl StateSchema {
                     (StateSchema::Declarative, StateCommitment::Void) => {}
                     (StateSchema::Fungible(_), StateCommitment::Fungible(value)) => {
                         // [SECURITY-CRITICAL]: Bulletproofs validation
-                        if let Err(err) = value.verify_range_proof() {
+                        if let Err(err) = verifier.verify_range_proof(&value) {
                             status.add_failure(validation::Failure::BulletproofsInvalid(
                                 *opid,
                                 state_type,
@@ -20,36 +20,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
 use core::iter::FromIterator;
+use core::mem;
 use core::ops::AddAssign;
-use std::fmt::{self, Display, Formatter};
 
 use bp::dbc::anchor;
-use bp::{seals, Txid};
+use bp::{seals, Chain, Txid};
 use strict_types::SemId;
 
+use super::validity::{dedup_unique, extend_unique, validity, StatusCore, Validity};
 use crate::contract::Opout;
 use crate::schema::{self, SchemaId};
 use crate::{
-    AssignmentType, BundleId, OccurrencesMismatch, OpFullType, OpId, SecretSeal, StateType,
+    AssignmentType, BundleId, ContractId, Ffv, OccurrencesMismatch, OpFullType, OpId, SecretSeal,
+    StateType, LIB_NAME_RGB,
 };
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Display)]
-#[repr(u8)]
-pub enum Validity {
-    #[display("is valid")]
-    Valid,
-
-    #[display("has non-mined terminal(s)")]
-    UnminedTerminals,
-
-    #[display("contains unknown witness transactions")]
-    UnresolvedTransactions,
-
-    #[display("is NOT valid")]
-    Invalid,
-}
-
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 #[cfg_attr(
     feature = "serde",
@@ -64,56 +54,156 @@ pub struct Status {
     pub info: Vec<Info>,
 }
 
+/// Compact summary of the validation [`Status`], containing the validity and
+/// number of entries of each kind.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct StatusSummary {
+    pub validity: Validity,
+    pub failures: usize,
+    pub warnings: usize,
+    pub info: usize,
+    pub unresolved: usize,
+    pub unmined_endpoints: usize,
+}
+
+/// Displays the status as a YAML document with `validity`, `failures`,
+/// `warnings`, `info`, `unresolvedTxids` and `unminedEndpointTxids` keys, where
+/// failures, warnings and info are represented by their descriptions.
 impl Display for Status {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Consignment {}", self.validity())?;
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { self.report(f, false) }
+}
 
-        if !self.unresolved_txids.is_empty() {
-            f.write_str("Unknown witness transactions:\n")?;
-            for txid in &self.unresolved_txids {
-                writeln!(f, "- {txid}")?;
-            }
-        }
+/// Single line of [`Status::to_ndjson`] output.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+#[serde(crate = "serde_crate", rename_all = "camelCase")]
+struct NdjsonEntry {
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<u16>,
+    message: String,
+    ops: Vec<String>,
+}
 
-        if !self.unmined_terminals.is_empty() {
-            f.write_str("Non-mined terminals:\n")?;
-            for txid in &self.unmined_terminals {
-                writeln!(f, "- {txid}")?;
-            }
-        }
+const ANSI_RED: &str = "\x1B[31m";
+const ANSI_YELLOW: &str = "\x1B[33m";
+const ANSI_BLUE: &str = "\x1B[34m";
+const ANSI_RESET: &str = "\x1B[0m";
 
-        if !self.failures.is_empty() {
-            f.write_str("Validation failures:\n")?;
-            for fail in &self.failures {
-                writeln!(f, "- {fail}")?;
-            }
+impl Validity {
+    fn yaml_name(self) -> &'static str {
+        match self {
+            Validity::Valid => "valid",
+            Validity::UnminedTerminals => "unminedTerminals",
+            Validity::UnresolvedTransactions => "unresolvedTransactions",
+            Validity::Invalid => "invalid",
         }
+    }
+}
 
-        if !self.warnings.is_empty() {
-            f.write_str("Validation warnings:\n")?;
-            for warn in &self.warnings {
-                writeln!(f, "- {warn}")?;
-            }
-        }
+impl Status {
+    /// Formats the status as a YAML document with the same structure as used
+    /// by its [`Display`] implementation, highlighting failures in red,
+    /// warnings in yellow and info in blue with ANSI escape codes if
+    /// `colorize` is set.
+    ///
+    /// The caller is responsible for detecting whether the output is a
+    /// terminal; with `colorize` unset the output is plain text.
+    #[cfg(feature = "ansi")]
+    pub fn to_colored_string(&self, colorize: bool) -> String {
+        let mut s = String::new();
+        self.report(&mut s, colorize)
+            .expect("writing to string never fails");
+        s
+    }
 
-        if !self.info.is_empty() {
-            f.write_str("Validation info:\n")?;
-            for info in &self.info {
-                writeln!(f, "- {info}")?;
+    /// Formats failures, warnings and info entries as newline-delimited JSON,
+    /// one object per line with `kind` (`failure`, `warning` or `info`),
+    /// `message` and `ops` fields. Failures additionally have `code` field
+    /// with their [`Failure::code`] and list their [`Failure::involved_ops`]
+    /// in `ops`.
+    #[cfg(feature = "serde")]
+    pub fn to_ndjson(&self) -> String {
+        let failures = self.failures.iter().map(|failure| NdjsonEntry {
+            kind: "failure",
+            code: Some(failure.code()),
+            message: failure.to_string(),
+            ops: failure.involved_ops().iter().map(OpId::to_string).collect(),
+        });
+        let warnings = self.warnings.iter().map(|warning| NdjsonEntry {
+            kind: "warning",
+            code: None,
+            message: warning.to_string(),
+            ops: vec![],
+        });
+        let info = self.info.iter().map(|info| NdjsonEntry {
+            kind: "info",
+            code: None,
+            message: info.to_string(),
+            ops: vec![],
+        });
+        failures
+            .chain(warnings)
+            .chain(info)
+            .map(|entry| {
+                let mut line =
+                    serde_json::to_string(&entry).expect("serializing strings never fails");
+                line.push('\n');
+                line
+            })
+            .collect()
+    }
+
+    /// Writes the status as a YAML document, without relying on a YAML
+    /// serializer.
+    fn report(&self, f: &mut dyn fmt::Write, colorize: bool) -> fmt::Result {
+        fn write_list(
+            f: &mut dyn fmt::Write,
+            key: &str,
+            items: impl ExactSizeIterator<Item = impl Display>,
+            color: (&str, &str),
+        ) -> fmt::Result {
+            if items.len() == 0 {
+                return writeln!(f, "{key}: []");
+            }
+            writeln!(f, "{key}:")?;
+            for item in items {
+                let item = item
+                    .to_string()
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n");
+                writeln!(f, "- {}\"{item}\"{}", color.0, color.1)?;
             }
+            Ok(())
         }
 
-        Ok(())
+        let reset = if colorize { ANSI_RESET } else { "" };
+        let color = |code| if colorize { (code, reset) } else { ("", "") };
+
+        writeln!(f, "validity: {}", self.validity().yaml_name())?;
+        write_list(f, "failures", self.failures.iter(), color(ANSI_RED))?;
+        write_list(f, "warnings", self.warnings.iter(), color(ANSI_YELLOW))?;
+        write_list(f, "info", self.info.iter(), color(ANSI_BLUE))?;
+        write_list(f, "unresolvedTxids", self.unresolved_txids.iter(), ("", ""))?;
+        write_list(f, "unminedEndpointTxids", self.unmined_terminals.iter(), ("", ""))
     }
 }
 
+/// Merges statuses, collapsing identical entries: each of the failures,
+/// warnings, info entries and txids is kept only at its first occurrence.
 impl AddAssign for Status {
     fn add_assign(&mut self, rhs: Self) {
-        self.unresolved_txids.extend(rhs.unresolved_txids);
-        self.unmined_terminals.extend(rhs.unmined_terminals);
-        self.failures.extend(rhs.failures);
-        self.warnings.extend(rhs.warnings);
-        self.info.extend(rhs.info);
+        extend_unique(&mut self.unresolved_txids, rhs.unresolved_txids);
+        extend_unique(&mut self.unmined_terminals, rhs.unmined_terminals);
+        extend_unique(&mut self.failures, rhs.failures);
+        extend_unique(&mut self.warnings, rhs.warnings);
+        extend_unique(&mut self.info, rhs.info);
     }
 }
 
@@ -129,6 +219,18 @@ impl Status {
     }
 }
 
+impl From<Status> for StatusCore<Txid, Failure, Warning, Info> {
+    fn from(status: Status) -> Self {
+        StatusCore {
+            unresolved_txids: status.unresolved_txids,
+            unmined_terminals: status.unmined_terminals,
+            failures: status.failures,
+            warnings: status.warnings,
+            info: status.info,
+        }
+    }
+}
+
 impl FromIterator<Failure> for Status {
     fn from_iter<T: IntoIterator<Item = Failure>>(iter: T) -> Self {
         Self {
@@ -163,19 +265,260 @@ impl Status {
         self
     }
 
-    pub fn validity(&self) -> Validity {
-        if self.failures.is_empty() {
-            if self.unmined_terminals.is_empty() {
-                Validity::Valid
-            } else {
-                Validity::UnminedTerminals
-            }
-        } else if self.unresolved_txids.is_empty() {
-            Validity::Invalid
-        } else {
-            Validity::UnresolvedTransactions
+    /// Removes repeated failures, warnings, info entries and txids, keeping
+    /// only the first occurrence of each of them.
+    pub fn dedup(&mut self) {
+        dedup_unique(&mut self.unresolved_txids);
+        dedup_unique(&mut self.unmined_terminals);
+        dedup_unique(&mut self.failures);
+        dedup_unique(&mut self.warnings);
+        dedup_unique(&mut self.info);
+    }
+
+    /// Appends entries of the `other` status without collapsing identical
+    /// entries, which is left to a single [`Status::dedup`] once the status
+    /// is complete.
+    pub(super) fn append(&mut self, other: Status) {
+        self.unresolved_txids.extend(other.unresolved_txids);
+        self.unmined_terminals.extend(other.unmined_terminals);
+        self.failures.extend(other.failures);
+        self.warnings.extend(other.warnings);
+        self.info.extend(other.info);
+    }
+
+    /// Keeps only the failures for which `f` returns `true`, for instance
+    /// the ones of a specific [`Failure::severity`].
+    pub fn retain_failures<F: Fn(&Failure) -> bool>(&mut self, f: F) { self.failures.retain(f) }
+
+    /// Keeps only the warnings for which `f` returns `true`.
+    pub fn retain_warnings<F: Fn(&Warning) -> bool>(&mut self, f: F) { self.warnings.retain(f) }
+
+    /// Keeps only the info entries for which `f` returns `true`.
+    pub fn retain_info<F: Fn(&Info) -> bool>(&mut self, f: F) { self.info.retain(f) }
+
+    /// Sorts failures by the ids of the operations they refer to, as reported
+    /// by [`Failure::involved_ops`], keeping the order of failures referring
+    /// to the same operations.
+    pub fn sort_failures(&mut self) { self.failures.sort_by_cached_key(Failure::involved_ops) }
+
+    /// Brings the status into a canonical form, independent from the order in
+    /// which the validation has discovered its entries.
+    ///
+    /// Failures are ordered by their [`Failure::code`], then by the ids of
+    /// the operations they refer to and then by their descriptions. Warnings
+    /// and info entries are ordered by their descriptions; transaction ids
+    /// are ordered by their values.
+    pub fn sort(&mut self) {
+        self.failures.sort_by_cached_key(|failure| {
+            (failure.code(), failure.involved_ops(), failure.to_string())
+        });
+        self.warnings.sort_by_cached_key(Warning::to_string);
+        self.info.sort_by_cached_key(Info::to_string);
+        self.unresolved_txids.sort();
+        self.unmined_terminals.sort();
+    }
+
+    /// Returns compact summary of the status.
+    pub fn summary(&self) -> StatusSummary {
+        StatusSummary {
+            validity: self.validity(),
+            failures: self.failures.len(),
+            warnings: self.warnings.len(),
+            info: self.info.len(),
+            unresolved: self.unresolved_txids.len(),
+            unmined_endpoints: self.unmined_terminals.len(),
         }
     }
+
+    /// Returns status in a canonical form; see [`Status::sort`] for details.
+    pub fn sorted(mut self) -> Status {
+        self.sort();
+        self
+    }
+
+    /// Returns failures referencing the operation `op`, as reported by
+    /// [`Failure::involved_ops`].
+    pub fn failures_for_op(&self, op: OpId) -> Vec<&Failure> {
+        self.failures
+            .iter()
+            .filter(|failure| failure.involved_ops().contains(&op))
+            .collect()
+    }
+
+    /// Converts the status into a strict one, where each of the warnings is
+    /// turned into [`Failure::Custom`] with the warning description, making
+    /// the consignment invalid. Clone the status before the conversion if the
+    /// non-strict view is still needed.
+    pub fn into_strict(mut self) -> Status {
+        let warnings = mem::take(&mut self.warnings);
+        self.failures.extend(
+            warnings
+                .into_iter()
+                .map(|warning| Failure::Custom(warning.to_string())),
+        );
+        self
+    }
+
+    /// Returns ids of all transactions mentioned in the status: the unresolved
+    /// and unmined ones, and the ones referenced by failures and warnings.
+    pub fn referenced_txids(&self) -> BTreeSet<Txid> {
+        let failures = self.failures.iter().filter_map(|failure| match failure {
+            Failure::NotInAnchor(_, txid) |
+            Failure::MpcInvalid(_, txid) |
+            Failure::SealNoWitnessTx(txid) |
+            Failure::ResolverError(txid, _) |
+            Failure::SealInvalid(_, txid, _) |
+            Failure::AnchorInvalid(_, txid, _) => Some(*txid),
+            _ => None,
+        });
+        let warnings = self.warnings.iter().filter_map(|warning| match warning {
+            Warning::TerminalWitnessNotMined(txid) | Warning::SealOnSpentUtxo(_, txid) => {
+                Some(*txid)
+            }
+            _ => None,
+        });
+        self.unresolved_txids
+            .iter()
+            .chain(&self.unmined_terminals)
+            .copied()
+            .chain(failures)
+            .chain(warnings)
+            .collect()
+    }
+
+    /// Returns ids of the operations which can't be fully validated since
+    /// the consignment contains only the confidential form of their seals
+    /// ([`Failure::ConfidentialSeal`]) or state
+    /// ([`Info::UncheckableConfidentialState`]).
+    pub fn uncheckable_ops(&self) -> BTreeSet<OpId> {
+        let failures = self.failures.iter().filter_map(|failure| match failure {
+            Failure::ConfidentialSeal(opout) => Some(opout.op),
+            _ => None,
+        });
+        let info = self.info.iter().filter_map(|info| match info {
+            Info::UncheckableConfidentialState(opid, _) => Some(*opid),
+            _ => None,
+        });
+        failures.chain(info).collect()
+    }
+
+    /// Returns terminal operations which will become valid once the
+    /// transaction `txid` gets mined, i.e. the ones reported with
+    /// [`Info::TerminalPending`] for that transaction.
+    pub fn endpoints_pending_on(&self, txid: Txid) -> Vec<OpId> {
+        self.info
+            .iter()
+            .filter_map(|info| match info {
+                Info::TerminalPending {
+                    opid,
+                    txid: witness,
+                } if *witness == txid => Some(*opid),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Detects whether the status contains failures of
+    /// [`Severity::Consensus`], meaning that the consignment is definitely
+    /// invalid and not just can't be fully validated.
+    pub fn has_consensus_failures(&self) -> bool {
+        self.failures
+            .iter()
+            .any(|failure| failure.severity() == Severity::Consensus)
+    }
+
+    pub fn validity(&self) -> Validity {
+        validity(&self.failures, &self.unresolved_txids, &self.unmined_terminals)
+    }
+}
+
+/// Status merged from validation of multiple sources (like several
+/// consignments validated in a batch), remembering the source of each of the
+/// failures.
+///
+/// Unlike merging with `+=`, failures are not deduplicated, so each of them
+/// keeps its own source label.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct LabeledStatus {
+    status: Status,
+    /// Source labels of the failures, by the failure index.
+    origins: Vec<String>,
+}
+
+impl LabeledStatus {
+    pub fn new() -> Self { Self::default() }
+
+    /// Merges `other` status, labelling its failures with `label`. Warnings,
+    /// info entries and txids are merged as with `+=`.
+    pub fn merge(&mut self, label: impl Into<String>, other: Status) {
+        let label = label.into();
+        self.origins
+            .extend(other.failures.iter().map(|_| label.clone()));
+        self.status.failures.extend(other.failures);
+        extend_unique(&mut self.status.warnings, other.warnings);
+        extend_unique(&mut self.status.info, other.info);
+        extend_unique(&mut self.status.unresolved_txids, other.unresolved_txids);
+        extend_unique(&mut self.status.unmined_terminals, other.unmined_terminals);
+    }
+
+    /// Returns label of the status which has produced the failure with the
+    /// given index in [`Status::failures`] of the merged status.
+    pub fn origin(&self, index: usize) -> Option<&str> {
+        self.origins.get(index).map(String::as_str)
+    }
+
+    /// Returns failures coming from the status with the given `label`.
+    pub fn failures_from<'me>(&'me self, label: &'me str) -> impl Iterator<Item = &'me Failure> {
+        self.status
+            .failures
+            .iter()
+            .zip(&self.origins)
+            .filter(move |(_, origin)| *origin == label)
+            .map(|(failure, _)| failure)
+    }
+
+    /// Returns the merged status.
+    pub fn status(&self) -> &Status { &self.status }
+
+    /// Releases the merged status, dropping the labels.
+    pub fn into_status(self) -> Status { self.status }
+}
+
+/// Helper for constructing [`Status`] with specific content in tests.
+#[cfg(any(test, feature = "test-helpers"))]
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct StatusBuilder(Status);
+
+#[cfg(any(test, feature = "test-helpers"))]
+impl StatusBuilder {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn failure(mut self, failure: impl Into<Failure>) -> Self {
+        self.0.add_failure(failure);
+        self
+    }
+
+    pub fn warning(mut self, warning: impl Into<Warning>) -> Self {
+        self.0.add_warning(warning);
+        self
+    }
+
+    pub fn info(mut self, info: impl Into<Info>) -> Self {
+        self.0.add_info(info);
+        self
+    }
+
+    pub fn unresolved_txid(mut self, txid: Txid) -> Self {
+        self.0.unresolved_txids.push(txid);
+        self
+    }
+
+    pub fn unmined_terminal(mut self, txid: Txid) -> Self {
+        self.0.unmined_terminals.push(txid);
+        self
+    }
+
+    pub fn finish(self) -> Status { self.0 }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Display, From)]
@@ -195,8 +538,16 @@ pub enum Failure {
         /// Actual schema id provided by the consignment.
         actual: SchemaId,
     },
+    /// schema {0} used by the contract is not known.
+    SchemaUnknown(SchemaId),
     /// schema uses reserved type for the blank state transition.
     SchemaBlankTransitionRedefined,
+    /// schema script uses opcode {0} which is not implemented by this
+    /// version of the virtual machine.
+    SchemaUnimplementedOpcode(u8),
+    /// schema uses fast-forward version {schema}, while only versions up to
+    /// {max_supported} are supported. Please update the software.
+    UnsupportedFfv { schema: Ffv, max_supported: Ffv },
 
     /// schema global state #{0} uses semantic data type absent in type library
     /// ({1}).
@@ -208,6 +559,10 @@ pub enum Failure {
     /// ({1}).
     SchemaOpMetaSemIdUnknown(OpFullType, SemId),
 
+    /// schema type system contains type {0} which is not resolvable against
+    /// the provided type libraries.
+    TypeLibMismatch(SemId),
+
     /// schema for {0} has zero inputs.
     SchemaOpEmptyInputs(OpFullType),
     /// schema for {0} references undeclared global state type {1}.
@@ -293,10 +648,26 @@ pub enum Failure {
     TransitionAbsent(OpId),
     /// bundle with id {0} is invalid.
     BundleInvalid(BundleId),
+    /// data of operation {0}, including its metadata, do not match the
+    /// operation id committed into the bundle.
+    MetadataCommitmentMismatch(OpId),
+    /// bundle {0} contains state transitions of a foreign contract {1}.
+    BundleForeignContract(BundleId, ContractId),
+
+    // Channel update errors
+    /// channel state {0} is absent from the previous channel bundle.
+    ChannelFundingAbsent(Opout),
+    /// channel update doesn't spend the channel state {0}.
+    ChannelFundingNotSpent(Opout),
+    /// channel update spending state {0} doesn't conserve the value.
+    ChannelValueMismatch(Opout),
 
     // Errors checking seal closing
     /// transition {0} is not anchored.
     NotAnchored(OpId),
+    /// witness transaction for operation {0} belongs to {2} network, while
+    /// the contract is issued on {1}.
+    NetworkMismatch(OpId, Chain, Chain),
     /// anchor for transition {0} doesn't commit to the actual transition data.
     NotInAnchor(OpId, Txid),
     /// transition {opid} references state type {state_type} absent in the
@@ -308,6 +679,9 @@ pub enum Failure {
     },
     /// transition {0} references non-existing previous output {1}.
     NoPrevOut(OpId, Opout),
+    /// operation {0} is referenced beyond the lookback window of the
+    /// validator and its state can't be recovered.
+    LookbackExceeded(OpId),
     /// seal defined in the history as a part of operation output {0} is
     /// confidential and can't be validated.
     ConfidentialSeal(Opout),
@@ -316,6 +690,9 @@ pub enum Failure {
     MpcInvalid(OpId, Txid),
     /// witness transaction {0} is not known to the transaction resolver.
     SealNoWitnessTx(Txid),
+    /// transaction resolver failed to retrieve witness transaction {0}.
+    /// Details: {1}
+    ResolverError(Txid, String),
     /// transition {0} doesn't close seal with the witness transaction {1}.
     /// Details: {2}
     SealInvalid(OpId, Txid, seals::txout::VerifyError),
@@ -364,14 +741,394 @@ pub enum Failure {
         expected: schema::FungibleType,
         found: schema::FungibleType,
     },
+    /// operation {0} assigns zero value to the state of type {1}, which is
+    /// required to be non-zero.
+    ZeroValueAssignment(OpId, schema::AssignmentType),
     /// invalid bulletproofs in {0}:{1}: {2}
     BulletproofsInvalid(OpId, u16, String),
-    /// operation {0} is invalid: {1}
-    ScriptFailure(OpId, String),
+    /// {0}
+    ScriptFailure(ScriptError),
 
     /// Custom error by external services on top of RGB Core.
     #[display(inner)]
     Custom(String),
+
+    /// Custom error by external services on top of RGB Core, carrying a
+    /// service-specific error code, which [`Failure::code`] offsets into the
+    /// range reserved for the custom codes.
+    #[display("{message}")]
+    CustomCoded { code: CustomCode, message: String },
+}
+
+/// Service-specific code of [`Failure::CustomCoded`], which must fit into the
+/// lower 15 bits.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[display(inner)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", try_from = "u16", into = "u16")
+)]
+pub struct CustomCode(u16);
+
+impl CustomCode {
+    /// Largest code which can be used by a service.
+    pub const MAX: u16 = Failure::CUSTOM_CODE_BASE - 1;
+
+    /// Constructs custom code, returning `None` if the code exceeds
+    /// [`CustomCode::MAX`].
+    pub const fn with(code: u16) -> Option<Self> {
+        if code > Self::MAX {
+            None
+        } else {
+            Some(CustomCode(code))
+        }
+    }
+
+    pub const fn to_u16(self) -> u16 { self.0 }
+}
+
+/// custom failure code {0} exceeds the maximal value of 0x7FFF.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub struct CustomCodeOverflow(pub u16);
+
+impl TryFrom<u16> for CustomCode {
+    type Error = CustomCodeOverflow;
+
+    fn try_from(code: u16) -> Result<Self, Self::Error> {
+        CustomCode::with(code).ok_or(CustomCodeOverflow(code))
+    }
+}
+
+impl From<CustomCode> for u16 {
+    fn from(code: CustomCode) -> Self { code.0 }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Failure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Failure::SealInvalid(_, _, err) => Some(err),
+            Failure::AnchorInvalid(_, _, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Details of the validation script failure.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct ScriptError {
+    /// Operation which has failed the validation.
+    pub op: OpId,
+    /// Offset of the failed script entry point within its library. This is
+    /// not the position of the failed instruction, which is not exposed by
+    /// the virtual machine.
+    pub entry_offset: u16,
+    /// Error message left by the script in `s0` register or the description
+    /// of the virtual machine error.
+    pub isa_error: String,
+    /// Value of `a8[0]` register, which may be used by the script to report
+    /// the error code.
+    pub exit_code: Option<u8>,
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "operation {} is invalid: {}", self.op, self.isa_error)?;
+        if let Some(code) = self.exit_code {
+            write!(f, " (exit code {code})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Cause of an anchor verification failure, allowing to distinguish them
+/// without matching on the details of [`anchor::VerifyError`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum AnchorErrorKind {
+    /// Multi-protocol commitment proof does not match the contract and the
+    /// bundle.
+    #[display("mpc-mismatch")]
+    Mpc,
+
+    /// Deterministic bitcoin commitment is not present in the witness
+    /// transaction.
+    #[display("dbc-mismatch")]
+    Dbc,
+}
+
+impl From<&anchor::VerifyError> for AnchorErrorKind {
+    fn from(err: &anchor::VerifyError) -> Self {
+        match err {
+            anchor::VerifyError::Mpc(_) => AnchorErrorKind::Mpc,
+            _ => AnchorErrorKind::Dbc,
+        }
+    }
+}
+
+/// Severity of a validation failure, distinguishing definitely invalid
+/// consignments from the ones which validity can't be determined.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum Severity {
+    /// Violation of the consensus rules: the consignment is invalid.
+    #[display("consensus")]
+    Consensus,
+
+    /// Consignment or validator lacks data required for the validation, which
+    /// may be provided by repeating the validation with a complete
+    /// consignment or schema.
+    #[display("recoverable")]
+    Recoverable,
+
+    /// Validation depends on the data which are not accessible in the current
+    /// environment, like witness transactions unknown to the resolver.
+    #[display("environmental")]
+    Environmental,
+}
+
+impl Failure {
+    /// Lowest failure code reserved for [`Failure::CustomCoded`], so custom
+    /// codes never clash with the codes of the built-in failures.
+    pub const CUSTOM_CODE_BASE: u16 = 0x8000;
+
+    /// Returns anchor verification error, if the failure is caused by an
+    /// invalid anchor.
+    pub fn anchor_error(&self) -> Option<&anchor::VerifyError> {
+        match self {
+            Failure::AnchorInvalid(_, _, err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns cause of the anchor verification error, if the failure is
+    /// caused by an invalid anchor.
+    pub fn anchor_error_kind(&self) -> Option<AnchorErrorKind> {
+        self.anchor_error().map(AnchorErrorKind::from)
+    }
+
+    /// Returns severity of the failure.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Failure::SealNoWitnessTx(_) |
+            Failure::ResolverError(..) |
+            Failure::ConfidentialSeal(_) => Severity::Environmental,
+            Failure::SchemaUnknown(_) |
+            Failure::OperationAbsent(_) |
+            Failure::TransitionAbsent(_) |
+            Failure::LookbackExceeded(_) |
+            Failure::ValencyNoParent { .. } => Severity::Recoverable,
+            _ => Severity::Consensus,
+        }
+    }
+
+    /// Returns ids of all operations referenced by the failure, including the
+    /// ones owning the referenced outputs.
+    pub fn involved_ops(&self) -> Vec<OpId> {
+        match self {
+            Failure::SchemaMismatch { .. } |
+            Failure::SchemaUnknown(_) |
+            Failure::SchemaBlankTransitionRedefined |
+            Failure::SchemaUnimplementedOpcode(_) |
+            Failure::UnsupportedFfv { .. } |
+            Failure::SchemaGlobalSemIdUnknown(..) |
+            Failure::SchemaOwnedSemIdUnknown(..) |
+            Failure::SchemaOpMetaSemIdUnknown(..) |
+            Failure::TypeLibMismatch(_) |
+            Failure::SchemaOpEmptyInputs(_) |
+            Failure::SchemaOpGlobalTypeUnknown(..) |
+            Failure::SchemaOpAssignmentTypeUnknown(..) |
+            Failure::SchemaOpValencyTypeUnknown(..) |
+            Failure::SubschemaGlobalStateMismatch(_) |
+            Failure::SubschemaAssignmentTypeMismatch(_) |
+            Failure::SubschemaValencyTypeMismatch(_) |
+            Failure::SubschemaTransitionTypeMismatch(_) |
+            Failure::SubschemaExtensionTypeMismatch(_) |
+            Failure::SubschemaOpMetaMismatch { .. } |
+            Failure::SubschemaOpGlobalStateMismatch(..) |
+            Failure::SubschemaOpInputMismatch(..) |
+            Failure::SubschemaOpRedeemMismatch(..) |
+            Failure::SubschemaOpAssignmentsMismatch(..) |
+            Failure::SubschemaOpValencyMismatch(..) |
+            Failure::BundleInvalid(_) |
+            Failure::BundleForeignContract(..) |
+            Failure::SealNoWitnessTx(_) |
+            Failure::ResolverError(..) |
+            Failure::Custom(_) |
+            Failure::CustomCoded { .. } => vec![],
+
+            Failure::SchemaUnknownExtensionType(opid, _) |
+            Failure::SchemaUnknownTransitionType(opid, _) |
+            Failure::SchemaUnknownGlobalStateType(opid, _) |
+            Failure::SchemaUnknownAssignmentType(opid, _) |
+            Failure::SchemaUnknownValencyType(opid, _) |
+            Failure::SchemaGlobalStateOccurrences(opid, ..) |
+            Failure::SchemaGlobalStateLimit(opid, ..) |
+            Failure::SchemaInvalidMetadata(opid, _) |
+            Failure::SchemaInvalidGlobalValue(opid, ..) |
+            Failure::SchemaInvalidOwnedValue(opid, ..) |
+            Failure::SchemaInputOccurrences(opid, ..) |
+            Failure::SchemaAssignmentOccurrences(opid, ..) |
+            Failure::OperationAbsent(opid) |
+            Failure::TransitionAbsent(opid) |
+            Failure::MetadataCommitmentMismatch(opid) |
+            Failure::NotAnchored(opid) |
+            Failure::NetworkMismatch(opid, ..) |
+            Failure::NotInAnchor(opid, _) |
+            Failure::LookbackExceeded(opid) |
+            Failure::MpcInvalid(opid, _) |
+            Failure::SealInvalid(opid, ..) |
+            Failure::AnchorInvalid(opid, ..) |
+            Failure::StateTypeMismatch { opid, .. } |
+            Failure::MediaTypeMismatch { opid, .. } |
+            Failure::FungibleTypeMismatch { opid, .. } |
+            Failure::ZeroValueAssignment(opid, _) |
+            Failure::BulletproofsInvalid(opid, ..) |
+            Failure::ScriptFailure(err) => vec![err.op],
+
+            Failure::ChannelFundingAbsent(opout) |
+            Failure::ChannelFundingNotSpent(opout) |
+            Failure::ChannelValueMismatch(opout) |
+            Failure::ConfidentialSeal(opout) => vec![opout.op],
+
+            Failure::NoPrevOut(opid, opout) => vec![*opid, opout.op],
+            Failure::NoPrevState { opid, prev_id, .. } |
+            Failure::ValencyNoParent { opid, prev_id, .. } |
+            Failure::NoPrevValency { opid, prev_id, .. } => vec![*opid, *prev_id],
+        }
+    }
+
+    /// Returns stable numeric code of the failure, allowing to match failures
+    /// without parsing their descriptions.
+    ///
+    /// Codes are grouped by the failure kind:
+    /// - `1xxx` for schema errors and operations not conforming to the schema;
+    /// - `2xxx` for consignment consistency errors;
+    /// - `3xxx` for seal and anchor errors;
+    /// - `4xxx` for state data errors;
+    /// - `9000` for custom failures;
+    /// - `0x8000` and above for [`Failure::CustomCoded`], with the code
+    ///   provided by the service added to [`Failure::CUSTOM_CODE_BASE`].
+    ///
+    /// Codes of removed variants must never be reused.
+    pub fn code(&self) -> u16 {
+        match self {
+            Failure::SchemaMismatch { .. } => 1001,
+            Failure::SchemaUnknown(_) => 1002,
+            Failure::SchemaBlankTransitionRedefined => 1003,
+            // 1004 was used by the removed `SchemaSubsetCycle` failure
+            Failure::SchemaUnimplementedOpcode(_) => 1005,
+            Failure::UnsupportedFfv { .. } => 1006,
+            Failure::SchemaGlobalSemIdUnknown(..) => 1010,
+            Failure::SchemaOwnedSemIdUnknown(..) => 1011,
+            Failure::SchemaOpMetaSemIdUnknown(..) => 1012,
+            Failure::TypeLibMismatch(_) => 1013,
+            Failure::SchemaOpEmptyInputs(_) => 1020,
+            Failure::SchemaOpGlobalTypeUnknown(..) => 1021,
+            Failure::SchemaOpAssignmentTypeUnknown(..) => 1022,
+            Failure::SchemaOpValencyTypeUnknown(..) => 1023,
+            Failure::SubschemaGlobalStateMismatch(_) => 1030,
+            Failure::SubschemaAssignmentTypeMismatch(_) => 1031,
+            Failure::SubschemaValencyTypeMismatch(_) => 1032,
+            Failure::SubschemaTransitionTypeMismatch(_) => 1033,
+            Failure::SubschemaExtensionTypeMismatch(_) => 1034,
+            Failure::SubschemaOpMetaMismatch { .. } => 1040,
+            Failure::SubschemaOpGlobalStateMismatch(..) => 1041,
+            Failure::SubschemaOpInputMismatch(..) => 1042,
+            Failure::SubschemaOpRedeemMismatch(..) => 1043,
+            Failure::SubschemaOpAssignmentsMismatch(..) => 1044,
+            Failure::SubschemaOpValencyMismatch(..) => 1045,
+            Failure::SchemaUnknownExtensionType(..) => 1050,
+            Failure::SchemaUnknownTransitionType(..) => 1051,
+            Failure::SchemaUnknownGlobalStateType(..) => 1052,
+            Failure::SchemaUnknownAssignmentType(..) => 1053,
+            Failure::SchemaUnknownValencyType(..) => 1054,
+            Failure::SchemaGlobalStateOccurrences(..) => 1060,
+            Failure::SchemaGlobalStateLimit(..) => 1061,
+            Failure::SchemaInvalidMetadata(..) => 1062,
+            Failure::SchemaInvalidGlobalValue(..) => 1063,
+            Failure::SchemaInvalidOwnedValue(..) => 1064,
+            Failure::SchemaInputOccurrences(..) => 1065,
+            Failure::SchemaAssignmentOccurrences(..) => 1066,
+            Failure::OperationAbsent(_) => 2001,
+            Failure::TransitionAbsent(_) => 2002,
+            Failure::BundleInvalid(_) => 2003,
+            Failure::MetadataCommitmentMismatch(_) => 2004,
+            Failure::BundleForeignContract(..) => 2005,
+            Failure::ChannelFundingAbsent(_) => 2010,
+            Failure::ChannelFundingNotSpent(_) => 2011,
+            Failure::ChannelValueMismatch(_) => 2012,
+            Failure::NoPrevState { .. } => 2020,
+            Failure::NoPrevOut(..) => 2021,
+            Failure::LookbackExceeded(_) => 2022,
+            Failure::ValencyNoParent { .. } => 2030,
+            Failure::NoPrevValency { .. } => 2031,
+            Failure::NotAnchored(_) => 3001,
+            Failure::NetworkMismatch(..) => 3002,
+            Failure::NotInAnchor(..) => 3003,
+            Failure::ConfidentialSeal(_) => 3004,
+            Failure::MpcInvalid(..) => 3005,
+            Failure::SealNoWitnessTx(_) => 3006,
+            Failure::SealInvalid(..) => 3007,
+            Failure::AnchorInvalid(..) => 3008,
+            Failure::ResolverError(..) => 3009,
+            Failure::StateTypeMismatch { .. } => 4001,
+            Failure::MediaTypeMismatch { .. } => 4002,
+            Failure::FungibleTypeMismatch { .. } => 4003,
+            Failure::ZeroValueAssignment(..) => 4004,
+            Failure::BulletproofsInvalid(..) => 4005,
+            Failure::ScriptFailure(..) => 4006,
+            Failure::Custom(_) => 9000,
+            Failure::CustomCoded { code, .. } => Failure::CUSTOM_CODE_BASE | code.to_u16(),
+        }
+    }
+
+    /// Returns a short suggestion on what the user may do about the failure,
+    /// suitable for presenting in wallet user interfaces. Failures which
+    /// can't be remedied by the user (like consensus violations inside the
+    /// contract history) return `None`.
+    pub fn remediation(&self) -> Option<&'static str> {
+        Some(match self {
+            Failure::SchemaMismatch { .. } => "this consignment uses an unsupported schema",
+            Failure::TypeLibMismatch(_) => {
+                "import the type libraries matching the ones used by the schema"
+            }
+            Failure::SealNoWitnessTx(_) => "request the missing transaction from the sender",
+            Failure::ResolverError(..) => "check the connection to the bitcoin backend and retry",
+            Failure::OperationAbsent(_) |
+            Failure::TransitionAbsent(_) |
+            Failure::NoPrevState { .. } |
+            Failure::NoPrevOut(..) |
+            Failure::ValencyNoParent { .. } |
+            Failure::NoPrevValency { .. } => {
+                "the consignment is incomplete; request the full contract history from the sender"
+            }
+            Failure::ConfidentialSeal(_) => {
+                "request the sender to reveal the seals of the transferred state"
+            }
+            Failure::ChannelFundingAbsent(_) |
+            Failure::ChannelFundingNotSpent(_) |
+            Failure::ChannelValueMismatch(_) => {
+                "reject the channel update and request a correct one from the counterparty"
+            }
+            _ => return None,
+        })
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Display, From)]
@@ -390,12 +1147,51 @@ pub enum Warning {
     ExcessiveOperation(OpId),
     /// terminal witness transaction {0} is not yet mined.
     TerminalWitnessNotMined(Txid),
+    /// seal defined by operation output {0} references output of transaction
+    /// {1} which is already spent; the state can't be ever spent.
+    SealOnSpentUtxo(Opout, Txid),
+    /// operation {0} contains global state of type {1} unknown to the schema,
+    /// which was ignored.
+    UnknownGlobalStateType(OpId, schema::GlobalStateType),
+    /// operation {0} assigns owned state of type {1} unknown to the schema,
+    /// which was ignored.
+    UnknownAssignmentType(OpId, AssignmentType),
 
     /// Custom warning by external services on top of RGB Core.
     #[display(inner)]
     Custom(String),
 }
 
+/// Kinds of the checks reported by [`Info::CheckPassed`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Display)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB, tags = order)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum CheckKind {
+    /// Operation conforms to the schema: known types, state occurrences and
+    /// values, script validation.
+    #[display("schema")]
+    #[strict_type(dumb)]
+    Schema,
+
+    /// Bundle containing the operation is committed into the anchor.
+    #[display("anchor")]
+    Anchor,
+
+    /// Witness transaction closes seals of the operation inputs.
+    #[display("seals")]
+    Seals,
+
+    /// Anchor deterministic bitcoin commitment is present in the witness
+    /// transaction.
+    #[display("bitcoin commitment")]
+    Dbc,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Display, From)]
 #[cfg_attr(
     feature = "serde",
@@ -408,7 +1204,765 @@ pub enum Info {
     /// thus was not validated.
     UncheckableConfidentialState(OpId, AssignmentType),
 
+    /// operation {op} has passed {check} check.
+    CheckPassed { op: OpId, check: CheckKind },
+
+    /// terminal operation {opid} will become valid once its witness
+    /// transaction {txid} gets mined.
+    TerminalPending { opid: OpId, txid: Txid },
+
     /// Custom info by external services on top of RGB Core.
     #[display(inner)]
     Custom(String),
 }
+
+#[cfg(test)]
+mod test {
+    use commit_verify::{mpc, Conceal};
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::GraphSeal;
+
+    #[test]
+    fn endpoints_pending_on() {
+        let opid = OpId::from([0xEF; 32]);
+        let txid = Txid::from([0xAB; 32]);
+        let mut status = Status::new();
+        status.unmined_terminals.push(txid);
+        status.add_warning(Warning::TerminalWitnessNotMined(txid));
+        status.add_info(Info::CheckPassed {
+            op: OpId::from([0xCD; 32]),
+            check: CheckKind::Schema,
+        });
+        status.add_info(Info::TerminalPending { opid, txid });
+        assert_eq!(status.endpoints_pending_on(txid), vec![opid]);
+        assert!(status
+            .endpoints_pending_on(Txid::from([0x11; 32]))
+            .is_empty());
+    }
+
+    #[test]
+    fn codes() {
+        let opid = OpId::from([0xEF; 32]);
+        let txid = Txid::from([0xAB; 32]);
+        let schema_id = SchemaId::from([0xCD; 32]);
+        let bundle_id = BundleId::from([0x12; 32]);
+        let opout = Opout::new(opid, 1, 0);
+        let sem_id = SemId::default();
+        let op_type = OpFullType::Genesis;
+        let occurrences = OccurrencesMismatch {
+            min: 1,
+            max: 1,
+            found: 0,
+        };
+        let failures = [
+            Failure::SchemaMismatch {
+                expected: schema_id,
+                actual: schema_id,
+            },
+            Failure::SchemaUnknown(schema_id),
+            Failure::SchemaBlankTransitionRedefined,
+            Failure::SchemaUnimplementedOpcode(0),
+            Failure::UnsupportedFfv {
+                schema: Ffv::with(1),
+                max_supported: Ffv::default(),
+            },
+            Failure::SchemaGlobalSemIdUnknown(1, sem_id),
+            Failure::SchemaOwnedSemIdUnknown(1, sem_id),
+            Failure::SchemaOpMetaSemIdUnknown(op_type, sem_id),
+            Failure::TypeLibMismatch(sem_id),
+            Failure::SchemaOpEmptyInputs(op_type),
+            Failure::SchemaOpGlobalTypeUnknown(op_type, 1),
+            Failure::SchemaOpAssignmentTypeUnknown(op_type, 1),
+            Failure::SchemaOpValencyTypeUnknown(op_type, 1),
+            Failure::SubschemaGlobalStateMismatch(1),
+            Failure::SubschemaAssignmentTypeMismatch(1),
+            Failure::SubschemaValencyTypeMismatch(1),
+            Failure::SubschemaTransitionTypeMismatch(1),
+            Failure::SubschemaExtensionTypeMismatch(1),
+            Failure::SubschemaOpMetaMismatch {
+                op_type,
+                expected: sem_id,
+                actual: sem_id,
+            },
+            Failure::SubschemaOpGlobalStateMismatch(op_type, 1),
+            Failure::SubschemaOpInputMismatch(op_type, 1),
+            Failure::SubschemaOpRedeemMismatch(op_type, 1),
+            Failure::SubschemaOpAssignmentsMismatch(op_type, 1),
+            Failure::SubschemaOpValencyMismatch(op_type, 1),
+            Failure::SchemaUnknownExtensionType(opid, 1),
+            Failure::SchemaUnknownTransitionType(opid, 1),
+            Failure::SchemaUnknownGlobalStateType(opid, 1),
+            Failure::SchemaUnknownAssignmentType(opid, 1),
+            Failure::SchemaUnknownValencyType(opid, 1),
+            Failure::SchemaGlobalStateOccurrences(opid, 1, occurrences),
+            Failure::SchemaGlobalStateLimit(opid, 1, 2, 1),
+            Failure::SchemaInvalidMetadata(opid, sem_id),
+            Failure::SchemaInvalidGlobalValue(opid, 1, sem_id),
+            Failure::SchemaInvalidOwnedValue(opid, 1, sem_id),
+            Failure::SchemaInputOccurrences(opid, 1, occurrences),
+            Failure::SchemaAssignmentOccurrences(opid, 1, occurrences),
+            Failure::OperationAbsent(opid),
+            Failure::TransitionAbsent(opid),
+            Failure::BundleInvalid(bundle_id),
+            Failure::MetadataCommitmentMismatch(opid),
+            Failure::BundleForeignContract(bundle_id, ContractId::from([0x34; 32])),
+            Failure::ChannelFundingAbsent(opout),
+            Failure::ChannelFundingNotSpent(opout),
+            Failure::ChannelValueMismatch(opout),
+            Failure::NotAnchored(opid),
+            Failure::NetworkMismatch(opid, Chain::Bitcoin, Chain::Testnet3),
+            Failure::NotInAnchor(opid, txid),
+            Failure::NoPrevState {
+                opid,
+                prev_id: opid,
+                state_type: 1,
+            },
+            Failure::NoPrevOut(opid, opout),
+            Failure::LookbackExceeded(opid),
+            Failure::ConfidentialSeal(opout),
+            Failure::MpcInvalid(opid, txid),
+            Failure::SealNoWitnessTx(txid),
+            Failure::ResolverError(txid, s!("timeout")),
+            Failure::SealInvalid(opid, txid, seals::txout::VerifyError::InconsistentCloseMethod),
+            Failure::AnchorInvalid(opid, txid, anchor::VerifyError::Mpc(mpc::InvalidProof)),
+            Failure::ValencyNoParent {
+                opid,
+                prev_id: opid,
+                valency: 1,
+            },
+            Failure::NoPrevValency {
+                opid,
+                prev_id: opid,
+                valency: 1,
+            },
+            Failure::StateTypeMismatch {
+                opid,
+                state_type: 1,
+                expected: StateType::Void,
+                found: StateType::Fungible,
+            },
+            Failure::MediaTypeMismatch {
+                opid,
+                state_type: 1,
+                expected: schema::MediaType::Any,
+                found: schema::MediaType::Any,
+            },
+            Failure::FungibleTypeMismatch {
+                opid,
+                state_type: 1,
+                expected: schema::FungibleType::Unsigned64Bit,
+                found: schema::FungibleType::Unsigned64Bit,
+            },
+            Failure::ZeroValueAssignment(opid, 1),
+            Failure::BulletproofsInvalid(opid, 0, s!("invalid")),
+            Failure::ScriptFailure(ScriptError {
+                op: opid,
+                entry_offset: 0,
+                isa_error: s!("failure"),
+                exit_code: None,
+            }),
+            Failure::Custom(s!("custom")),
+            Failure::CustomCoded {
+                code: CustomCode::with(9001).unwrap(),
+                message: s!("custom"),
+            },
+        ];
+        let codes = failures.iter().map(Failure::code).collect::<BTreeSet<_>>();
+        assert_eq!(codes.len(), failures.len());
+        assert!(!codes.contains(&0));
+    }
+
+    #[test]
+    fn custom_coded() {
+        let failure = Failure::CustomCoded {
+            code: CustomCode::with(100).unwrap(),
+            message: s!("asset is frozen"),
+        };
+        assert_eq!(failure.code(), 0x8064);
+        assert_eq!(failure.to_string(), "asset is frozen");
+        assert!(failure.involved_ops().is_empty());
+
+        let custom = Failure::CustomCoded {
+            code: CustomCode::with(0).unwrap(),
+            message: s!("custom"),
+        };
+        assert_eq!(custom.code(), Failure::CUSTOM_CODE_BASE);
+        let max = Failure::CustomCoded {
+            code: CustomCode::with(CustomCode::MAX).unwrap(),
+            message: s!("custom"),
+        };
+        assert_eq!(max.code(), u16::MAX);
+        assert_eq!(CustomCode::with(0x8001), None);
+        assert_eq!(CustomCode::try_from(0x8000), Err(CustomCodeOverflow(0x8000)));
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&failure).unwrap();
+            assert_eq!(serde_json::from_str::<Failure>(&json).unwrap(), failure);
+            assert!(serde_json::from_str::<CustomCode>("32769").is_err());
+        }
+    }
+
+    #[test]
+    fn severity() {
+        let opid = OpId::from([0xEF; 32]);
+        let txid = Txid::from([0xAB; 32]);
+        let opout = Opout::new(opid, 1, 0);
+
+        assert_eq!(Failure::SealNoWitnessTx(txid).severity(), Severity::Environmental);
+        assert_eq!(Failure::ResolverError(txid, s!("timeout")).severity(), Severity::Environmental);
+        assert_eq!(Failure::ConfidentialSeal(opout).severity(), Severity::Environmental);
+        assert_eq!(Failure::OperationAbsent(opid).severity(), Severity::Recoverable);
+        assert_eq!(
+            Failure::SchemaMismatch {
+                expected: SchemaId::from([0x01; 32]),
+                actual: SchemaId::from([0x02; 32]),
+            }
+            .severity(),
+            Severity::Consensus
+        );
+        assert_eq!(
+            Failure::StateTypeMismatch {
+                opid,
+                state_type: 1,
+                expected: StateType::Void,
+                found: StateType::Fungible,
+            }
+            .severity(),
+            Severity::Consensus
+        );
+
+        let mut status = Status::with_failure(Failure::SealNoWitnessTx(txid));
+        status.add_failure(Failure::ConfidentialSeal(opout));
+        assert!(!status.has_consensus_failures());
+        status.add_failure(Failure::ScriptFailure(ScriptError {
+            op: opid,
+            entry_offset: 0,
+            isa_error: s!("failure"),
+            exit_code: None,
+        }));
+        assert!(status.has_consensus_failures());
+        assert!(!Status::new().has_consensus_failures());
+    }
+
+    #[test]
+    fn dedup() {
+        let shared = Txid::from([0xAB; 32]);
+        let other = Txid::from([0xCD; 32]);
+
+        let mut status = Status::with_failure(Failure::SealNoWitnessTx(shared));
+        status.add_failure(Failure::SealNoWitnessTx(other));
+        status += Status::with_failure(Failure::SealNoWitnessTx(shared));
+        assert_eq!(status.failures, vec![
+            Failure::SealNoWitnessTx(shared),
+            Failure::SealNoWitnessTx(other)
+        ]);
+
+        let mut status = Status::with_failure(Failure::SealNoWitnessTx(other));
+        status.add_failure(Failure::SealNoWitnessTx(shared));
+        status.add_failure(Failure::SealNoWitnessTx(other));
+        assert_eq!(status.failures.len(), 3);
+        status.dedup();
+        assert_eq!(status.failures, vec![
+            Failure::SealNoWitnessTx(other),
+            Failure::SealNoWitnessTx(shared)
+        ]);
+    }
+
+    #[test]
+    fn referenced_txids() {
+        let opid = OpId::from([0x01; 32]);
+        let txids = [0xA0, 0xA1, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6, 0xA7].map(|b| Txid::from([b; 32]));
+        let status = StatusBuilder::new()
+            .failure(Failure::SealNoWitnessTx(txids[0]))
+            .failure(Failure::NotInAnchor(opid, txids[1]))
+            .failure(Failure::MpcInvalid(opid, txids[2]))
+            .failure(Failure::SealInvalid(
+                opid,
+                txids[3],
+                seals::txout::VerifyError::InconsistentCloseMethod,
+            ))
+            .failure(Failure::AnchorInvalid(
+                opid,
+                txids[4],
+                anchor::VerifyError::Mpc(mpc::InvalidProof),
+            ))
+            .failure(Failure::OperationAbsent(opid))
+            .warning(Warning::TerminalWitnessNotMined(txids[5]))
+            .unresolved_txid(txids[0])
+            .unresolved_txid(txids[6])
+            .unmined_terminal(txids[7])
+            .finish();
+
+        assert_eq!(status.referenced_txids(), txids.into_iter().collect());
+        assert!(Status::new().referenced_txids().is_empty());
+    }
+
+    #[test]
+    fn uncheckable_ops() {
+        let a = OpId::from([0x01; 32]);
+        let b = OpId::from([0x02; 32]);
+        let c = OpId::from([0x03; 32]);
+        let status = StatusBuilder::new()
+            .failure(Failure::ConfidentialSeal(Opout::new(a, 1, 0)))
+            .failure(Failure::ConfidentialSeal(Opout::new(b, 1, 2)))
+            .failure(Failure::OperationAbsent(c))
+            .info(Info::UncheckableConfidentialState(b, 1))
+            .info(Info::UncheckableConfidentialState(c, 2))
+            .finish();
+
+        assert_eq!(status.uncheckable_ops(), bset! { a, b, c });
+        assert!(Status::new().uncheckable_ops().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn ndjson() {
+        let opid = OpId::from([0xEF; 32]);
+        let status = StatusBuilder::new()
+            .failure(Failure::OperationAbsent(opid))
+            .failure(Failure::Custom(s!("multi\nline")))
+            .warning(Warning::Custom(s!("warning")))
+            .info(Info::Custom(s!("info")))
+            .finish();
+
+        let ndjson = status.to_ndjson();
+        assert!(ndjson.ends_with('\n'));
+        let lines = ndjson
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["kind"], "failure");
+        assert_eq!(lines[0]["code"], Failure::OperationAbsent(opid).code());
+        assert_eq!(lines[0]["ops"], serde_json::json!([opid.to_string()]));
+        assert_eq!(lines[1]["message"], "multi\nline");
+        assert_eq!(lines[2]["kind"], "warning");
+        assert!(lines[2].get("code").is_none());
+        assert_eq!(lines[3]["kind"], "info");
+
+        assert_eq!(Status::new().to_ndjson(), "");
+    }
+
+    #[test]
+    fn labeled() {
+        let opid = OpId::from([0xEF; 32]);
+        let txid = Txid::from([0xAB; 32]);
+        let shared = Failure::OperationAbsent(opid);
+
+        let mut labeled = LabeledStatus::new();
+        labeled.merge("first", Status::with_failure(shared.clone()));
+        labeled.merge(
+            s!("second"),
+            StatusBuilder::new()
+                .failure(Failure::SealNoWitnessTx(txid))
+                .failure(shared.clone())
+                .warning(Warning::Custom(s!("warning")))
+                .finish(),
+        );
+
+        let status = labeled.status();
+        assert_eq!(status.failures.len(), 3);
+        assert_eq!(status.failures[1], Failure::SealNoWitnessTx(txid));
+        assert_eq!(labeled.origin(0), Some("first"));
+        assert_eq!(labeled.origin(1), Some("second"));
+        assert_eq!(labeled.origin(2), Some("second"));
+        assert_eq!(labeled.origin(3), None);
+        assert_eq!(labeled.failures_from("first").collect::<Vec<_>>(), vec![&shared]);
+        assert_eq!(labeled.failures_from("second").count(), 2);
+        assert_eq!(labeled.into_status().warnings.len(), 1);
+    }
+
+    #[test]
+    fn retain() {
+        let opid = OpId::from([0xEF; 32]);
+        let txid = Txid::from([0xAB; 32]);
+        let mut status = StatusBuilder::new()
+            .failure(Failure::SealNoWitnessTx(txid))
+            .failure(Failure::OperationAbsent(opid))
+            .failure(Failure::MetadataCommitmentMismatch(opid))
+            .warning(Warning::Custom(s!("warning")))
+            .info(Info::Custom(s!("info")))
+            .finish();
+
+        status.retain_failures(|f| f.severity() == Severity::Consensus);
+        assert_eq!(status.failures, vec![Failure::MetadataCommitmentMismatch(opid)]);
+        assert_eq!(status.validity(), Validity::Invalid);
+
+        status.retain_warnings(|_| false);
+        status.retain_info(|_| true);
+        assert!(status.warnings.is_empty());
+        assert_eq!(status.info.len(), 1);
+
+        status.retain_failures(|f| f.severity() == Severity::Environmental);
+        assert_eq!(status.validity(), Validity::Valid);
+    }
+
+    #[test]
+    fn summary() {
+        let txid = Txid::from([0xAB; 32]);
+        let status = StatusBuilder::new()
+            .failure(Failure::SealNoWitnessTx(txid))
+            .warning(Warning::Custom(s!("first")))
+            .warning(Warning::Custom(s!("second")))
+            .info(Info::Custom(s!("info")))
+            .unresolved_txid(txid)
+            .unmined_terminal(txid)
+            .unmined_terminal(Txid::from([0xCD; 32]))
+            .finish();
+
+        assert_eq!(status.summary(), StatusSummary {
+            validity: Validity::UnresolvedTransactions,
+            failures: 1,
+            warnings: 2,
+            info: 1,
+            unresolved: 1,
+            unmined_endpoints: 2,
+        });
+        assert_eq!(Status::new().summary().validity, Validity::Valid);
+    }
+
+    #[test]
+    fn sorted() {
+        let a = OpId::from([0x01; 32]);
+        let b = OpId::from([0x02; 32]);
+        let txid = Txid::from([0xAB; 32]);
+
+        let failures = [
+            Failure::Custom(s!("b")),
+            Failure::SealNoWitnessTx(txid),
+            Failure::OperationAbsent(b),
+            Failure::Custom(s!("a")),
+            Failure::OperationAbsent(a),
+        ];
+        let warnings =
+            [Warning::Custom(s!("b")), Warning::ExcessiveOperation(b), Warning::Custom(s!("a"))];
+        let status = |shift: usize| {
+            let mut status = Status::new();
+            let mut failures = failures.clone();
+            let mut warnings = warnings.clone();
+            let mut info = [0, 1, 2].map(|i| Info::Custom(i.to_string()));
+            failures.rotate_left(shift % failures.len());
+            warnings.rotate_left(shift % warnings.len());
+            info.rotate_right(shift % info.len());
+            for failure in failures {
+                status.add_failure(failure);
+            }
+            for warning in warnings {
+                status.add_warning(warning);
+            }
+            for info in info {
+                status.add_info(info);
+            }
+            status.sorted()
+        };
+
+        let canonical = status(0);
+        assert_eq!(canonical, status(1));
+        assert_eq!(canonical, status(2));
+        assert_eq!(canonical.failures, vec![
+            Failure::OperationAbsent(a),
+            Failure::OperationAbsent(b),
+            Failure::SealNoWitnessTx(txid),
+            Failure::Custom(s!("a")),
+            Failure::Custom(s!("b")),
+        ]);
+        assert_eq!(canonical.warnings, vec![
+            Warning::Custom(s!("a")),
+            Warning::Custom(s!("b")),
+            Warning::ExcessiveOperation(b),
+        ]);
+        assert_eq!(canonical.info, vec![
+            Info::Custom(s!("0")),
+            Info::Custom(s!("1")),
+            Info::Custom(s!("2")),
+        ]);
+    }
+
+    #[test]
+    fn failures_for_op() {
+        let opid = OpId::from([0x01; 32]);
+        let prev_id = OpId::from([0x02; 32]);
+        let other = OpId::from([0x03; 32]);
+
+        let status = StatusBuilder::new()
+            .failure(Failure::OperationAbsent(opid))
+            .failure(Failure::NoPrevOut(other, Opout::new(prev_id, 1, 0)))
+            .failure(Failure::StateTypeMismatch {
+                opid: prev_id,
+                state_type: 1,
+                expected: StateType::Void,
+                found: StateType::Fungible,
+            })
+            .failure(Failure::ScriptFailure(ScriptError {
+                op: opid,
+                entry_offset: 0,
+                isa_error: s!("failure"),
+                exit_code: None,
+            }))
+            .failure(Failure::SealNoWitnessTx(Txid::from([0xAB; 32])))
+            .finish();
+
+        assert_eq!(status.failures[1].involved_ops(), vec![other, prev_id]);
+        assert_eq!(status.failures_for_op(opid), vec![&status.failures[0], &status.failures[3]]);
+        assert_eq!(status.failures_for_op(prev_id), vec![&status.failures[1], &status.failures[2]]);
+        assert_eq!(status.failures_for_op(other), vec![&status.failures[1]]);
+        assert!(status.failures_for_op(OpId::from([0x04; 32])).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn error_source() {
+        use std::error::Error;
+
+        let opid = OpId::from([0xEF; 32]);
+        let txid = Txid::from([0xAB; 32]);
+
+        let failure: Box<dyn Error> = Box::new(Failure::AnchorInvalid(
+            opid,
+            txid,
+            anchor::VerifyError::Mpc(mpc::InvalidProof),
+        ));
+        assert_eq!(
+            failure
+                .source()
+                .and_then(|err| err.downcast_ref::<anchor::VerifyError>()),
+            Some(&anchor::VerifyError::Mpc(mpc::InvalidProof))
+        );
+
+        let failure =
+            Failure::SealInvalid(opid, txid, seals::txout::VerifyError::InconsistentCloseMethod);
+        assert!(matches!(
+            failure
+                .source()
+                .and_then(|err| err.downcast_ref::<seals::txout::VerifyError>()),
+            Some(seals::txout::VerifyError::InconsistentCloseMethod)
+        ));
+
+        assert!(Failure::OperationAbsent(opid).source().is_none());
+    }
+
+    #[test]
+    fn strict() {
+        let warning = Warning::ExcessiveOperation(OpId::from([0xEF; 32]));
+        let status = StatusBuilder::new().warning(warning.clone()).finish();
+        assert_eq!(status.validity(), Validity::Valid);
+
+        let strict = status.clone().into_strict();
+        assert_eq!(strict.validity(), Validity::Invalid);
+        assert!(strict.warnings.is_empty());
+        assert_eq!(strict.failures, vec![Failure::Custom(warning.to_string())]);
+        assert_eq!(status.warnings, vec![warning]);
+    }
+
+    #[test]
+    fn script_error() {
+        let failure = Failure::ScriptFailure(ScriptError {
+            op: OpId::from([0xEF; 32]),
+            entry_offset: 0x1234,
+            isa_error: s!("insufficient inflation allowance"),
+            exit_code: Some(7),
+        });
+        assert!(failure
+            .to_string()
+            .ends_with(" is invalid: insufficient inflation allowance (exit code 7)"));
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&failure).unwrap();
+            let Failure::ScriptFailure(err) = serde_json::from_str(&json).unwrap() else {
+                panic!("script failure is not preserved by serde")
+            };
+            assert_eq!(err.entry_offset, 0x1234);
+            assert_eq!(Failure::ScriptFailure(err), failure);
+        }
+    }
+
+    #[test]
+    fn info_display() {
+        let opid = OpId::from([0xEF; 32]);
+        let msg = Info::UncheckableConfidentialState(opid, 5).to_string();
+        // Multi-line doc comments may be joined with line breaks
+        assert_eq!(
+            msg.split_whitespace().collect::<Vec<_>>().join(" "),
+            format!(
+                "operation {opid} contains state in assignment 5 which is confidential and thus \
+                 was not validated."
+            )
+        );
+        assert_eq!(Info::Custom(s!("custom")).to_string(), "custom");
+    }
+
+    #[test]
+    fn warning_display() {
+        let opid = OpId::from([0xEF; 32]);
+        let txid = Txid::from([0xAB; 32]);
+        let seal = GraphSeal::strict_dumb().conceal();
+        let opout = Opout::new(opid, 1, 0);
+
+        let msg = Warning::TerminalSealAbsent(opid, seal).to_string();
+        assert!(msg.contains(&opid.to_string()) && msg.contains(&seal.to_string()));
+        let msg = Warning::ExcessiveOperation(opid).to_string();
+        assert!(msg.contains(&opid.to_string()));
+        let msg = Warning::TerminalWitnessNotMined(txid).to_string();
+        assert!(msg.contains(&txid.to_string()));
+        let msg = Warning::SealOnSpentUtxo(opout, txid).to_string();
+        assert!(msg.contains(&opout.to_string()) && msg.contains(&txid.to_string()));
+        let msg = Warning::UnknownGlobalStateType(opid, 5).to_string();
+        assert!(msg.contains(&opid.to_string()));
+        let msg = Warning::UnknownAssignmentType(opid, 5).to_string();
+        assert!(msg.contains(&opid.to_string()));
+        assert_eq!(Warning::Custom(s!("custom")).to_string(), "custom");
+    }
+
+    #[test]
+    #[cfg(feature = "ansi")]
+    fn colored_string() {
+        let mut status = Status::with_failure(Failure::Custom(s!("failure")));
+        status.add_warning(Warning::Custom(s!("warning")));
+        status.add_info(Info::Custom(s!("info")));
+
+        let colored = status.to_colored_string(true);
+        assert!(colored.contains(&format!("{ANSI_RED}\"failure\"{ANSI_RESET}")));
+        assert!(colored.contains(&format!("{ANSI_YELLOW}\"warning\"{ANSI_RESET}")));
+        assert!(colored.contains(&format!("{ANSI_BLUE}\"info\"{ANSI_RESET}")));
+        assert_eq!(status.to_colored_string(false), status.to_string());
+    }
+
+    fn yaml_sample() -> (Status, Failure) {
+        let failure = Failure::SchemaMismatch {
+            expected: SchemaId::from([0xCD; 32]),
+            actual: SchemaId::from([0xAB; 32]),
+        };
+        let mut status = Status::with_failure(failure.clone());
+        status.add_warning(Warning::Custom(s!("first warning")));
+        status.add_warning(Warning::Custom(s!("second \"quoted\" warning")));
+        (status, failure)
+    }
+
+    #[test]
+    fn yaml_display() {
+        let (status, failure) = yaml_sample();
+        assert_eq!(
+            status.to_string(),
+            format!(
+                "validity: invalid
+failures:
+- \"{}\"
+warnings:
+- \"first warning\"
+- \"second \\\"quoted\\\" warning\"
+info: []
+unresolvedTxids: []
+unminedEndpointTxids: []
+",
+                failure.to_string().replace('\n', "\\n")
+            )
+        );
+    }
+
+    #[test]
+    fn anchor_error_kind() {
+        let opid = OpId::from([0xEF; 32]);
+        let txid = Txid::from([0xAB; 32]);
+        let failure =
+            Failure::AnchorInvalid(opid, txid, anchor::VerifyError::Mpc(mpc::InvalidProof));
+        assert!(failure.anchor_error().is_some());
+        assert_eq!(failure.anchor_error_kind(), Some(AnchorErrorKind::Mpc));
+
+        let failure = Failure::NotInAnchor(opid, txid);
+        assert_eq!(failure.anchor_error(), None);
+        assert_eq!(failure.anchor_error_kind(), None);
+    }
+
+    #[test]
+    fn remediation() {
+        let txid = Txid::from([0xAB; 32]);
+        let schema_id = SchemaId::from([0xCD; 32]);
+        assert_eq!(
+            Failure::SealNoWitnessTx(txid).remediation(),
+            Some("request the missing transaction from the sender")
+        );
+        assert_eq!(
+            Failure::SchemaMismatch {
+                expected: schema_id,
+                actual: schema_id,
+            }
+            .remediation(),
+            Some("this consignment uses an unsupported schema")
+        );
+        assert!(Failure::OperationAbsent(OpId::from([0xEF; 32]))
+            .remediation()
+            .is_some());
+        assert_eq!(Failure::SchemaBlankTransitionRedefined.remediation(), None);
+        assert_eq!(Failure::Custom(s!("custom")).remediation(), None);
+    }
+
+    #[test]
+    fn builder_validity() {
+        let txid = Txid::from([0xAB; 32]);
+
+        assert_eq!(StatusBuilder::new().finish().validity(), Validity::Valid);
+        assert_eq!(
+            StatusBuilder::new()
+                .warning(Warning::Custom(s!("warning")))
+                .info(Info::Custom(s!("info")))
+                .finish()
+                .validity(),
+            Validity::Valid
+        );
+        assert_eq!(
+            StatusBuilder::new()
+                .unmined_terminal(txid)
+                .finish()
+                .validity(),
+            Validity::UnminedTerminals
+        );
+        assert_eq!(
+            StatusBuilder::new()
+                .failure(Failure::Custom(s!("failure")))
+                .finish()
+                .validity(),
+            Validity::Invalid
+        );
+        assert_eq!(
+            StatusBuilder::new()
+                .failure(Failure::SealNoWitnessTx(txid))
+                .unresolved_txid(txid)
+                .finish()
+                .validity(),
+            Validity::UnresolvedTransactions
+        );
+    }
+}
+
+/// Uses status types through `core` and `alloc` paths only, checking they
+/// don't require `std`.
+#[cfg(test)]
+#[no_implicit_prelude]
+mod no_std_test {
+    use ::alloc::string::String;
+    use ::core::convert::From;
+    use ::core::iter::{IntoIterator, Iterator};
+    use ::core::{assert, assert_eq};
+
+    use super::{Failure, Info, Status, Validity, Warning};
+
+    #[test]
+    fn status_validity() {
+        let mut status = Status::new();
+        status.add_warning(Warning::Custom(String::from("warning")));
+        status.add_info(Info::Custom(String::from("info")));
+        assert_eq!(status.validity(), Validity::Valid);
+        assert!(status.validity().is_usable());
+
+        let failures = ::alloc::vec![Failure::Custom(String::from("failure"))];
+        status += failures.into_iter().collect::<Status>();
+        assert_eq!(status.validity(), Validity::Invalid);
+        assert_eq!(status.failures.len(), 1);
+        assert_eq!(status.warnings.len(), 1);
+        assert_eq!(status.info.len(), 1);
+    }
+}
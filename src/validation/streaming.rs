@@ -0,0 +1,311 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2023 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2023 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2023 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Incremental validation of consignments delivered by streaming transports
+//! one bundle at a time.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use super::{AnchoredBundle, CommitmentVerifier, DefaultVerifier, Failure, Status};
+use crate::{Assignments, ContractId, Extension, Genesis, GraphSeal, OpId, Operation, Transition};
+
+/// Validator accepting bundles of a consignment one by one, in an arbitrary
+/// order, and tracking operations which are referenced by the received
+/// bundles but were not received yet.
+///
+/// Each bundle is checked on arrival for the structural consistency and for
+/// the presence in its anchor; checks which require the whole history (schema
+/// validation, seal closing etc) must be performed with
+/// [`super::Validator`] once [`StreamingValidator::is_complete`] returns
+/// `true`.
+pub struct StreamingValidator<V: CommitmentVerifier = DefaultVerifier> {
+    contract_id: ContractId,
+    verifier: V,
+    received: BTreeSet<OpId>,
+    outstanding: BTreeSet<OpId>,
+}
+
+impl StreamingValidator {
+    /// Constructs validator for the contract with the given genesis.
+    pub fn new(genesis: &Genesis) -> Self { Self::with_verifier(genesis, DefaultVerifier) }
+}
+
+impl<V: CommitmentVerifier> StreamingValidator<V> {
+    /// Constructs validator for the contract with the given genesis, using a
+    /// custom commitment verifier.
+    pub fn with_verifier(genesis: &Genesis, verifier: V) -> Self {
+        StreamingValidator {
+            contract_id: genesis.contract_id(),
+            verifier,
+            received: bset! { genesis.id() },
+            outstanding: empty!(),
+        }
+    }
+
+    /// Validates a newly received anchored bundle and updates the set of the
+    /// outstanding operations.
+    pub fn feed_bundle(&mut self, anchored_bundle: &AnchoredBundle) -> Status {
+        let mut status = Status::new();
+        let AnchoredBundle { anchor, bundle } = anchored_bundle;
+        let bundle_id = bundle.bundle_id();
+
+        if !bundle.validate() {
+            status.add_failure(Failure::BundleInvalid(bundle_id));
+        }
+        for opid in bundle.mismatching_ids() {
+            status.add_failure(Failure::MetadataCommitmentMismatch(opid));
+        }
+        let in_anchor = self
+            .verifier
+            .verify_mpc(anchor, self.contract_id, bundle_id.into())
+            .is_some();
+
+        for transition in bundle.values().filter_map(|item| item.transition.as_ref()) {
+            let opid = transition.id();
+            if !in_anchor {
+                status.add_failure(Failure::NotInAnchor(opid, anchor.txid));
+            }
+            self.receive(opid, transition.inputs.iter().map(|input| input.prev_out.op));
+        }
+        status
+    }
+
+    /// Registers a newly received state extension. Extensions are not
+    /// anchored, so the only thing done is update of the set of the
+    /// outstanding operations.
+    pub fn feed_extension(&mut self, extension: &Extension) {
+        self.receive(extension.id(), extension.redeemed.iter().map(|(_, opid)| *opid));
+    }
+
+    /// Detects whether all operations referenced by the received bundles and
+    /// extensions were received.
+    pub fn is_complete(&self) -> bool { self.outstanding.is_empty() }
+
+    /// Returns ids of operations which are referenced by the received data,
+    /// but were not received yet.
+    pub fn outstanding(&self) -> &BTreeSet<OpId> { &self.outstanding }
+
+    fn receive(&mut self, opid: OpId, parents: impl IntoIterator<Item = OpId>) {
+        self.received.insert(opid);
+        self.outstanding.remove(&opid);
+        for parent in parents {
+            if !self.received.contains(&parent) {
+                self.outstanding.insert(parent);
+            }
+        }
+    }
+}
+
+/// Storage for the state of the operations evicted from the lookback window
+/// of [`LookbackValidator`].
+pub trait StateCache {
+    /// Stores state assigned by an operation evicted from the window.
+    fn store(&mut self, opid: OpId, state: Assignments<GraphSeal>);
+
+    /// Recovers previously stored state of an operation.
+    fn load(&self, opid: OpId) -> Option<Assignments<GraphSeal>>;
+}
+
+/// Cache which doesn't store anything, limiting the validation to the
+/// lookback window.
+impl StateCache for () {
+    fn store(&mut self, _: OpId, _: Assignments<GraphSeal>) {}
+
+    fn load(&self, _: OpId) -> Option<Assignments<GraphSeal>> { None }
+}
+
+impl StateCache for BTreeMap<OpId, Assignments<GraphSeal>> {
+    fn store(&mut self, opid: OpId, state: Assignments<GraphSeal>) { self.insert(opid, state); }
+
+    fn load(&self, opid: OpId) -> Option<Assignments<GraphSeal>> { self.get(&opid).cloned() }
+}
+
+/// Validator of the state transitions fed in the topological order, keeping
+/// in memory the state of only a bounded number of the most recent
+/// operations.
+///
+/// The state of older operations is moved to the [`StateCache`] and gets
+/// loaded from it on demand. Spending of an output which can be neither
+/// found in the window nor recovered from the cache results in
+/// [`Failure::LookbackExceeded`].
+pub struct LookbackValidator<Cache: StateCache = ()> {
+    window: usize,
+    recent: VecDeque<(OpId, Assignments<GraphSeal>)>,
+    cache: Cache,
+}
+
+impl LookbackValidator {
+    /// Constructs validator keeping state of at most `window` operations,
+    /// starting with the given genesis.
+    pub fn new(genesis: &Genesis, window: usize) -> Self { Self::with_cache(genesis, window, ()) }
+}
+
+impl<Cache: StateCache> LookbackValidator<Cache> {
+    /// Constructs validator keeping state of at most `window` operations,
+    /// starting with the given genesis and moving state of the older
+    /// operations to the `cache`.
+    pub fn with_cache(genesis: &Genesis, window: usize, cache: Cache) -> Self {
+        let mut validator = LookbackValidator {
+            window: window.max(1),
+            recent: VecDeque::with_capacity(window),
+            cache,
+        };
+        validator.push(genesis.id(), genesis.assignments.transmutate_seals());
+        validator
+    }
+
+    /// Checks that all the inputs of the transition spend known outputs of
+    /// the previous operations and adds the transition state to the window.
+    pub fn feed_transition(&mut self, transition: &Transition) -> Status {
+        let mut status = Status::new();
+        let opid = transition.id();
+        for input in &transition.inputs {
+            let prev_out = input.prev_out;
+            let Some(exists) = self.with_state(prev_out.op, |state| {
+                state
+                    .get(&prev_out.ty)
+                    .map_or(false, |assigns| prev_out.no < assigns.len_u16())
+            }) else {
+                status.add_failure(Failure::LookbackExceeded(prev_out.op));
+                continue;
+            };
+            if !exists {
+                status.add_failure(Failure::NoPrevOut(opid, prev_out));
+            }
+        }
+        self.push(opid, transition.assignments.clone());
+        status
+    }
+
+    /// Returns the cache with the state of the operations evicted from the
+    /// window.
+    pub fn into_cache(self) -> Cache { self.cache }
+
+    fn with_state<T>(&self, opid: OpId, f: impl FnOnce(&Assignments<GraphSeal>) -> T) -> Option<T> {
+        match self.recent.iter().find(|(id, _)| *id == opid) {
+            Some((_, state)) => Some(f(state)),
+            None => self.cache.load(opid).as_ref().map(f),
+        }
+    }
+
+    fn push(&mut self, opid: OpId, state: Assignments<GraphSeal>) {
+        if self.recent.len() == self.window {
+            let (evicted, state) = self.recent.pop_front().expect("window is never empty");
+            self.cache.store(evicted, state);
+        }
+        self.recent.push_back((opid, state));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::Confined;
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::{
+        Assign, AssignmentType, BundleItem, Input, Opout, TransitionBundle, TypedAssigns, VoidState,
+    };
+
+    fn anchored_bundle(parent: OpId) -> (OpId, AnchoredBundle) {
+        let transition = Transition {
+            inputs: Confined::try_from_iter([Input::with(Opout::new(parent, 0, 0))])
+                .unwrap()
+                .into(),
+            ..Transition::strict_dumb()
+        };
+        let opid = transition.id();
+        let item = BundleItem {
+            inputs: tiny_bset! { 0 },
+            transition: Some(transition),
+        };
+        (opid, AnchoredBundle {
+            anchor: strict_dumb!(),
+            bundle: TransitionBundle::from(tiny_bmap! { opid => item }),
+        })
+    }
+
+    #[test]
+    fn out_of_order() {
+        let genesis = Genesis::strict_dumb();
+        let (first_id, first) = anchored_bundle(genesis.id());
+        let (_, second) = anchored_bundle(first_id);
+
+        let mut validator = StreamingValidator::new(&genesis);
+        validator.feed_bundle(&second);
+        assert!(!validator.is_complete());
+        assert_eq!(validator.outstanding(), &bset! { first_id });
+
+        validator.feed_bundle(&first);
+        assert!(validator.is_complete());
+    }
+
+    fn chained(parent: Opout, ty: AssignmentType) -> Transition {
+        Transition {
+            inputs: Confined::try_from_iter([Input::with(parent)])
+                .unwrap()
+                .into(),
+            assignments: Confined::try_from(bmap! {
+                ty => TypedAssigns::Declarative(
+                    Confined::try_from(vec![Assign::revealed(strict_dumb!(), VoidState::default())])
+                        .unwrap(),
+                ),
+            })
+            .unwrap()
+            .into(),
+            ..Transition::strict_dumb()
+        }
+    }
+
+    #[test]
+    fn long_chain() {
+        let mut genesis = Genesis::strict_dumb();
+        genesis.assignments = Confined::try_from(bmap! {
+            0 => TypedAssigns::Declarative(
+                Confined::try_from(vec![Assign::revealed(strict_dumb!(), VoidState::default())])
+                    .unwrap(),
+            ),
+        })
+        .unwrap()
+        .into();
+
+        let mut validator = LookbackValidator::with_cache(&genesis, 4, BTreeMap::new());
+        let mut prev = Opout::new(genesis.id(), 0, 0);
+        for no in 1..=100 {
+            let transition = chained(prev, no);
+            assert!(validator.feed_transition(&transition).failures.is_empty());
+            prev = Opout::new(transition.id(), no, 0);
+        }
+
+        let mut validator = LookbackValidator::new(&genesis, 4);
+        let mut prev = Opout::new(genesis.id(), 0, 0);
+        for no in 1..=100 {
+            let transition = chained(prev, no);
+            assert!(validator.feed_transition(&transition).failures.is_empty());
+            prev = Opout::new(transition.id(), no, 0);
+        }
+        let spending_genesis = chained(Opout::new(genesis.id(), 0, 0), 0);
+        assert_eq!(validator.feed_transition(&spending_genesis).failures, vec![
+            Failure::LookbackExceeded(genesis.id())
+        ]);
+    }
+}
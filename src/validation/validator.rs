@@ -20,22 +20,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::mem;
+use std::sync::Arc;
 
 use bp::dbc::Anchor;
 use bp::seals::txout::{TxPtr, Witness};
-use bp::{Tx, Txid};
+use bp::{Chain, Outpoint, Tx, Txid};
 use commit_verify::mpc;
 use single_use_seals::SealWitness;
 
-use super::status::{Failure, Warning};
-use super::{ConsignmentApi, Status, Validity, VirtualMachine};
+use super::model::validate_non_zero_values;
+use super::status::{CheckKind, Failure, Info, Warning};
+use super::{
+    CommitmentVerifier, ConsignmentApi, DefaultVerifier, Status, UnknownTypePolicy, Validity,
+    VirtualMachine,
+};
 use crate::contract::Opout;
 use crate::validation::AnchoredBundle;
 use crate::vm::AluRuntime;
 use crate::{
-    BundleId, ContractId, OpId, OpRef, Operation, Schema, SchemaId, SchemaRoot, Script, SubSchema,
-    Transition, TransitionBundle, TypedAssigns,
+    AssignmentType, BundleId, ContractId, OpId, OpRef, Operation, Schema, SchemaId, SchemaRoot,
+    Script, ScriptComplexity, SubSchema, Transition, TransitionBundle, TypedAssigns,
 };
 
 #[derive(Clone, Debug, Display, Error, From)]
@@ -47,8 +54,232 @@ pub enum TxResolverError {
     Other(Txid, String),
 }
 
+impl From<TxResolverError> for Failure {
+    fn from(err: TxResolverError) -> Self {
+        match err {
+            TxResolverError::Unknown(txid) => Failure::SealNoWitnessTx(txid),
+            TxResolverError::Other(txid, details) => Failure::ResolverError(txid, details),
+        }
+    }
+}
+
 pub trait ResolveTx {
     fn resolve_tx(&self, txid: Txid) -> Result<Tx, TxResolverError>;
+
+    /// Checks whether a transaction output is already spent.
+    ///
+    /// Resolvers which do not track the UTXO set should keep the default
+    /// implementation, which always reports outputs as unspent.
+    fn is_spent(&self, outpoint: Outpoint) -> Result<bool, TxResolverError> {
+        let _ = outpoint;
+        Ok(false)
+    }
+
+    /// Returns the network from which the resolver retrieves transactions.
+    ///
+    /// Resolvers which are not bound to a specific network should keep the
+    /// default implementation returning `None`, which disables the check that
+    /// witness transactions belong to the network declared by the genesis.
+    fn chain(&self) -> Option<Chain> { None }
+}
+
+/// In-memory resolver, knowing only the transactions it contains.
+impl ResolveTx for BTreeMap<Txid, Tx> {
+    fn resolve_tx(&self, txid: Txid) -> Result<Tx, TxResolverError> {
+        self.get(&txid)
+            .cloned()
+            .ok_or(TxResolverError::Unknown(txid))
+    }
+}
+
+/// Registry of schemata, providing them by their ids.
+pub trait SchemaResolver {
+    fn schema(&self, id: SchemaId) -> Option<SubSchema>;
+}
+
+impl SchemaResolver for BTreeMap<SchemaId, SubSchema> {
+    fn schema(&self, id: SchemaId) -> Option<SubSchema> { self.get(&id).cloned() }
+}
+
+/// Cache of validation results, keyed by the consignment integrity hash (see
+/// [`ConsignmentApi::integrity_hash`]).
+pub trait ValidationCache {
+    /// Returns cached validation status for a consignment with the given
+    /// integrity hash, if any.
+    fn cached_status(&self, integrity_hash: &[u8; 32]) -> Option<Status>;
+
+    /// Stores validation status of a consignment with the given integrity
+    /// hash.
+    fn cache_status(&mut self, integrity_hash: [u8; 32], status: Status);
+}
+
+impl ValidationCache for BTreeMap<[u8; 32], Status> {
+    fn cached_status(&self, integrity_hash: &[u8; 32]) -> Option<Status> {
+        self.get(integrity_hash).cloned()
+    }
+
+    fn cache_status(&mut self, integrity_hash: [u8; 32], status: Status) {
+        self.insert(integrity_hash, status);
+    }
+}
+
+/// Observer of the validation progress, notified about validation events as
+/// they happen. All the methods do nothing by default.
+pub trait ValidationObserver {
+    /// Called once an operation has been validated against the schema.
+    fn on_operation_validated(&mut self, op: OpId) { let _ = op; }
+
+    /// Called before validation of the contract history branch ending in the
+    /// bundle `id` starts, or, when validating bundles independently, before
+    /// validation of the bundle itself.
+    fn on_bundle_started(&mut self, id: BundleId) { let _ = id; }
+
+    /// Called on each of the failures as soon as it gets discovered.
+    fn on_failure(&mut self, failure: &Failure) { let _ = failure; }
+}
+
+/// Observer counting validation events.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct CountingObserver {
+    pub operations: usize,
+    pub bundles: usize,
+    pub failures: usize,
+}
+
+impl ValidationObserver for CountingObserver {
+    fn on_operation_validated(&mut self, _: OpId) { self.operations += 1; }
+    fn on_bundle_started(&mut self, _: BundleId) { self.bundles += 1; }
+    fn on_failure(&mut self, _: &Failure) { self.failures += 1; }
+}
+
+/// Options of the consignment validation performed by
+/// [`Validator::validate_with`]. Default options produce the same validation
+/// as [`Validator::validate`].
+#[derive(Default)]
+pub struct ValidationOptions<'a> {
+    verifier: Option<Box<dyn CommitmentVerifier + 'a>>,
+    schemata: Option<&'a dyn SchemaResolver>,
+    cache: Option<&'a mut dyn ValidationCache>,
+    policy: UnknownTypePolicy,
+    complexity_cap: Option<ScriptComplexity>,
+    non_zero: BTreeSet<AssignmentType>,
+    verbose: bool,
+    per_bundle: bool,
+    on_failure: Option<&'a mut dyn FnMut(&Failure)>,
+    observer: Option<&'a mut dyn ValidationObserver>,
+    #[cfg(feature = "vm-debug")]
+    registers: Option<&'a mut crate::vm::RegisterTrace>,
+}
+
+impl<'a> ValidationOptions<'a> {
+    pub fn new() -> Self { Self::default() }
+
+    /// Routes all cryptographic commitment checks (bulletproofs, Pedersen
+    /// commitment sums, MPC and DBC anchor commitments) through the provided
+    /// `verifier` instead of [`DefaultVerifier`].
+    pub fn verifier(mut self, verifier: impl CommitmentVerifier + 'a) -> Self {
+        self.verifier = Some(Box::new(verifier));
+        self
+    }
+
+    /// Validates the consignment against the schema declared by the contract
+    /// genesis, retrieved from the `schemata` registry, rather than the schema
+    /// provided by the consignment. The validation fails with
+    /// [`Failure::SchemaUnknown`] if the registry doesn't know the schema.
+    pub fn schemata(mut self, schemata: &'a dyn SchemaResolver) -> Self {
+        self.schemata = Some(schemata);
+        self
+    }
+
+    /// Returns validation status from the `cache` if it already contains the
+    /// status for a consignment with the same integrity hash, without
+    /// accessing the resolver. Otherwise, the validation status is stored in
+    /// the cache.
+    ///
+    /// The cache is keyed by the consignment only, so it must not be shared
+    /// between validations with different options.
+    pub fn cache(mut self, cache: &'a mut dyn ValidationCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Handles global and owned state of types unknown to the schema
+    /// according to the provided `policy`.
+    pub fn policy(mut self, policy: UnknownTypePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Fails each operation with [`Failure::ScriptFailure`] if the schema
+    /// script complexity exceeds the provided `cap` or can't be computed.
+    ///
+    /// The cap is a policy of the validating party and not a consensus rule,
+    /// so it is not enforced by default. It bounds the script code size only,
+    /// not the number of executed instructions (see [`Script::complexity`]).
+    pub fn complexity_cap(mut self, cap: ScriptComplexity) -> Self {
+        self.complexity_cap = Some(cap);
+        self
+    }
+
+    /// Fails with [`Failure::ZeroValueAssignment`] on each revealed
+    /// zero-value fungible assignment of the `non_zero` types.
+    ///
+    /// Since the schema itself can't express that an assignment type forbids
+    /// zero values without changing its consensus commitment, the set of such
+    /// types is provided by the caller.
+    pub fn non_zero(mut self, non_zero: BTreeSet<AssignmentType>) -> Self {
+        self.non_zero = non_zero;
+        self
+    }
+
+    /// Records [`Info::CheckPassed`] for each successful schema, anchor, seal
+    /// and bitcoin commitment check, producing a complete audit log of what
+    /// was verified.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Validates each of the anchored bundles of the consignment
+    /// independently: each of the bundle transitions gets validated against
+    /// the schema and its anchor and witness transaction are checked. The
+    /// transition graph is not traversed, so operations not included into
+    /// the bundles (apart from genesis) are not validated.
+    ///
+    /// The returned failures are sorted by the ids of the operations they
+    /// refer to.
+    pub fn per_bundle(mut self, per_bundle: bool) -> Self {
+        self.per_bundle = per_bundle;
+        self
+    }
+
+    /// Invokes `on_failure` callback for each failure as soon as it gets
+    /// discovered, in the order of their discovery. The returned status
+    /// still contains all the failures.
+    ///
+    /// NB: [`Failure::SealNoWitnessTx`] for the terminal witness transactions
+    /// gets reported by the callback, but later gets replaced in the returned
+    /// status with [`Warning::TerminalWitnessNotMined`].
+    pub fn on_failure(mut self, on_failure: &'a mut dyn FnMut(&Failure)) -> Self {
+        self.on_failure = Some(on_failure);
+        self
+    }
+
+    /// Notifies the `observer` about the validation progress.
+    pub fn observer(mut self, observer: &'a mut dyn ValidationObserver) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Stores final state of the VM registers after running schema scripts
+    /// for each of the validated operations into `registers`.
+    ///
+    /// Intended for debugging schema scripts.
+    #[cfg(feature = "vm-debug")]
+    pub fn registers(mut self, registers: &'a mut crate::vm::RegisterTrace) -> Self {
+        self.registers = Some(registers);
+        self
+    }
 }
 
 pub struct Validator<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx> {
@@ -59,19 +290,62 @@ pub struct Validator<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx> {
     schema_id: SchemaId,
     genesis_id: OpId,
     contract_id: ContractId,
-    anchor_index: BTreeMap<OpId, &'consignment Anchor<mpc::MerkleProof>>,
+    anchor_index: Arc<BTreeMap<OpId, &'consignment Anchor<mpc::MerkleProof>>>,
     end_transitions: Vec<(&'consignment Transition, BundleId)>,
     validation_index: BTreeSet<OpId>,
     anchor_validation_index: BTreeSet<OpId>,
 
     vm: Box<dyn VirtualMachine + 'consignment>,
+    verifier: Box<dyn CommitmentVerifier + 'consignment>,
     resolver: &'resolver R,
+    verbose: bool,
+    policy: UnknownTypePolicy,
+    non_zero: BTreeSet<AssignmentType>,
+    on_failure: Option<&'consignment mut dyn FnMut(&Failure)>,
+    observer: Option<&'consignment mut dyn ValidationObserver>,
+    reported_failures: usize,
+    #[cfg(feature = "rayon")]
+    deferred: Option<Vec<Check<'consignment>>>,
+}
+
+/// Operation checks collected during the contract graph traversal to be run
+/// concurrently; see [`Validator::validate_bundles_parallel`].
+#[cfg(feature = "rayon")]
+enum Check<'consignment> {
+    Operation(OpRef<'consignment>),
+    Transition(&'consignment Transition, BundleId, &'consignment Anchor<mpc::MerkleProof>),
+}
+
+/// Part of the [`ValidationOptions`] shared by the validators running the
+/// checks of a parallel validation.
+#[cfg(feature = "rayon")]
+struct WorkerOptions<'a> {
+    verifier: &'a (dyn CommitmentVerifier + Sync),
+    policy: UnknownTypePolicy,
+    complexity_cap: Option<ScriptComplexity>,
+    non_zero: BTreeSet<AssignmentType>,
+    verbose: bool,
 }
 
 impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
     Validator<'consignment, 'resolver, C, R>
 {
-    fn init(consignment: &'consignment C, resolver: &'resolver R) -> Self {
+    fn init(
+        consignment: &'consignment C,
+        resolver: &'resolver R,
+        options: ValidationOptions<'consignment>,
+    ) -> Self {
+        let ValidationOptions {
+            verifier,
+            policy,
+            complexity_cap,
+            non_zero,
+            verbose,
+            on_failure,
+            observer,
+            ..
+        } = options;
+
         // We use validation status object to store all detected failures and
         // warnings
         let mut status = Status::default();
@@ -81,6 +355,9 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
         let contract_id = consignment.genesis().contract_id();
         let schema_id = consignment.genesis().schema_id;
 
+        // [VALIDATION]: Making sure no bundles were spliced from other contracts
+        status += consignment.verify_single_contract();
+
         // Create indexes
         let mut anchor_index = BTreeMap::<OpId, &Anchor<mpc::MerkleProof>>::new();
         for AnchoredBundle {
@@ -91,6 +368,9 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
             if !TransitionBundle::validate(bundle) {
                 status.add_failure(Failure::BundleInvalid(bundle.bundle_id()));
             }
+            for opid in bundle.mismatching_ids() {
+                status.add_failure(Failure::MetadataCommitmentMismatch(opid));
+            }
             for transition in bundle.values().filter_map(|item| item.transition.as_ref()) {
                 let opid = transition.id();
                 anchor_index.insert(opid, anchor);
@@ -134,11 +414,7 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
         // Index used to avoid repeated validations of the same anchor+transition pairs
         let anchor_validation_index = BTreeSet::<OpId>::new();
 
-        let vm = match &consignment.schema().script {
-            Script::AluVM(lib) => {
-                Box::new(AluRuntime::new(lib)) as Box<dyn VirtualMachine + 'consignment>
-            }
-        };
+        let vm = Self::script_vm(Cow::Borrowed(&consignment.schema().script), complexity_cap);
 
         Self {
             consignment,
@@ -146,15 +422,70 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
             schema_id,
             genesis_id,
             contract_id,
-            anchor_index,
+            anchor_index: Arc::new(anchor_index),
             end_transitions,
             validation_index,
             anchor_validation_index,
             vm,
+            verifier: verifier.unwrap_or_else(|| Box::new(DefaultVerifier)),
+            resolver,
+            verbose,
+            policy,
+            non_zero,
+            on_failure,
+            observer,
+            reported_failures: 0,
+            #[cfg(feature = "rayon")]
+            deferred: None,
+        }
+    }
+
+    /// Constructs validator running the checks collected by another one,
+    /// sharing its anchor index and validating operations against `schema`.
+    #[cfg(feature = "rayon")]
+    fn worker(
+        consignment: &'consignment C,
+        resolver: &'resolver R,
+        schema: &'consignment SubSchema,
+        anchor_index: Arc<BTreeMap<OpId, &'consignment Anchor<mpc::MerkleProof>>>,
+        options: &WorkerOptions<'consignment>,
+    ) -> Self {
+        let genesis = consignment.genesis();
+        let vm = Self::script_vm(Cow::Borrowed(&schema.script), options.complexity_cap);
+        Self {
+            consignment,
+            status: Status::default(),
+            schema_id: genesis.schema_id,
+            genesis_id: genesis.id(),
+            contract_id: genesis.contract_id(),
+            anchor_index,
+            end_transitions: vec![],
+            validation_index: none!(),
+            anchor_validation_index: none!(),
+            vm,
+            verifier: Box::new(options.verifier),
             resolver,
+            verbose: options.verbose,
+            policy: options.policy,
+            non_zero: options.non_zero.clone(),
+            on_failure: None,
+            observer: None,
+            reported_failures: 0,
+            deferred: None,
         }
     }
 
+    fn script_vm(
+        script: Cow<'consignment, Script>,
+        cap: Option<ScriptComplexity>,
+    ) -> Box<dyn VirtualMachine + 'consignment> {
+        let script = match script {
+            Cow::Borrowed(Script::AluVM(script)) => Cow::Borrowed(script),
+            Cow::Owned(Script::AluVM(script)) => Cow::Owned(script),
+        };
+        Box::new(AluRuntime::with_script(script, cap))
+    }
+
     /// Validation procedure takes a schema object, root schema (if any),
     /// resolver function returning transaction and its fee for a given
     /// transaction id, and returns a validation object listing all detected
@@ -165,25 +496,395 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
     /// consignment data. This can help it debugging and detecting all problems
     /// with the consignment.
     pub fn validate(consignment: &'consignment C, resolver: &'resolver R) -> Status {
-        let mut validator = Validator::init(consignment, resolver);
+        Self::validate_with(consignment, resolver, ValidationOptions::default())
+    }
+
+    /// Runs the same validation procedure as [`Validator::validate`],
+    /// customized with the provided `options`.
+    pub fn validate_with(
+        consignment: &'consignment C,
+        resolver: &'resolver R,
+        mut options: ValidationOptions<'consignment>,
+    ) -> Status {
+        let cache = options.cache.take();
+        Self::cached(consignment, cache, || {
+            let schemata = options.schemata.take();
+            let per_bundle = options.per_bundle;
+            let complexity_cap = options.complexity_cap;
+            #[cfg(feature = "vm-debug")]
+            let registers = options.registers.take();
+
+            let mut validator = Validator::init(consignment, resolver, options);
+            let schema = match validator.select_schema(schemata, complexity_cap) {
+                Ok(schema) => schema,
+                Err(status) => return status,
+            };
+            let mut status = if per_bundle {
+                validator.run_per_bundle(&schema)
+            } else {
+                validator.run_with(&schema);
+                mem::take(&mut validator.status)
+            };
+            // Operations are validated independently, and their statuses are
+            // appended without collapsing identical entries until here
+            status.dedup();
+
+            #[cfg(feature = "vm-debug")]
+            if let Some(registers) = registers {
+                *registers = validator.vm.take_registers();
+            }
+            status
+        })
+    }
 
-        validator.validate_schema(consignment.schema());
+    /// Returns validation status from the `cache`, if it is present there;
+    /// otherwise runs the validation and stores its status in the `cache`.
+    fn cached(
+        consignment: &'consignment C,
+        cache: Option<&'consignment mut dyn ValidationCache>,
+        validate: impl FnOnce() -> Status,
+    ) -> Status {
+        let integrity_hash = cache.is_some().then(|| consignment.integrity_hash());
+        if let (Some(cache), Some(integrity_hash)) = (cache.as_deref(), &integrity_hash) {
+            if let Some(status) = cache.cached_status(integrity_hash) {
+                return status;
+            }
+        }
+        let status = validate();
+        if let (Some(cache), Some(integrity_hash)) = (cache, integrity_hash) {
+            cache.cache_status(integrity_hash, status.clone());
+        }
+        status
+    }
+
+    /// Returns the schema to validate the consignment against: either the
+    /// consignment schema or, if `schemata` are provided, the schema declared
+    /// by the contract genesis, in which case the validator VM is set up to
+    /// run the script of that schema.
+    fn select_schema(
+        &mut self,
+        schemata: Option<&dyn SchemaResolver>,
+        complexity_cap: Option<ScriptComplexity>,
+    ) -> Result<Cow<'consignment, SubSchema>, Status> {
+        let Some(schemata) = schemata else {
+            return Ok(Cow::Borrowed(self.consignment.schema()));
+        };
+        let schema_id = self.consignment.genesis().declared_schema_id();
+        let Some(schema) = schemata.schema(schema_id) else {
+            return Err(Status::with_failure(Failure::SchemaUnknown(schema_id)));
+        };
+        self.vm = Self::script_vm(Cow::Owned(schema.script.clone()), complexity_cap);
+        Ok(Cow::Owned(schema))
+    }
+
+    /// Runs the same validation procedure as [`Validator::validate`] with
+    /// the default options, checking the operations concurrently. Produces
+    /// the same status as the serial version, up to the order of its entries
+    /// (see [`Status::sort`]).
+    ///
+    /// The contract graph is traversed by a single thread, which collects the
+    /// schema, anchor and witness checks of the operations; the checks are
+    /// then run in parallel, after which the terminal seals are processed as
+    /// in the serial version. To customize the validation, use
+    /// [`Validator::validate_bundles_parallel_with_options`].
+    #[cfg(feature = "rayon")]
+    pub fn validate_bundles_parallel(consignment: &'consignment C, resolver: &'resolver R) -> Status
+    where
+        C: Sync,
+        R: Sync,
+    {
+        Self::validate_bundles_parallel_with(consignment, resolver, None)
+    }
+
+    /// Runs [`Validator::validate_bundles_parallel`], notifying the
+    /// `observer` about the validation progress. Bundles are reported during
+    /// the graph traversal; validated operations and failures get reported
+    /// once the parallel checks are completed.
+    #[cfg(feature = "rayon")]
+    pub fn validate_bundles_parallel_with(
+        consignment: &'consignment C,
+        resolver: &'resolver R,
+        observer: Option<&'consignment mut dyn ValidationObserver>,
+    ) -> Status
+    where
+        C: Sync,
+        R: Sync,
+    {
+        let options = ValidationOptions {
+            observer,
+            ..default!()
+        };
+        Self::validate_bundles_parallel_with_options(
+            consignment,
+            resolver,
+            &DefaultVerifier,
+            options,
+        )
+    }
+
+    /// Runs [`Validator::validate_bundles_parallel`], customized with the
+    /// provided `options`. Since the commitment checks are run by multiple
+    /// threads, the `verifier` is provided separately from the options and
+    /// must be [`Sync`]. Callbacks of the options are invoked by the calling
+    /// thread only.
+    ///
+    /// # Panics
+    ///
+    /// If the `options` contain a verifier, request the per-bundle validation
+    /// or tracing of the VM registers, none of which are supported by the
+    /// parallel validation.
+    #[cfg(feature = "rayon")]
+    pub fn validate_bundles_parallel_with_options(
+        consignment: &'consignment C,
+        resolver: &'resolver R,
+        verifier: &'consignment (dyn CommitmentVerifier + Sync),
+        mut options: ValidationOptions<'consignment>,
+    ) -> Status
+    where
+        C: Sync,
+        R: Sync,
+    {
+        assert!(
+            options.verifier.is_none(),
+            "parallel validation verifier must be provided as a separate argument"
+        );
+        assert!(!options.per_bundle, "per-bundle validation can't be run in parallel");
+        #[cfg(feature = "vm-debug")]
+        assert!(
+            options.registers.is_none(),
+            "VM registers can't be traced during parallel validation"
+        );
+
+        let cache = options.cache.take();
+        Self::cached(consignment, cache, || {
+            let schemata = options.schemata.take();
+            let worker_options = WorkerOptions {
+                verifier,
+                policy: options.policy,
+                complexity_cap: options.complexity_cap,
+                non_zero: options.non_zero.clone(),
+                verbose: options.verbose,
+            };
+            let mut validator = Validator::init(consignment, resolver, options);
+            validator.verifier = Box::new(verifier);
+            match validator.select_schema(schemata, worker_options.complexity_cap) {
+                Ok(schema) => validator.run_parallel(&schema, &worker_options),
+                Err(status) => status,
+            }
+        })
+    }
+
+    /// Traverses the contract graph and runs the collected operation checks
+    /// in parallel; see [`Validator::validate_bundles_parallel`].
+    #[cfg(feature = "rayon")]
+    fn run_parallel(&mut self, schema: &SubSchema, options: &WorkerOptions) -> Status
+    where
+        C: Sync,
+        R: Sync,
+    {
+        use rayon::prelude::*;
+
+        self.validate_schema(schema);
+        if self.status.validity() == Validity::Invalid {
+            self.report_failures();
+            return self.take_status();
+        }
+
+        self.deferred = Some(vec![]);
+        if !self.validate_graph(schema) {
+            self.report_failures();
+            return self.take_status();
+        }
+        let checks = self.deferred.take().unwrap_or_default();
+        let operations = checks
+            .iter()
+            .filter_map(|check| match check {
+                Check::Operation(operation) => Some(operation.id()),
+                Check::Transition(..) => None,
+            })
+            .collect::<Vec<_>>();
+        let (consignment, resolver) = (self.consignment, self.resolver);
+        let anchor_index = Arc::clone(&self.anchor_index);
+        self.status.append(
+            checks
+                .into_par_iter()
+                .map_init(
+                    || {
+                        Validator::worker(
+                            consignment,
+                            resolver,
+                            schema,
+                            Arc::clone(&anchor_index),
+                            options,
+                        )
+                    },
+                    |worker, check| {
+                        worker.run_check(schema, check);
+                        worker.take_status()
+                    },
+                )
+                .reduce(Status::new, |mut acc, status| {
+                    acc.append(status);
+                    acc
+                }),
+        );
+        self.status.dedup();
+        if let Some(observer) = self.observer.as_mut() {
+            for opid in operations {
+                observer.on_operation_validated(opid);
+            }
+        }
+        self.report_failures();
+        self.validate_terminals();
+        self.report_failures();
+        self.take_status()
+    }
+
+    #[cfg(feature = "rayon")]
+    fn run_check(&mut self, schema: &SubSchema, check: Check<'consignment>) {
+        match check {
+            Check::Operation(operation) => self.validate_operation(schema, operation),
+            Check::Transition(transition, bundle_id, anchor) => {
+                self.validate_anchored(transition, bundle_id, anchor)
+            }
+        }
+    }
+
+    /// Validates schema, genesis and each of the anchored bundles
+    /// independently; see [`ValidationOptions::per_bundle`].
+    fn run_per_bundle(&mut self, schema: &SubSchema) -> Status {
+        if !self.validate_prelude(schema) {
+            return self.take_status();
+        }
+        let consignment = self.consignment;
+        let mut status = self.take_status();
+        for bundle in consignment.anchored_bundles() {
+            status.append(self.validate_bundle(schema, bundle));
+        }
+        status.sort_failures();
+        status
+    }
+
+    /// Takes the status collected so far, leaving the empty one in its place.
+    fn take_status(&mut self) -> Status {
+        self.reported_failures = 0;
+        mem::take(&mut self.status)
+    }
+
+    /// Validates schema and genesis, returning whether it makes sense to
+    /// proceed with validation of the operations.
+    fn validate_prelude(&mut self, schema: &SubSchema) -> bool {
+        self.report_failures();
+        self.validate_schema(schema);
+        self.report_failures();
+        if self.status.validity() == Validity::Invalid {
+            return false;
+        }
+        if schema.schema_id() != self.schema_id {
+            self.status.add_failure(Failure::SchemaMismatch {
+                expected: self.schema_id,
+                actual: schema.schema_id(),
+            });
+            return false;
+        }
+        self.validate_operation(schema, OpRef::Genesis(self.consignment.genesis()));
+        true
+    }
+
+    /// Validates transitions of a single bundle, returning the status of
+    /// their validation.
+    fn validate_bundle(
+        &mut self,
+        schema: &SubSchema,
+        bundle: &'consignment AnchoredBundle,
+    ) -> Status {
+        let AnchoredBundle {
+            ref anchor,
+            ref bundle,
+        } = *bundle;
+        let bundle_id = bundle.bundle_id();
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_bundle_started(bundle_id);
+        }
+        for transition in bundle.values().filter_map(|item| item.transition.as_ref()) {
+            self.validate_operation(schema, OpRef::Transition(transition));
+            self.validate_anchored(transition, bundle_id, anchor);
+            self.report_failures();
+        }
+        self.take_status()
+    }
+
+    fn run_with(&mut self, schema: &SubSchema) {
+        self.report_failures();
+        self.validate_schema(schema);
+        self.report_failures();
         // We must return here, since if the schema is not valid there is no reason to
         // validate contract nodes against it: it will produce a plenty of errors
-        if validator.status.validity() == Validity::Invalid {
-            return validator.status;
+        if self.status.validity() == Validity::Invalid {
+            return;
         }
 
-        validator.validate_contract(consignment.schema());
-
-        // Done. Returning status report with all possible failures, issues, warnings
-        // and notifications about transactions we were unable to obtain.
-        validator.status
+        self.validate_contract(schema);
+        self.report_failures();
     }
 
     fn validate_schema(&mut self, schema: &SubSchema) { self.status += schema.verify(); }
 
+    /// Passes failures discovered since the last call to the failure
+    /// callback and the observer, if any.
+    fn report_failures(&mut self) {
+        if self.on_failure.is_none() && self.observer.is_none() {
+            return;
+        }
+        for failure in &self.status.failures[self.reported_failures..] {
+            if let Some(on_failure) = self.on_failure.as_mut() {
+                on_failure(failure);
+            }
+            if let Some(observer) = self.observer.as_mut() {
+                observer.on_failure(failure);
+            }
+        }
+        self.reported_failures = self.status.failures.len();
+    }
+
+    fn check_passed(&mut self, op: OpId, check: CheckKind) {
+        if self.verbose {
+            self.status.add_info(Info::CheckPassed { op, check });
+        }
+    }
+
+    fn validate_operation<Root: SchemaRoot>(
+        &mut self,
+        schema: &Schema<Root>,
+        operation: OpRef<'consignment>,
+    ) {
+        let mut status = schema.validate(
+            self.consignment,
+            operation,
+            self.vm.as_ref(),
+            self.verifier.as_ref(),
+            self.policy,
+        );
+        status += validate_non_zero_values(operation, &self.non_zero);
+        if status.failures.is_empty() {
+            self.check_passed(operation.id(), CheckKind::Schema);
+        }
+        self.status.append(status);
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_operation_validated(operation.id());
+        }
+        self.report_failures();
+    }
+
     fn validate_contract<Root: SchemaRoot>(&mut self, schema: &Schema<Root>) {
+        if self.validate_graph(schema) {
+            self.validate_terminals();
+        }
+    }
+
+    /// Validates genesis and the operation graph up to each of the
+    /// terminals, returning whether the schema matches the contract one.
+    fn validate_graph<Root: SchemaRoot>(&mut self, schema: &Schema<Root>) -> bool {
         // [VALIDATION]: Making sure that we were supplied with the schema
         //               that corresponds to the schema of the contract genesis
         if schema.schema_id() != self.schema_id {
@@ -194,15 +895,11 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
             // Unlike other failures, here we return immediatelly, since there is no point
             // to validate all consignment data against an invalid schema: it will result in
             // a plenty of meaningless errors
-            return;
+            return false;
         }
 
         // [VALIDATION]: Validate genesis
-        self.status += schema.validate(
-            self.consignment,
-            OpRef::Genesis(self.consignment.genesis()),
-            self.vm.as_ref(),
-        );
+        self.check_operation(schema, OpRef::Genesis(self.consignment.genesis()));
         self.validation_index.insert(self.genesis_id);
 
         // [VALIDATION]: Iterating over each endpoint, reconstructing operation
@@ -210,9 +907,26 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
         // NB: We are not aiming to validate the consignment as a whole, but instead
         // treat it as a superposition of subgraphs, one for each endpoint; and validate
         // them independently.
+        let mut started_bundles = BTreeSet::new();
         for (operation, bundle_id) in self.end_transitions.clone() {
+            if let Some(observer) = self.observer.as_mut() {
+                if started_bundles.insert(bundle_id) {
+                    observer.on_bundle_started(bundle_id);
+                }
+            }
             self.validate_branch(schema, operation, bundle_id);
         }
+        true
+    }
+
+    /// Checks terminal seals and witness transactions of the terminal
+    /// transitions, and reports excessive operations.
+    fn validate_terminals(&mut self) {
+        // [VALIDATION]: Checking that terminal seals are not defined over
+        //               already spent transaction outputs
+        for (operation, _) in self.end_transitions.clone() {
+            self.validate_terminal_seals(operation);
+        }
         // Replace missed (not yet mined) endpoint witness transaction failures
         // with a dedicated type
         for (operation, _) in &self.end_transitions {
@@ -224,13 +938,21 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
                     .position(|f| f == &Failure::SealNoWitnessTx(anchor.txid))
                 {
                     self.status.failures.remove(pos);
+                    if pos < self.reported_failures {
+                        self.reported_failures -= 1;
+                    }
                     self.status
                         .unresolved_txids
                         .retain(|txid| *txid != anchor.txid);
                     self.status.unmined_terminals.push(anchor.txid);
                     self.status
-                        .warnings
-                        .push(Warning::TerminalWitnessNotMined(anchor.txid));
+                        .add_warning(Warning::TerminalWitnessNotMined(anchor.txid));
+                }
+                if self.status.unmined_terminals.contains(&anchor.txid) {
+                    self.status.add_info(Info::TerminalPending {
+                        opid: operation.id(),
+                        txid: anchor.txid,
+                    });
                 }
             }
         }
@@ -243,6 +965,30 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
         }
     }
 
+    fn validate_terminal_seals(&mut self, transition: &'consignment Transition) {
+        let opid = transition.id();
+        let witness_txid = self.anchor_index.get(&opid).map(|anchor| anchor.txid);
+        for (ty, assigns) in transition.assignments.iter() {
+            for no in 0..assigns.len_u16() {
+                let Ok(Some(seal)) = assigns.revealed_seal_at(no) else {
+                    continue;
+                };
+                let txid = match (seal.txid, witness_txid) {
+                    (TxPtr::Txid(txid), _) => txid,
+                    (TxPtr::WitnessTx, Some(txid)) => txid,
+                    // Absence of the anchor is reported during branch validation
+                    (TxPtr::WitnessTx, None) => continue,
+                };
+                if let Ok(true) = self.resolver.is_spent(Outpoint::new(txid, seal.vout)) {
+                    // The seal can't be closed anymore, however this doesn't make
+                    // the contract history invalid, so we just warn the user
+                    self.status
+                        .add_warning(Warning::SealOnSpentUtxo(Opout::new(opid, *ty, no), txid));
+                }
+            }
+        }
+    }
+
     fn validate_branch<Root: SchemaRoot>(
         &mut self,
         schema: &Schema<Root>,
@@ -267,7 +1013,7 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
             // [VALIDATION]: Verify operation against the schema. Here we check only a single
             //               operation, not state evolution (it will be checked lately)
             if !self.validation_index.contains(&opid) {
-                self.status += schema.validate(self.consignment, operation, self.vm.as_ref());
+                self.check_operation(schema, operation);
                 self.validation_index.insert(opid);
             }
 
@@ -281,18 +1027,7 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
                     // have a corresponding anchor
                     if let Some(anchor) = self.anchor_index.get(&opid).cloned() {
                         if !self.anchor_validation_index.contains(&opid) {
-                            // Ok, now we have the `operation` and the `anchor`, let's do all
-                            // required checks
-
-                            // [VALIDATION]: Check that transition is committed into the anchor.
-                            //               This must be done with deterministic bitcoin
-                            // commitments &               LNPBP-4.
-                            if anchor.convolve(self.contract_id, bundle_id.into()).is_err() {
-                                self.status
-                                    .add_failure(Failure::NotInAnchor(opid, anchor.txid));
-                            }
-
-                            self.validate_transition(transition, bundle_id, anchor);
+                            self.check_anchored(transition, bundle_id, anchor);
                             self.anchor_validation_index.insert(opid);
                         }
                     } else {
@@ -339,7 +1074,64 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
                     }
                 }
             }
+            self.report_failures();
+        }
+    }
+
+    /// Validates the operation against the schema, or postpones the check
+    /// when the validator collects checks for a parallel run.
+    fn check_operation<Root: SchemaRoot>(
+        &mut self,
+        schema: &Schema<Root>,
+        operation: OpRef<'consignment>,
+    ) {
+        #[cfg(feature = "rayon")]
+        if let Some(deferred) = self.deferred.as_mut() {
+            deferred.push(Check::Operation(operation));
+            return;
+        }
+        self.validate_operation(schema, operation);
+    }
+
+    /// Validates the transition anchor and witness, or postpones the check
+    /// when the validator collects checks for a parallel run.
+    fn check_anchored(
+        &mut self,
+        transition: &'consignment Transition,
+        bundle_id: BundleId,
+        anchor: &'consignment Anchor<mpc::MerkleProof>,
+    ) {
+        #[cfg(feature = "rayon")]
+        if let Some(deferred) = self.deferred.as_mut() {
+            deferred.push(Check::Transition(transition, bundle_id, anchor));
+            return;
+        }
+        self.validate_anchored(transition, bundle_id, anchor);
+    }
+
+    fn validate_anchored(
+        &mut self,
+        transition: &'consignment Transition,
+        bundle_id: BundleId,
+        anchor: &'consignment Anchor<mpc::MerkleProof>,
+    ) {
+        let opid = transition.id();
+
+        // [VALIDATION]: Check that transition is committed into the anchor.
+        //               This must be done with deterministic bitcoin commitments &
+        //               LNPBP-4.
+        if self
+            .verifier
+            .verify_mpc(anchor, self.contract_id, bundle_id.into())
+            .is_none()
+        {
+            self.status
+                .add_failure(Failure::NotInAnchor(opid, anchor.txid));
+        } else {
+            self.check_passed(opid, CheckKind::Anchor);
         }
+
+        self.validate_transition(transition, bundle_id, anchor);
     }
 
     fn validate_transition(
@@ -353,21 +1145,38 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
         // Check that the anchor is committed into a transaction spending all of the
         // transition inputs.
         match self.resolver.resolve_tx(txid) {
-            Err(_) => {
+            Err(err) => {
                 // We wre unable to retrieve corresponding transaction, so can't check.
                 // Reporting this incident and continuing further. Why this happens? No
                 // connection to Bitcoin Core, Electrum or other backend etc. So this is not a
                 // failure in a strict sense, however we can't be sure that the consignment is
                 // valid. That's why we keep the track of such information in a separate place
                 // (`unresolved_txids` field of the validation status object).
+                // The same witness transaction may be shared by multiple transitions, so we
+                // report it only once.
+                if self.status.unresolved_txids.contains(&txid) {
+                    return;
+                }
                 self.status.unresolved_txids.push(txid);
                 // This also can mean that there is no known transaction with the id provided by
                 // the anchor, i.e. consignment is invalid. We are proceeding with further
                 // validation in order to detect the rest of problems (and reporting the
                 // failure!)
-                self.status.add_failure(Failure::SealNoWitnessTx(txid));
+                self.status.add_failure(err.into());
             }
             Ok(witness_tx) => {
+                // [VALIDATION]: Making sure the witness transaction belongs to the network
+                //               declared by the contract genesis
+                let expected = self.consignment.genesis().network();
+                if let Some(actual) = self.resolver.chain() {
+                    if actual != expected {
+                        self.status.add_failure(Failure::NetworkMismatch(
+                            transition.id(),
+                            expected,
+                            actual,
+                        ));
+                    }
+                }
                 let witness = Witness::with(witness_tx, anchor.clone());
                 self.validate_witness(transition, witness, bundle_id, anchor)
             }
@@ -433,28 +1242,807 @@ impl<'consignment, 'resolver, C: ConsignmentApi, R: ResolveTx>
         }
 
         let message = mpc::Message::from(bundle_id);
-        match anchor.convolve(self.contract_id, message) {
-            Err(_) => {
+        match self.verifier.verify_mpc(anchor, self.contract_id, message) {
+            None => {
                 self.status.add_failure(Failure::MpcInvalid(opid, txid));
             }
-            Ok(commitment) => {
+            Some(commitment) => {
                 // [VALIDATION]: CHECKING SINGLE-USE-SEALS
-                witness
-                    .verify_many_seals(&seals, &commitment)
-                    .map_err(|err| {
+                match witness.verify_many_seals(&seals, &commitment) {
+                    Err(err) => {
                         self.status
                             .add_failure(Failure::SealInvalid(opid, txid, err));
-                    })
-                    .ok();
+                    }
+                    Ok(_) => self.check_passed(opid, CheckKind::Seals),
+                }
             }
         }
 
         // [VALIDATION]: Checking anchor deterministic bitcoin commitment
-        if let Err(err) = anchor.verify(self.contract_id, message, &witness.tx) {
+        if let Err(err) = self
+            .verifier
+            .verify_dbc(anchor, self.contract_id, message, &witness.tx)
+        {
             // The operation is not committed to bitcoin transaction graph!
             // Ultimate failure. But continuing to detect the rest (after reporting it).
             self.status
                 .add_failure(Failure::AnchorInvalid(opid, txid, err));
+        } else {
+            self.check_passed(opid, CheckKind::Dbc);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use aluvm::isa::Instr;
+    use aluvm::library::Lib;
+    use amplify::confinement::Confined;
+    use strict_encoding::StrictDumb;
+    use strict_types::{SemId, Ty, TypeSystem};
+
+    use super::*;
+    use crate::validation::consignment::test::Mock;
+    use crate::vm::{AluScript, ContractOp, RgbIsa};
+    use crate::{
+        AssignRights, BundleItem, Extension, Genesis, GenesisSchema, GlobalStateSchema, GraphSeal,
+        Input, Inputs, SecretSeal, TransitionSchema, VoidState,
+    };
+
+    #[derive(Default)]
+    struct CountingResolver(Cell<usize>);
+
+    impl ResolveTx for CountingResolver {
+        fn resolve_tx(&self, txid: Txid) -> Result<Tx, TxResolverError> {
+            self.0.set(self.0.get() + 1);
+            Err(TxResolverError::Unknown(txid))
+        }
+    }
+
+    /// Resolver reporting all transaction outputs as spent.
+    struct SpentResolver;
+
+    impl ResolveTx for SpentResolver {
+        fn resolve_tx(&self, txid: Txid) -> Result<Tx, TxResolverError> {
+            Err(TxResolverError::Unknown(txid))
+        }
+
+        fn is_spent(&self, _: Outpoint) -> Result<bool, TxResolverError> { Ok(true) }
+    }
+
+    /// Verifier accepting all anchors as committing to any message.
+    struct AnchorTrustingVerifier;
+
+    impl CommitmentVerifier for AnchorTrustingVerifier {
+        fn verify_mpc(
+            &self,
+            _: &Anchor<mpc::MerkleProof>,
+            _: ContractId,
+            _: mpc::Message,
+        ) -> Option<mpc::Commitment> {
+            Some(strict_dumb!())
+        }
+    }
+
+    /// Mock consignment with explicitly provided terminals.
+    struct Terminated {
+        mock: Mock,
+        terminals: BTreeSet<(BundleId, SecretSeal)>,
+    }
+
+    impl ConsignmentApi for Terminated {
+        type BundleIter<'container> = std::slice::Iter<'container, AnchoredBundle>;
+
+        fn schema(&self) -> &SubSchema { self.mock.schema() }
+        fn operation(&self, opid: OpId) -> Option<OpRef> { self.mock.operation(opid) }
+        fn genesis(&self) -> &Genesis { self.mock.genesis() }
+        fn transition(&self, opid: OpId) -> Option<&Transition> { self.mock.transition(opid) }
+        fn extension(&self, opid: OpId) -> Option<&Extension> { self.mock.extension(opid) }
+        fn terminals(&self) -> BTreeSet<(BundleId, SecretSeal)> { self.terminals.clone() }
+        fn anchored_bundles(&self) -> Self::BundleIter<'_> { self.mock.anchored_bundles() }
+        fn bundle_by_id(&self, bundle_id: BundleId) -> Option<&TransitionBundle> {
+            self.mock.bundle_by_id(bundle_id)
+        }
+        fn op_ids_except(&self, ids: &BTreeSet<OpId>) -> BTreeSet<OpId> {
+            self.mock.op_ids_except(ids)
+        }
+        fn has_operation(&self, opid: OpId) -> bool { self.mock.has_operation(opid) }
+        fn known_transitions_by_bundle_id(&self, bundle_id: BundleId) -> Option<Vec<&Transition>> {
+            let bundle = self.bundle_by_id(bundle_id)?;
+            Some(
+                bundle
+                    .values()
+                    .filter_map(|item| item.transition.as_ref())
+                    .collect(),
+            )
+        }
+    }
+
+    /// Consignment additionally providing transitions which are not a part
+    /// of any of its anchored bundles.
+    struct Detached {
+        inner: Terminated,
+        transitions: Vec<Transition>,
+    }
+
+    impl ConsignmentApi for Detached {
+        type BundleIter<'container> = std::slice::Iter<'container, AnchoredBundle>;
+
+        fn schema(&self) -> &SubSchema { self.inner.schema() }
+        fn operation(&self, opid: OpId) -> Option<OpRef> {
+            self.inner.operation(opid).or_else(|| {
+                self.transitions
+                    .iter()
+                    .find(|transition| transition.id() == opid)
+                    .map(OpRef::Transition)
+            })
+        }
+        fn genesis(&self) -> &Genesis { self.inner.genesis() }
+        fn transition(&self, opid: OpId) -> Option<&Transition> { self.inner.transition(opid) }
+        fn extension(&self, opid: OpId) -> Option<&Extension> { self.inner.extension(opid) }
+        fn terminals(&self) -> BTreeSet<(BundleId, SecretSeal)> { self.inner.terminals() }
+        fn anchored_bundles(&self) -> Self::BundleIter<'_> { self.inner.anchored_bundles() }
+        fn bundle_by_id(&self, bundle_id: BundleId) -> Option<&TransitionBundle> {
+            self.inner.bundle_by_id(bundle_id)
+        }
+        fn op_ids_except(&self, ids: &BTreeSet<OpId>) -> BTreeSet<OpId> {
+            self.inner.op_ids_except(ids)
+        }
+        fn has_operation(&self, opid: OpId) -> bool { self.inner.has_operation(opid) }
+        fn known_transitions_by_bundle_id(&self, bundle_id: BundleId) -> Option<Vec<&Transition>> {
+            self.inner.known_transitions_by_bundle_id(bundle_id)
+        }
+    }
+
+    fn anchored_bundle(
+        txid: Txid,
+        transitions: impl IntoIterator<Item = Transition>,
+    ) -> AnchoredBundle {
+        let mut anchor: Anchor<mpc::MerkleProof> = strict_dumb!();
+        anchor.txid = txid;
+        let items = transitions
+            .into_iter()
+            .map(|transition| {
+                let item = BundleItem {
+                    inputs: none!(),
+                    transition: Some(transition.clone()),
+                };
+                (transition.id(), item)
+            })
+            .collect::<BTreeMap<_, _>>();
+        AnchoredBundle {
+            anchor,
+            bundle: TransitionBundle::from(Confined::try_from(items).unwrap()),
+        }
+    }
+
+    #[test]
+    fn merged_consignment_validates() {
+        let unit = Ty::<SemId>::UNIT.id(None);
+        let schema = SubSchema {
+            genesis: GenesisSchema {
+                metadata: unit,
+                ..default!()
+            },
+            transitions: tiny_bmap! {
+                1 => TransitionSchema { metadata: unit, ..default!() },
+                2 => TransitionSchema { metadata: unit, ..default!() },
+            },
+            type_system: TypeSystem::from(Confined::try_from(bmap! { unit => Ty::UNIT }).unwrap()),
+            ..default!()
+        };
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        // Two partial consignments, each delivering a single terminal bundle
+        let partial = |transition_type: u16, txid: Txid| {
+            let transition = Transition {
+                contract_id: genesis.contract_id(),
+                transition_type,
+                ..Transition::strict_dumb()
+            };
+            let bundle = anchored_bundle(txid, [transition]);
+            Terminated {
+                terminals: bset! { (bundle.bundle.bundle_id(), SecretSeal::strict_dumb()) },
+                mock: Mock {
+                    schema: schema.clone(),
+                    genesis: genesis.clone(),
+                    bundles: vec![bundle],
+                    extensions: vec![],
+                },
+            }
+        };
+        let (txid1, txid2) = (Txid::from([0xAB; 32]), Txid::from([0xCD; 32]));
+        let first = partial(1, txid1);
+        let second = partial(2, txid2);
+        let merged = first.try_merge(&second).unwrap();
+
+        // Witness transactions are not mined yet, which is the only issue with
+        // the consignments
+        let options = || ValidationOptions::new().verifier(AnchorTrustingVerifier);
+        let resolver = CountingResolver::default();
+        for (status, txids) in [
+            (Validator::validate_with(&first, &resolver, options()), vec![txid1]),
+            (Validator::validate_with(&second, &resolver, options()), vec![txid2]),
+            (Validator::validate_with(&merged, &resolver, options()), vec![txid1, txid2]),
+        ] {
+            assert_eq!(status.failures, vec![]);
+            assert_eq!(status.validity(), Validity::UnminedTerminals);
+            let mut unmined = status.unmined_terminals.clone();
+            unmined.sort();
+            assert_eq!(unmined, txids);
+        }
+    }
+
+    #[test]
+    fn resolver_errors() {
+        let txid = Txid::from([0xAB; 32]);
+        let resolver = BTreeMap::<Txid, Tx>::new();
+        let err = resolver.resolve_tx(txid).unwrap_err();
+        assert_eq!(Failure::from(err), Failure::SealNoWitnessTx(txid));
+        assert_eq!(
+            Failure::from(TxResolverError::Other(txid, s!("timeout"))),
+            Failure::ResolverError(txid, s!("timeout"))
+        );
+    }
+
+    #[test]
+    fn cached_validation() {
+        let consignment = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![],
+            extensions: vec![],
+        };
+        let status = Status::with_failure(Failure::Custom(s!("cached")));
+        let mut cache = bmap! { consignment.integrity_hash() => status.clone() };
+
+        let resolver = CountingResolver::default();
+        let options = ValidationOptions::new().cache(&mut cache);
+        assert_eq!(Validator::validate_with(&consignment, &resolver, options), status);
+        assert_eq!(resolver.0.get(), 0);
+
+        let mut cache = BTreeMap::<[u8; 32], Status>::new();
+        let options = ValidationOptions::new().cache(&mut cache);
+        let status = Validator::validate_with(&consignment, &resolver, options);
+        assert_eq!(cache.get(&consignment.integrity_hash()), Some(&status));
+    }
+
+    #[test]
+    fn cached_validation_tampered_extension() {
+        let extension = Extension::strict_dumb();
+        let mut consignment = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![],
+            extensions: vec![extension.clone()],
+        };
+        let status = Status::with_failure(Failure::Custom(s!("cached")));
+        let mut cache = bmap! { consignment.integrity_hash() => status.clone() };
+
+        consignment.extensions = vec![Extension {
+            extension_type: 1,
+            ..extension
+        }];
+        let options = ValidationOptions::new().cache(&mut cache);
+        assert_ne!(
+            Validator::validate_with(&consignment, &CountingResolver::default(), options),
+            status
+        );
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn streamed_failures() {
+        let transition = Transition::strict_dumb();
+        let item = BundleItem {
+            inputs: none!(),
+            transition: Some(transition.clone()),
+        };
+        let consignment = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![AnchoredBundle {
+                anchor: strict_dumb!(),
+                bundle: TransitionBundle::from(tiny_bmap! { transition.id() => item }),
+            }],
+            extensions: vec![],
+        };
+
+        let mut streamed = vec![];
+        let mut on_failure = |failure: &Failure| streamed.push(failure.clone());
+        let status = Validator::validate_with(
+            &consignment,
+            &CountingResolver::default(),
+            ValidationOptions::new().on_failure(&mut on_failure),
+        );
+        assert!(status.failures.len() > 1);
+        assert_eq!(streamed, status.failures);
+    }
+
+    #[test]
+    fn not_anchored() {
+        let schema = SubSchema::default();
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        let extension = Extension {
+            contract_id: genesis.contract_id(),
+            ..Extension::strict_dumb()
+        };
+        let detached = Transition {
+            contract_id: genesis.contract_id(),
+            transition_type: 2,
+            ..Transition::strict_dumb()
+        };
+        let terminal = Transition {
+            contract_id: genesis.contract_id(),
+            transition_type: 1,
+            inputs: Inputs::from(
+                Confined::try_from(bset! {
+                    Input::with(Opout::new(extension.id(), 0, 0)),
+                    Input::with(Opout::new(detached.id(), 0, 0)),
+                })
+                .unwrap(),
+            ),
+            ..Transition::strict_dumb()
+        };
+        let bundle = anchored_bundle(Txid::from([0xAB; 32]), [terminal.clone()]);
+        let consignment = Detached {
+            inner: Terminated {
+                terminals: bset! { (bundle.bundle.bundle_id(), SecretSeal::strict_dumb()) },
+                mock: Mock {
+                    schema,
+                    genesis,
+                    bundles: vec![bundle],
+                    extensions: vec![extension.clone()],
+                },
+            },
+            transitions: vec![detached.clone()],
+        };
+
+        let status = Validator::validate(&consignment, &BTreeMap::<Txid, Tx>::new());
+        let not_anchored = status
+            .failures
+            .iter()
+            .filter_map(|failure| match failure {
+                Failure::NotAnchored(opid) => Some(*opid),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // Extensions do not close seals and are not required to be anchored,
+        // unlike transitions
+        assert!(!not_anchored.contains(&extension.id()));
+        assert!(!not_anchored.contains(&terminal.id()));
+        assert_eq!(not_anchored, vec![detached.id()]);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parallel_bundles() {
+        let schema = SubSchema::default();
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        let bundles = (1..=4u8)
+            .map(|no| {
+                let transition = Transition {
+                    contract_id: genesis.contract_id(),
+                    transition_type: no as u16,
+                    ..Transition::strict_dumb()
+                };
+                anchored_bundle(Txid::from([no; 32]), [transition])
+            })
+            .collect::<Vec<_>>();
+        // The last bundle is not a terminal one and is reported as excessive
+        let terminals = bundles[..3]
+            .iter()
+            .map(|bundle| (bundle.bundle.bundle_id(), SecretSeal::strict_dumb()))
+            .collect();
+        let consignment = Terminated {
+            terminals,
+            mock: Mock {
+                schema,
+                genesis,
+                bundles,
+                extensions: vec![],
+            },
+        };
+        let resolver = BTreeMap::<Txid, Tx>::new();
+
+        let mut serial = Validator::validate(&consignment, &resolver);
+        let mut parallel = Validator::validate_bundles_parallel(&consignment, &resolver);
+        assert!(!serial.failures.is_empty());
+        assert_eq!(serial.unmined_terminals.len(), 3);
+        assert!(serial
+            .warnings
+            .iter()
+            .any(|warning| matches!(warning, Warning::ExcessiveOperation(_))));
+        serial.sort();
+        parallel.sort();
+        assert_eq!(serial, parallel);
+
+        // Workers use the verifier and the options provided by the caller
+        let mut serial = Validator::validate_with(
+            &consignment,
+            &resolver,
+            ValidationOptions::new()
+                .verifier(AnchorTrustingVerifier)
+                .verbose(true),
+        );
+        let mut parallel = Validator::validate_bundles_parallel_with_options(
+            &consignment,
+            &resolver,
+            &AnchorTrustingVerifier,
+            ValidationOptions::new().verbose(true),
+        );
+        assert!(serial
+            .info
+            .iter()
+            .any(|info| matches!(info, Info::CheckPassed { .. })));
+        assert_ne!(serial, Validator::validate(&consignment, &resolver));
+        serial.sort();
+        parallel.sort();
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    #[should_panic(expected = "per-bundle validation can't be run in parallel")]
+    fn parallel_per_bundle() {
+        let consignment = Mock {
+            schema: SubSchema::default(),
+            genesis: Genesis::strict_dumb(),
+            bundles: vec![],
+            extensions: vec![],
+        };
+        Validator::validate_bundles_parallel_with_options(
+            &consignment,
+            &BTreeMap::<Txid, Tx>::new(),
+            &DefaultVerifier,
+            ValidationOptions::new().per_bundle(true),
+        );
+    }
+
+    #[test]
+    fn observer() {
+        let schema = SubSchema::default();
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        let bundles = (1..=2u8)
+            .map(|no| {
+                let transition = Transition {
+                    contract_id: genesis.contract_id(),
+                    transition_type: no as u16,
+                    ..Transition::strict_dumb()
+                };
+                anchored_bundle(Txid::from([no; 32]), [transition])
+            })
+            .collect::<Vec<_>>();
+        let terminals = bundles
+            .iter()
+            .map(|bundle| (bundle.bundle.bundle_id(), SecretSeal::strict_dumb()))
+            .collect();
+        let consignment = Terminated {
+            terminals,
+            mock: Mock {
+                schema,
+                genesis,
+                bundles,
+                extensions: vec![],
+            },
+        };
+        let resolver = BTreeMap::<Txid, Tx>::new();
+
+        let mut observer = CountingObserver::default();
+        let status = Validator::validate_with(
+            &consignment,
+            &resolver,
+            ValidationOptions::new().observer(&mut observer),
+        );
+        assert_eq!(status.unmined_terminals.len(), 2);
+        // Missing terminal witnesses are reported as failures before they get
+        // replaced with warnings
+        assert_eq!(observer, CountingObserver {
+            operations: 3,
+            bundles: 2,
+            failures: status.failures.len() + 2,
+        });
+        assert_eq!(Validator::validate(&consignment, &resolver), status);
+
+        let mut observer = CountingObserver::default();
+        let status = Validator::validate_with(
+            &consignment,
+            &resolver,
+            ValidationOptions::new()
+                .per_bundle(true)
+                .observer(&mut observer),
+        );
+        assert_eq!(observer, CountingObserver {
+            operations: 3,
+            bundles: 2,
+            failures: status.failures.len(),
+        });
+
+        #[cfg(feature = "rayon")]
+        {
+            let mut observer = CountingObserver::default();
+            let status = Validator::validate_bundles_parallel_with(
+                &consignment,
+                &resolver,
+                Some(&mut observer),
+            );
+            assert_eq!(observer, CountingObserver {
+                operations: 3,
+                bundles: 2,
+                failures: status.failures.len() + 2,
+            });
         }
     }
+
+    #[test]
+    fn verbose_checks() {
+        // Genesis with empty metadata is valid only if the metadata type is unit
+        let unit = Ty::<SemId>::UNIT.id(None);
+        let schema = SubSchema {
+            genesis: GenesisSchema {
+                metadata: unit,
+                ..default!()
+            },
+            type_system: TypeSystem::from(Confined::try_from(bmap! { unit => Ty::UNIT }).unwrap()),
+            ..default!()
+        };
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        let genesis_id = genesis.id();
+        let consignment = Mock {
+            schema,
+            genesis,
+            bundles: vec![],
+            extensions: vec![],
+        };
+        let resolver = CountingResolver::default();
+        let is_check_passed = |info: &&Info| matches!(info, Info::CheckPassed { .. });
+
+        let options = ValidationOptions::new().verbose(true);
+        let status = Validator::validate_with(&consignment, &resolver, options);
+        assert_eq!(status.validity(), Validity::Valid);
+        assert_eq!(status.info.iter().filter(is_check_passed).count(), 1);
+        assert!(status.info.contains(&Info::CheckPassed {
+            op: genesis_id,
+            check: CheckKind::Schema
+        }));
+
+        let status = Validator::validate(&consignment, &resolver);
+        assert_eq!(status.validity(), Validity::Valid);
+        assert_eq!(status.info.iter().filter(is_check_passed).count(), 0);
+    }
+
+    #[test]
+    fn schema_registry() {
+        let schema = SubSchema {
+            global_types: tiny_bmap! { 0 => GlobalStateSchema::once(default!()) },
+            ..default!()
+        };
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        let consignment = Mock {
+            schema: SubSchema::default(),
+            genesis,
+            bundles: vec![],
+            extensions: vec![],
+        };
+        let resolver = CountingResolver::default();
+        let is_mismatch = |failure: &Failure| matches!(failure, Failure::SchemaMismatch { .. });
+
+        assert!(Validator::validate(&consignment, &resolver)
+            .failures
+            .iter()
+            .any(is_mismatch));
+
+        let schemata = bmap! { schema.schema_id() => schema.clone() };
+        let options = ValidationOptions::new().schemata(&schemata);
+        let status = Validator::validate_with(&consignment, &resolver, options);
+        assert!(!status.failures.iter().any(is_mismatch));
+
+        let schemata = BTreeMap::<SchemaId, SubSchema>::new();
+        let options = ValidationOptions::new().schemata(&schemata);
+        let status = Validator::validate_with(&consignment, &resolver, options);
+        assert_eq!(status.failures, vec![Failure::SchemaUnknown(schema.schema_id())]);
+    }
+
+    #[test]
+    fn complexity_cap() {
+        // Script exceeding the default cap, which is enforced only on request
+        let code = vec![Instr::ExtensionCodes(RgbIsa::Contract(ContractOp::PcVs(0))); 70];
+        let lib = Lib::assemble::<Instr<RgbIsa>>(&code).unwrap();
+        let schema = SubSchema {
+            script: Script::AluVM(AluScript {
+                libs: Confined::try_from(bmap! { lib.id() => lib }).unwrap(),
+                entry_points: none!(),
+            }),
+            ..default!()
+        };
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        let consignment = Mock {
+            schema,
+            genesis,
+            bundles: vec![],
+            extensions: vec![],
+        };
+        let resolver = CountingResolver::default();
+        let is_script_failure = |failure: &Failure| matches!(failure, Failure::ScriptFailure(_));
+
+        let status = Validator::validate(&consignment, &resolver);
+        assert!(!status.failures.iter().any(is_script_failure));
+
+        let options = ValidationOptions::new().complexity_cap(70_000.into());
+        let status = Validator::validate_with(&consignment, &resolver, options);
+        assert!(!status.failures.iter().any(is_script_failure));
+
+        let options = ValidationOptions::new().complexity_cap(ScriptComplexity::DEFAULT);
+        let status = Validator::validate_with(&consignment, &resolver, options);
+        assert!(status.failures.iter().any(is_script_failure));
+
+        // The cap applies to the schema retrieved from the registry as well
+        let schemata = bmap! { consignment.schema.schema_id() => consignment.schema.clone() };
+        let consignment = Mock {
+            schema: SubSchema::default(),
+            ..consignment
+        };
+        let options = ValidationOptions::new()
+            .schemata(&schemata)
+            .complexity_cap(ScriptComplexity::DEFAULT);
+        let status = Validator::validate_with(&consignment, &resolver, options);
+        assert!(status.failures.iter().any(is_script_failure));
+    }
+
+    #[test]
+    fn repeated_witness_failure() {
+        let schema = SubSchema::default();
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        let shared = Txid::from([0xAB; 32]);
+        let terminal_txid = Txid::from([0xCD; 32]);
+        let parents = [1, 2].map(|transition_type| Transition {
+            transition_type,
+            ..Transition::strict_dumb()
+        });
+        let inputs = parents
+            .iter()
+            .map(|parent| Input::with(Opout::new(parent.id(), 0, 0)))
+            .collect::<BTreeSet<_>>();
+        let child = Transition {
+            transition_type: 3,
+            inputs: Inputs::from(Confined::try_from(inputs).unwrap()),
+            ..Transition::strict_dumb()
+        };
+        let terminal = anchored_bundle(terminal_txid, [child]);
+        let consignment = Terminated {
+            terminals: bset! { (terminal.bundle.bundle_id(), SecretSeal::strict_dumb()) },
+            mock: Mock {
+                schema,
+                genesis,
+                bundles: vec![anchored_bundle(shared, parents), terminal],
+                extensions: vec![],
+            },
+        };
+
+        // Both parent transitions share the same unknown witness transaction
+        let status = Validator::validate(&consignment, &CountingResolver::default());
+        let witness_failures = status
+            .failures
+            .iter()
+            .filter(|failure| **failure == Failure::SealNoWitnessTx(shared))
+            .count();
+        assert_eq!(witness_failures, 1);
+        assert_eq!(status.unresolved_txids, vec![shared]);
+        assert_eq!(status.unmined_terminals, vec![terminal_txid]);
+    }
+
+    #[test]
+    fn seal_on_spent_utxo() {
+        let spent = Txid::from([0xEF; 32]);
+        let mut seal = GraphSeal::strict_dumb();
+        seal.txid = TxPtr::Txid(spent);
+        let rights = AssignRights::revealed(seal, VoidState::default());
+        let transition = Transition {
+            assignments: Confined::try_from(bmap! {
+                1 => TypedAssigns::Declarative(Confined::try_from(vec![rights]).unwrap()),
+            })
+            .unwrap()
+            .into(),
+            ..Transition::strict_dumb()
+        };
+        let opid = transition.id();
+        let terminal = anchored_bundle(Txid::from([0xAB; 32]), [transition]);
+        let schema = SubSchema::default();
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            ..Genesis::strict_dumb()
+        };
+        let consignment = Terminated {
+            terminals: bset! { (terminal.bundle.bundle_id(), SecretSeal::strict_dumb()) },
+            mock: Mock {
+                schema,
+                genesis,
+                bundles: vec![terminal],
+                extensions: vec![],
+            },
+        };
+        let is_spent_warning = |warning: &Warning| matches!(warning, Warning::SealOnSpentUtxo(..));
+
+        let status = Validator::validate(&consignment, &SpentResolver);
+        assert!(status
+            .warnings
+            .contains(&Warning::SealOnSpentUtxo(Opout::new(opid, 1, 0), spent)));
+
+        let status = Validator::validate(&consignment, &CountingResolver::default());
+        assert!(!status.warnings.iter().any(is_spent_warning));
+    }
+
+    #[test]
+    #[cfg(feature = "vm-debug")]
+    fn register_trace() {
+        use aluvm::isa::ControlFlowOp;
+        use aluvm::library::LibSite;
+        use aluvm::reg::RegS;
+        use amplify::confinement::SmallBlob;
+        use amplify::num::u4;
+
+        use crate::vm::{ContractOp, EntryPoint, RegisterTrace};
+
+        let lib = Lib::assemble::<Instr<RgbIsa>>(&[
+            Instr::ExtensionCodes(RgbIsa::Contract(ContractOp::LdM(RegS::from(u4::with(2))))),
+            Instr::ControlFlow(ControlFlowOp::Succ),
+        ])
+        .unwrap();
+        let schema = SubSchema {
+            script: Script::AluVM(AluScript {
+                entry_points: Confined::try_from(bmap! {
+                    EntryPoint::ValidateGenesis => LibSite::with(0, lib.id()),
+                })
+                .unwrap(),
+                libs: Confined::try_from(bmap! { lib.id() => lib }).unwrap(),
+            }),
+            ..default!()
+        };
+        let genesis = Genesis {
+            schema_id: schema.schema_id(),
+            metadata: SmallBlob::try_from(b"debug".to_vec()).unwrap(),
+            ..Genesis::strict_dumb()
+        };
+        let genesis_id = genesis.id();
+        let consignment = Mock {
+            schema,
+            genesis,
+            bundles: vec![],
+            extensions: vec![],
+        };
+
+        let mut registers = RegisterTrace::new();
+        let options = ValidationOptions::new().registers(&mut registers);
+        Validator::validate_with(&consignment, &CountingResolver::default(), options);
+        let runs = &registers[&genesis_id];
+        assert_eq!(runs.len(), 1);
+        let (entry_point, regs) = &runs[0];
+        assert_eq!(*entry_point, EntryPoint::ValidateGenesis);
+        assert_eq!(regs.get_s(2).map(|bs| bs.to_vec()), Some(b"debug".to_vec()));
+    }
 }
@@ -0,0 +1,292 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2023 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2023 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2023 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validity of the contract history and a generic container of the
+//! validation results.
+//!
+//! Unlike the rest of the validation, the module depends only on `core` and
+//! `alloc`, such that the results can be processed in `no_std` environments.
+//! [`Status`](super::Status) carries failures referencing bitcoin and AluVM
+//! data types; [`StatusCore`] allows to carry lightweight payloads (like
+//! failure codes) instead.
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::iter::FromIterator;
+use core::ops::AddAssign;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+#[repr(u8)]
+pub enum Validity {
+    #[display("is valid")]
+    Valid,
+
+    #[display("has non-mined terminal(s)")]
+    UnminedTerminals,
+
+    #[display("contains unknown witness transactions")]
+    UnresolvedTransactions,
+
+    #[display("is NOT valid")]
+    Invalid,
+}
+
+impl Validity {
+    /// Returns process exit code which should be used by command-line tools
+    /// reporting the validity; the codes grow with the severity of the
+    /// problems:
+    /// - `0` for [`Validity::Valid`];
+    /// - `1` for [`Validity::UnminedTerminals`], i.e. when the consignment is
+    ///   valid except its endpoints, which are not yet mined;
+    /// - `2` for [`Validity::UnresolvedTransactions`];
+    /// - `3` for [`Validity::Invalid`].
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Validity::Valid => 0,
+            Validity::UnminedTerminals => 1,
+            Validity::UnresolvedTransactions => 2,
+            Validity::Invalid => 3,
+        }
+    }
+
+    /// Restores validity from the process exit code produced by
+    /// [`Validity::exit_code`], returning `None` for unknown codes.
+    pub fn from_exit_code(code: i32) -> Option<Validity> {
+        Some(match code {
+            0 => Validity::Valid,
+            1 => Validity::UnminedTerminals,
+            2 => Validity::UnresolvedTransactions,
+            3 => Validity::Invalid,
+            _ => return None,
+        })
+    }
+
+    /// Detects whether the contract history is valid, except possibly its
+    /// terminal witness transactions not being mined yet, meaning the state
+    /// can be already used.
+    pub fn is_usable(&self) -> bool { matches!(self, Validity::Valid | Validity::UnminedTerminals) }
+}
+
+impl From<Validity> for i32 {
+    fn from(validity: Validity) -> Self { validity.exit_code() }
+}
+
+/// Container of validation results, generic over the transaction id,
+/// failure, warning and info types. Follows the semantics of
+/// [`Status`](super::Status), which can be converted into it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct StatusCore<Tx, F, W, I> {
+    pub unresolved_txids: Vec<Tx>,
+    pub unmined_terminals: Vec<Tx>,
+    pub failures: Vec<F>,
+    pub warnings: Vec<W>,
+    pub info: Vec<I>,
+}
+
+impl<Tx, F, W, I> Default for StatusCore<Tx, F, W, I> {
+    fn default() -> Self {
+        StatusCore {
+            unresolved_txids: Vec::new(),
+            unmined_terminals: Vec::new(),
+            failures: Vec::new(),
+            warnings: Vec::new(),
+            info: Vec::new(),
+        }
+    }
+}
+
+/// Merges statuses, collapsing identical entries, as it is done for
+/// [`Status`](super::Status).
+impl<Tx, F, W, I> AddAssign for StatusCore<Tx, F, W, I>
+where
+    Tx: PartialEq + Debug,
+    F: PartialEq + Debug,
+    W: PartialEq + Debug,
+    I: PartialEq + Debug,
+{
+    fn add_assign(&mut self, rhs: Self) {
+        extend_unique(&mut self.unresolved_txids, rhs.unresolved_txids);
+        extend_unique(&mut self.unmined_terminals, rhs.unmined_terminals);
+        extend_unique(&mut self.failures, rhs.failures);
+        extend_unique(&mut self.warnings, rhs.warnings);
+        extend_unique(&mut self.info, rhs.info);
+    }
+}
+
+impl<Tx, F, W, I> FromIterator<F> for StatusCore<Tx, F, W, I> {
+    fn from_iter<T: IntoIterator<Item = F>>(iter: T) -> Self {
+        Self {
+            failures: iter.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+}
+
+impl<Tx, F, W, I> StatusCore<Tx, F, W, I> {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn add_failure(&mut self, failure: impl Into<F>) -> &Self {
+        self.failures.push(failure.into());
+        self
+    }
+
+    pub fn add_warning(&mut self, warning: impl Into<W>) -> &Self {
+        self.warnings.push(warning.into());
+        self
+    }
+
+    pub fn add_info(&mut self, info: impl Into<I>) -> &Self {
+        self.info.push(info.into());
+        self
+    }
+
+    pub fn validity(&self) -> Validity {
+        validity(&self.failures, &self.unresolved_txids, &self.unmined_terminals)
+    }
+}
+
+/// Appends items absent in `dst` to its end, preserving their order.
+///
+/// Items are indexed by their debug representation, such that each of them
+/// gets compared only with the items having the same representation, and not
+/// with all the items of `dst`.
+pub(super) fn extend_unique<T: PartialEq + Debug>(dst: &mut Vec<T>, src: Vec<T>) {
+    if src.is_empty() {
+        return;
+    }
+    let mut index = BTreeMap::<String, Vec<usize>>::new();
+    for (pos, item) in dst.iter().enumerate() {
+        index.entry(format!("{item:?}")).or_default().push(pos);
+    }
+    for item in src {
+        let same = index.entry(format!("{item:?}")).or_default();
+        if same.iter().all(|pos| dst[*pos] != item) {
+            same.push(dst.len());
+            dst.push(item);
+        }
+    }
+}
+
+/// Removes repeated items, keeping only the first occurrence of each of them.
+pub(super) fn dedup_unique<T: PartialEq + Debug>(items: &mut Vec<T>) {
+    let all = core::mem::take(items);
+    extend_unique(items, all);
+}
+
+/// Detects validity from the collected validation results.
+pub(super) fn validity<F, Tx>(failures: &[F], unresolved: &[Tx], unmined: &[Tx]) -> Validity {
+    if failures.is_empty() {
+        if unmined.is_empty() {
+            Validity::Valid
+        } else {
+            Validity::UnminedTerminals
+        }
+    } else if unresolved.is_empty() {
+        Validity::Invalid
+    } else {
+        Validity::UnresolvedTransactions
+    }
+}
+
+/// Tests use only `core` and `alloc`, checking that the module doesn't
+/// depend on `std`.
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+
+    use super::*;
+
+    type CodeStatus = StatusCore<[u8; 32], u16, u16, u16>;
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(Validity::Valid.exit_code(), 0);
+        assert_eq!(Validity::UnminedTerminals.exit_code(), 1);
+        assert_eq!(Validity::UnresolvedTransactions.exit_code(), 2);
+        assert_eq!(Validity::Invalid.exit_code(), 3);
+
+        for validity in [
+            Validity::Valid,
+            Validity::UnminedTerminals,
+            Validity::UnresolvedTransactions,
+            Validity::Invalid,
+        ] {
+            assert_eq!(Validity::from_exit_code(i32::from(validity)), Some(validity));
+        }
+        assert_eq!(Validity::from_exit_code(4), None);
+        assert_eq!(Validity::from_exit_code(-1), None);
+    }
+
+    #[test]
+    fn usable() {
+        assert!(Validity::Valid.is_usable());
+        assert!(Validity::UnminedTerminals.is_usable());
+        assert!(!Validity::UnresolvedTransactions.is_usable());
+        assert!(!Validity::Invalid.is_usable());
+    }
+
+    #[test]
+    fn core_validity() {
+        let mut status = CodeStatus::new();
+        assert_eq!(status.validity(), Validity::Valid);
+
+        status.unmined_terminals.push([0xAB; 32]);
+        assert_eq!(status.validity(), Validity::UnminedTerminals);
+
+        status += [1001u16].into_iter().collect();
+        assert_eq!(status.validity(), Validity::Invalid);
+
+        status += CodeStatus {
+            unresolved_txids: vec![[0xCD; 32]],
+            failures: vec![1001, 3001],
+            ..default!()
+        };
+        assert_eq!(status.failures, vec![1001, 3001]);
+        assert_eq!(status.validity(), Validity::UnresolvedTransactions);
+
+        status.add_warning(7u16);
+        assert_eq!(status.warnings, vec![7]);
+    }
+
+    #[test]
+    fn unique() {
+        let mut items = vec![3, 1, 3, 2, 1];
+        dedup_unique(&mut items);
+        assert_eq!(items, vec![3, 1, 2]);
+
+        extend_unique(&mut items, vec![2, 4, 4, 1, 5]);
+        assert_eq!(items, vec![3, 1, 2, 4, 5]);
+    }
+}
@@ -0,0 +1,248 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2023 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2023 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2023 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Abstraction over cryptographic commitment verification used by the
+//! validation routines.
+
+use std::collections::BTreeMap;
+
+use bp::dbc::{anchor, Anchor};
+use bp::{Tx, Txid};
+use commit_verify::mpc;
+use secp256k1_zkp::PedersenCommitment;
+
+use super::{Failure, ResolveTx, Status};
+use crate::{BundleId, ConcealedValue, ContractId, OpId, RangeProofError};
+
+/// Trait for verifying cryptographic commitments used by RGB: bulletproof
+/// range proofs, Pedersen commitment sums and anchor commitments (MPC and
+/// DBC).
+///
+/// Default method implementations perform verification with the RGB Core
+/// built-in procedures; implementations may override them with optimized
+/// (hardware-accelerated or batched) versions.
+pub trait CommitmentVerifier {
+    /// Verifies bulletproof range proof for a concealed fungible value.
+    fn verify_range_proof(&self, value: &ConcealedValue) -> Result<bool, RangeProofError> {
+        value.verify_range_proof()
+    }
+
+    /// Verifies that the sum of Pedersen commitments in inputs is equal to
+    /// the sum of commitments in outputs.
+    fn verify_commitments_sum(
+        &self,
+        inputs: &[PedersenCommitment],
+        outputs: &[PedersenCommitment],
+    ) -> bool {
+        secp256k1_zkp::verify_commitments_sum_to_equal(secp256k1_zkp::SECP256K1, inputs, outputs)
+    }
+
+    /// Convolves multi-protocol commitment proof from the anchor for the
+    /// given contract and message, returning the commitment or `None` if the
+    /// proof is invalid.
+    fn verify_mpc(
+        &self,
+        anchor: &Anchor<mpc::MerkleProof>,
+        contract_id: ContractId,
+        message: mpc::Message,
+    ) -> Option<mpc::Commitment> {
+        anchor.convolve(contract_id, message).ok()
+    }
+
+    /// Verifies that the anchor deterministic bitcoin commitment is present
+    /// in the witness transaction.
+    fn verify_dbc(
+        &self,
+        anchor: &Anchor<mpc::MerkleProof>,
+        contract_id: ContractId,
+        message: mpc::Message,
+        tx: &Tx,
+    ) -> Result<(), anchor::VerifyError> {
+        anchor.verify(contract_id, message, tx).map(|_| ())
+    }
+}
+
+impl<T: CommitmentVerifier + ?Sized> CommitmentVerifier for &T {
+    fn verify_range_proof(&self, value: &ConcealedValue) -> Result<bool, RangeProofError> {
+        (*self).verify_range_proof(value)
+    }
+
+    fn verify_commitments_sum(
+        &self,
+        inputs: &[PedersenCommitment],
+        outputs: &[PedersenCommitment],
+    ) -> bool {
+        (*self).verify_commitments_sum(inputs, outputs)
+    }
+
+    fn verify_mpc(
+        &self,
+        anchor: &Anchor<mpc::MerkleProof>,
+        contract_id: ContractId,
+        message: mpc::Message,
+    ) -> Option<mpc::Commitment> {
+        (*self).verify_mpc(anchor, contract_id, message)
+    }
+
+    fn verify_dbc(
+        &self,
+        anchor: &Anchor<mpc::MerkleProof>,
+        contract_id: ContractId,
+        message: mpc::Message,
+        tx: &Tx,
+    ) -> Result<(), anchor::VerifyError> {
+        (*self).verify_dbc(anchor, contract_id, message, tx)
+    }
+}
+
+/// Commitment verifier using RGB Core built-in verification procedures.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct DefaultVerifier;
+
+impl CommitmentVerifier for DefaultVerifier {}
+
+/// Verifies anchors of the contract transitions, each provided together with
+/// the id of the transition and its bundle, resolving each of the witness
+/// transactions shared by multiple anchors only once.
+///
+/// Reports the same failures as the full consignment validation does:
+/// [`Failure::MpcInvalid`] and [`Failure::AnchorInvalid`] for each of the
+/// operations with an invalid anchor; and a single failure produced by the
+/// resolver for each of the witness transactions which can't be resolved.
+pub fn verify_anchors_batched(
+    contract_id: ContractId,
+    anchors: &[(OpId, BundleId, &Anchor<mpc::MerkleProof>)],
+    resolver: &(impl ResolveTx + ?Sized),
+    verifier: &impl CommitmentVerifier,
+) -> Status {
+    let mut status = Status::new();
+
+    let mut by_witness = BTreeMap::<Txid, Vec<_>>::new();
+    for item in anchors {
+        by_witness.entry(item.2.txid).or_default().push(item);
+    }
+
+    for (txid, anchors) in by_witness {
+        let tx = match resolver.resolve_tx(txid) {
+            Ok(tx) => tx,
+            Err(err) => {
+                status.unresolved_txids.push(txid);
+                status.add_failure(err);
+                continue;
+            }
+        };
+        for (opid, bundle_id, anchor) in anchors {
+            let message = mpc::Message::from(*bundle_id);
+            if verifier.verify_mpc(anchor, contract_id, message).is_none() {
+                status.add_failure(Failure::MpcInvalid(*opid, txid));
+            }
+            if let Err(err) = verifier.verify_dbc(anchor, contract_id, message, &tx) {
+                status.add_failure(Failure::AnchorInvalid(*opid, txid, err));
+            }
+        }
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use bp::secp256k1::rand::thread_rng;
+    use commit_verify::Conceal;
+    use strict_encoding::StrictDumb;
+    use strict_types::TypeSystem;
+
+    use super::*;
+    use crate::{AssignFungible, FungibleType, GraphSeal, OpId, RevealedValue, StateSchema};
+
+    #[derive(Default)]
+    struct CountingVerifier {
+        range_proofs: Cell<usize>,
+    }
+
+    impl CommitmentVerifier for CountingVerifier {
+        fn verify_range_proof(&self, _: &ConcealedValue) -> Result<bool, RangeProofError> {
+            self.range_proofs.set(self.range_proofs.get() + 1);
+            Ok(true)
+        }
+    }
+
+    #[derive(Default)]
+    struct CountingResolver(Cell<usize>);
+
+    impl ResolveTx for CountingResolver {
+        fn resolve_tx(&self, _: Txid) -> Result<Tx, crate::validation::TxResolverError> {
+            self.0.set(self.0.get() + 1);
+            Ok(Tx::strict_dumb())
+        }
+    }
+
+    #[test]
+    fn anchors_batched() {
+        let contract_id = ContractId::from([0x01; 32]);
+        let bundle_id = BundleId::from([0x02; 32]);
+        let a = OpId::from([0xAA; 32]);
+        let b = OpId::from([0xBB; 32]);
+        let anchor = Anchor::<mpc::MerkleProof>::strict_dumb();
+        let txid = anchor.txid;
+        let anchors = [(a, bundle_id, &anchor), (b, bundle_id, &anchor)];
+
+        let resolver = CountingResolver::default();
+        let status = verify_anchors_batched(contract_id, &anchors, &resolver, &DefaultVerifier);
+        assert_eq!(resolver.0.get(), 1);
+        for opid in [a, b] {
+            assert!(status.failures.iter().any(
+                |f| matches!(f, Failure::AnchorInvalid(id, t, _) if *id == opid && *t == txid)
+            ));
+        }
+
+        let resolver = BTreeMap::<Txid, Tx>::new();
+        let status = verify_anchors_batched(contract_id, &anchors, &resolver, &DefaultVerifier);
+        assert_eq!(status.unresolved_txids, vec![txid]);
+        assert_eq!(status.failures, vec![Failure::SealNoWitnessTx(txid)]);
+    }
+
+    #[test]
+    fn range_proofs_routed() {
+        let verifier = CountingVerifier::default();
+        let value = RevealedValue::new(100u64, &mut thread_rng());
+        let assign = AssignFungible::revealed(GraphSeal::strict_dumb(), value).conceal();
+        let schema = StateSchema::Fungible(FungibleType::Unsigned64Bit);
+
+        let status =
+            schema.validate(&TypeSystem::default(), &OpId::from([0u8; 32]), 0, &assign, &verifier);
+        assert!(status.failures.is_empty());
+        assert_eq!(verifier.range_proofs.get(), 1);
+
+        let status = schema.validate(
+            &TypeSystem::default(),
+            &OpId::from([0u8; 32]),
+            0,
+            &assign,
+            &DefaultVerifier,
+        );
+        assert_eq!(status.failures.len(), 1);
+        assert_eq!(verifier.range_proofs.get(), 1);
+    }
+}
@@ -0,0 +1,202 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2023 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2023 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2023 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Assembler of RGB validation scripts, used by the [`rgb_asm!`] macro.
+//!
+//! Each function in this module constructs a single instruction named after
+//! its assembly mnemonic, checking that the register operands are in range.
+//!
+//! [`rgb_asm!`]: crate::rgb_asm
+
+use aluvm::isa::{ControlFlowOp, Instr};
+use aluvm::library::Lib;
+use aluvm::reg::{Reg16, RegS};
+use amplify::num::u4;
+
+use crate::vm::{ContractOp, RgbInstr, RgbIsa};
+
+/// Errors happening during assembling of RGB validation scripts.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum AsmError {
+    /// register index {0} is out of range; only registers 0 to 15 are
+    /// available.
+    RegisterOutOfRange(u8),
+
+    /// assembled code exceeds the maximal size of AluVM library.
+    TooLarge,
+}
+
+/// Assembles instructions into AluVM bytecode, failing on the first invalid
+/// instruction.
+pub fn assemble(
+    instrs: impl IntoIterator<Item = Result<RgbInstr, AsmError>>,
+) -> Result<Vec<u8>, AsmError> {
+    let instrs = instrs.into_iter().collect::<Result<Vec<_>, _>>()?;
+    let lib = Lib::assemble(&instrs).map_err(|_| AsmError::TooLarge)?;
+    Ok(lib.code.as_ref().to_vec())
+}
+
+fn reg(index: u8) -> Result<u4, AsmError> {
+    if index > 15 {
+        return Err(AsmError::RegisterOutOfRange(index));
+    }
+    Ok(u4::with(index))
+}
+
+fn contract(op: ContractOp) -> Result<RgbInstr, AsmError> {
+    Ok(Instr::ExtensionCodes(RgbIsa::Contract(op)))
+}
+
+/// `nop`: does nothing.
+pub fn nop() -> Result<RgbInstr, AsmError> { Ok(Instr::Nop) }
+
+/// `fail`: terminates the program with a failure.
+pub fn fail() -> Result<RgbInstr, AsmError> { Ok(Instr::ControlFlow(ControlFlowOp::Fail)) }
+
+/// `succ`: terminates the program with a success.
+pub fn succ() -> Result<RgbInstr, AsmError> { Ok(Instr::ControlFlow(ControlFlowOp::Succ)) }
+
+/// `ret`: returns from the current subroutine.
+pub fn ret() -> Result<RgbInstr, AsmError> { Ok(Instr::ControlFlow(ControlFlowOp::Ret)) }
+
+/// `cnp state_type, a16`: see [`ContractOp::CnP`].
+pub fn cnp(state_type: u16, a16: u8) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::CnP(state_type, Reg16::from(reg(a16)?)))
+}
+
+/// `cns state_type, a16`: see [`ContractOp::CnS`].
+pub fn cns(state_type: u16, a16: u8) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::CnS(state_type, Reg16::from(reg(a16)?)))
+}
+
+/// `cng state_type, a8`: see [`ContractOp::CnG`].
+pub fn cng(state_type: u16, a8: u8) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::CnG(state_type, Reg16::from(reg(a8)?)))
+}
+
+/// `cnc state_type, a16`: see [`ContractOp::CnC`].
+pub fn cnc(state_type: u16, a16: u8) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::CnC(state_type, Reg16::from(reg(a16)?)))
+}
+
+/// `ldp state_type, index, s16`: see [`ContractOp::LdP`].
+pub fn ldp(state_type: u16, index: u16, s16: u8) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::LdP(state_type, index, RegS::from(reg(s16)?)))
+}
+
+/// `lds state_type, index, s16`: see [`ContractOp::LdS`].
+pub fn lds(state_type: u16, index: u16, s16: u8) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::LdS(state_type, index, RegS::from(reg(s16)?)))
+}
+
+/// `ldf state_type, index, a64`: see [`ContractOp::LdF`].
+pub fn ldf(state_type: u16, index: u16, a64: u8) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::LdF(state_type, index, Reg16::from(reg(a64)?)))
+}
+
+/// `ldg state_type, index, s16`: see [`ContractOp::LdG`].
+pub fn ldg(state_type: u16, index: u8, s16: u8) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::LdG(state_type, index, RegS::from(reg(s16)?)))
+}
+
+/// `ldc state_type, depth, s16`: see [`ContractOp::LdC`].
+pub fn ldc(state_type: u16, depth: u16, s16: u8) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::LdC(state_type, depth, RegS::from(reg(s16)?)))
+}
+
+/// `ldm s16`: see [`ContractOp::LdM`].
+pub fn ldm(s16: u8) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::LdM(RegS::from(reg(s16)?)))
+}
+
+/// `pcvs state_type`: see [`ContractOp::PcVs`].
+pub fn pcvs(state_type: u16) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::PcVs(state_type))
+}
+
+/// `pcas state_type, s16`: see [`ContractOp::PcAs`].
+pub fn pcas(state_type: u16, s16: u8) -> Result<RgbInstr, AsmError> {
+    contract(ContractOp::PcAs(state_type, RegS::from(reg(s16)?)))
+}
+
+/// Assembles RGB validation script from a list of `;`-terminated
+/// instructions, returning the bytecode or [`AsmError`].
+///
+/// Operands are given as numbers: state types and indexes are followed by
+/// register indexes. Unknown mnemonics, wrong number of operands and
+/// out-of-range numeric literals fail to compile; out-of-range registers
+/// produce [`AsmError::RegisterOutOfRange`].
+///
+/// ```
+/// let code = rgb::rgb_asm! {
+///     cnp 1, 0;
+///     pcvs 1;
+///     succ;
+/// }
+/// .unwrap();
+/// ```
+#[macro_export]
+macro_rules! rgb_asm {
+    ($($mnemonic:ident $($arg:expr),* ;)*) => {
+        $crate::vm::asm::assemble([$($crate::vm::asm::$mnemonic($($arg),*)),*])
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use aluvm::isa::opcodes::INSTR_SUCC;
+
+    use super::*;
+    use crate::vm::disassemble;
+    use crate::vm::opcodes::*;
+
+    #[test]
+    fn program() {
+        let code = rgb_asm! {
+            cnp 1, 0;
+            ldg 7, 8, 3;
+            pcvs 11;
+            succ;
+        }
+        .unwrap();
+        #[rustfmt::skip]
+        let reference = [
+            INSTR_CNP, 1, 0, 0x00,
+            INSTR_LDG, 7, 0, 8, 0x03,
+            INSTR_PCVS, 11, 0,
+            INSTR_SUCC,
+        ];
+        assert_eq!(code, reference);
+
+        let instrs = disassemble(&code).unwrap();
+        assert_eq!(instrs, vec![
+            cnp(1, 0).unwrap(),
+            ldg(7, 8, 3).unwrap(),
+            pcvs(11).unwrap(),
+            succ().unwrap()
+        ]);
+        assert_eq!(instrs[2].to_string(), "pcvs     11");
+
+        assert_eq!(rgb_asm! { ldm 16; }, Err(AsmError::RegisterOutOfRange(16)));
+    }
+}
@@ -0,0 +1,169 @@
+// RGB Core Library: consensus layer for RGB smart contracts.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2023 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2023 LNP/BP Standards Association. All rights reserved.
+// Copyright (C) 2019-2023 Dr Maxim Orlovsky. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Disassembler of AluVM bytecode using RGB instruction set extension.
+
+use aluvm::isa::{Bytecode, Instr};
+use aluvm::library::{Cursor, Lib, LibSeg, Read};
+
+use crate::vm::{ContractOp, RgbIsa};
+
+/// AluVM instruction with RGB instruction set extension.
+pub type RgbInstr = Instr<RgbIsa>;
+
+/// Errors happening during bytecode disassembling.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum DisasmError {
+    /// bytecode contains reserved or unimplemented opcode {0:#04x}.
+    Reserved(u8),
+
+    /// bytecode is truncated in the middle of an instruction.
+    Truncated,
+}
+
+/// Disassembles bytecode into a list of instructions, which can be displayed
+/// as assembly mnemonics. Opcodes from the RGB range are decoded as
+/// [`RgbIsa`] instructions; the rest is decoded by AluVM.
+///
+/// Fails on reserved opcodes, which are not implemented by this version of
+/// the virtual machine.
+pub fn disassemble(code: &[u8]) -> Result<Vec<RgbInstr>, DisasmError> {
+    let data: &[u8] = &[];
+    let libs = LibSeg::default();
+    let mut reader = Cursor::with(code, data, &libs);
+
+    let mut instrs = Vec::new();
+    while !reader.is_eof() {
+        let instr = RgbInstr::decode(&mut reader).map_err(|_| DisasmError::Truncated)?;
+        match instr {
+            Instr::ExtensionCodes(ref isa) if !isa.is_implemented() => {
+                return Err(DisasmError::Reserved(isa.instr_byte()));
+            }
+            Instr::ReservedInstruction(_) => return Err(DisasmError::Reserved(instr.instr_byte())),
+            _ => instrs.push(instr),
+        }
+    }
+    Ok(instrs)
+}
+
+/// Errors detected by [`validate_isae`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum IsaeError {
+    /// bytecode uses opcode {byte:#04x} at offset {offset} from a prohibited
+    /// ISA extension.
+    Prohibited { offset: u16, byte: u8 },
+
+    /// bytecode is truncated in the middle of an instruction.
+    Truncated,
+}
+
+/// Checks that the bytecode uses only AluVM core instructions and RGB contract
+/// instructions, rejecting opcodes from other ISA extensions, which are
+/// prohibited for now.
+pub fn validate_isae(code: &[u8]) -> Result<(), IsaeError> {
+    let data: &[u8] = &[];
+    let libs = LibSeg::default();
+    let mut reader = Cursor::with(code, data, &libs);
+
+    let mut offset = 0u16;
+    while !reader.is_eof() {
+        let instr = RgbInstr::decode(&mut reader).map_err(|_| IsaeError::Truncated)?;
+        if let Instr::ExtensionCodes(ref isa) = instr {
+            let byte = isa.instr_byte();
+            if !ContractOp::instr_range().contains(&byte) {
+                return Err(IsaeError::Prohibited { offset, byte });
+            }
+        }
+        offset += Lib::assemble(&[instr])
+            .map_err(|_| IsaeError::Truncated)?
+            .code
+            .as_ref()
+            .len() as u16;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use aluvm::isa::ControlFlowOp;
+    use aluvm::reg::{Reg16, RegS};
+    use amplify::num::u4;
+
+    use super::*;
+    use crate::vm::opcodes::*;
+
+    #[test]
+    fn round_trip() {
+        let a16 = Reg16::from(u4::with(2));
+        let s = RegS::from(u4::with(3));
+        let ops = [
+            (INSTR_CNP, ContractOp::CnP(1, a16)),
+            (INSTR_CNS, ContractOp::CnS(2, a16)),
+            (INSTR_CNG, ContractOp::CnG(3, a16)),
+            (INSTR_CNC, ContractOp::CnC(4, a16)),
+            (INSTR_LDP, ContractOp::LdP(1, 2, s)),
+            (INSTR_LDS, ContractOp::LdS(3, 4, s)),
+            (INSTR_LDF, ContractOp::LdF(5, 6, a16)),
+            (INSTR_LDG, ContractOp::LdG(7, 8, s)),
+            (INSTR_LDC, ContractOp::LdC(9, 10, s)),
+            (INSTR_LDM, ContractOp::LdM(s)),
+            (INSTR_PCVS, ContractOp::PcVs(11)),
+            (INSTR_PCAS, ContractOp::PcAs(12, s)),
+        ];
+        for (opcode, op) in ops {
+            let expected = vec![RgbInstr::ExtensionCodes(RgbIsa::Contract(op)), Instr::Nop];
+            let lib = Lib::assemble(&expected).unwrap();
+            assert_eq!(lib.code.as_ref()[0], opcode);
+            let code = disassemble(lib.code.as_ref()).unwrap();
+            assert_eq!(code, expected);
+            assert_eq!(code[0].to_string(), op.to_string());
+        }
+
+        assert_eq!(disassemble(&[INSTR_PCCS, 0, 0]), Err(DisasmError::Reserved(INSTR_PCCS)));
+        assert_eq!(disassemble(&[INSTR_PCVS, 0]), Err(DisasmError::Truncated));
+    }
+
+    #[test]
+    fn isae() {
+        let permitted = Lib::assemble(&[
+            RgbInstr::ControlFlow(ControlFlowOp::Succ),
+            RgbInstr::ExtensionCodes(RgbIsa::Contract(ContractOp::PcVs(1))),
+        ])
+        .unwrap();
+        assert_eq!(validate_isae(permitted.code.as_ref()), Ok(()));
+
+        let prohibited = Lib::assemble(&[
+            RgbInstr::ExtensionCodes(RgbIsa::Contract(ContractOp::PcVs(1))),
+            RgbInstr::ExtensionCodes(RgbIsa::Fail(0b10_000_000)),
+        ])
+        .unwrap();
+        assert_eq!(
+            validate_isae(prohibited.code.as_ref()),
+            Err(IsaeError::Prohibited {
+                offset: 3,
+                byte: 0b10_000_000
+            })
+        );
+    }
+}
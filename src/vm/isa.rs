@@ -28,7 +28,7 @@ use aluvm::isa::{Bytecode, BytecodeError, ExecStep, InstructionSet};
 use aluvm::library::{CodeEofError, LibSite, Read, Write};
 use aluvm::reg::CoreRegs;
 
-use super::opcodes::{INSTR_ISAE_FROM, INSTR_ISAE_TO};
+use super::opcodes::{RgbOpcode, INSTR_ISAE_FROM, INSTR_ISAE_TO};
 use super::{ContractOp, TimechainOp};
 use crate::validation::OpInfo;
 
@@ -45,6 +45,29 @@ pub enum RgbIsa {
     Fail(u8),
 }
 
+impl RgbIsa {
+    /// Detects whether the instruction is implemented by this version of the
+    /// virtual machine. Unimplemented instructions (using reserved opcodes)
+    /// always fail when executed.
+    pub fn is_implemented(&self) -> bool {
+        !matches!(
+            self,
+            RgbIsa::Fail(_) | RgbIsa::Contract(ContractOp::Fail(_)) | RgbIsa::Timechain(_)
+        )
+    }
+
+    /// Weight of the instruction used in computing script complexity.
+    /// Unimplemented instructions (see [`RgbIsa::is_implemented`]), which
+    /// just fail, weigh the same as the core AluVM instructions, even if
+    /// their opcode is defined.
+    pub fn weight(&self) -> u64 {
+        if !self.is_implemented() {
+            return 1;
+        }
+        RgbOpcode::try_from(self.instr_byte()).map_or(1, RgbOpcode::weight)
+    }
+}
+
 impl InstructionSet for RgbIsa {
     type Context<'ctx> = OpInfo<'ctx>;
 
@@ -102,10 +125,11 @@ impl Bytecode for RgbIsa {
             instr if ContractOp::instr_range().contains(&instr) => {
                 RgbIsa::Contract(ContractOp::decode(reader)?)
             }
-            instr if TimechainOp::instr_range().contains(&instr) => {
-                RgbIsa::Timechain(TimechainOp::decode(reader)?)
+            // Timechain operations are not defined yet, so their decoding is not possible
+            x => {
+                reader.read_u8()?;
+                RgbIsa::Fail(x)
             }
-            x => RgbIsa::Fail(x),
         })
     }
 }
@@ -24,15 +24,21 @@
 //!
 //! Concrete virtual machine implementations must be wrapped into this API
 
+pub mod asm;
 pub mod opcodes;
+mod disasm;
 mod isa;
 mod op_contract;
 mod op_timechain;
 mod script;
 mod runtime;
 
+pub use asm::AsmError;
+pub use disasm::{disassemble, validate_isae, DisasmError, IsaeError, RgbInstr};
 pub use isa::RgbIsa;
 pub use op_contract::ContractOp;
 pub use op_timechain::TimechainOp;
 pub use runtime::AluRuntime;
-pub use script::{AluScript, EntryPoint, LIBS_MAX_TOTAL};
+#[cfg(feature = "vm-debug")]
+pub use runtime::RegisterTrace;
+pub use script::{AluScript, EntryPoint, EntryPointParseError, LIBS_MAX_TOTAL};
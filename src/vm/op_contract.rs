@@ -35,7 +35,7 @@ use strict_encoding::StrictSerialize;
 
 use super::opcodes::*;
 use crate::validation::OpInfo;
-use crate::{Assign, TypedAssigns};
+use crate::{Assign, PedersenCommitment, TypedAssigns};
 
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
 pub enum ContractOp {
@@ -127,11 +127,24 @@ pub enum ContractOp {
     /// to `false`.
     #[display("pcvs     {0}")]
     PcVs(u16),
+
     /*
     /// Verifies corrected sum of pedersen commitments adding a value taken from `RegR` to the list
     /// of inputs (negatives).
     PcCs(u16, RegR),
      */
+    /// Adds up pedersen commitments of the owned state with type id from the
+    /// first argument and puts the resulting commitment, serialized as 33
+    /// bytes, into a register provided in the second argument.
+    ///
+    /// The sum commits to the sum of the values with the sum of their
+    /// blinding factors, such that the script can compare it with other
+    /// commitments. If the state is absent, is not of `FungibleState::Bits64`
+    /// or the commitments sum up to the point at infinity, sets `st0` to
+    /// `false` and terminates the program.
+    #[display("pcas     {0},{1}")]
+    PcAs(u16, RegS),
+
     /// All other future unsupported operations, which must set `st0` to
     /// `false`.
     Fail(u8),
@@ -255,14 +268,25 @@ impl InstructionSet for ContractOp {
                     _ => fail!(),
                 };
 
-                if !secp256k1_zkp::verify_commitments_sum_to_equal(
-                    secp256k1_zkp::SECP256K1,
-                    &inputs,
-                    &outputs,
-                ) {
+                if !context.verifier.verify_commitments_sum(&inputs, &outputs) {
                     fail!()
                 }
             }
+            ContractOp::PcAs(state_type, reg) => {
+                let Some(TypedAssigns::Fungible(state)) = context.owned_state.get(*state_type)
+                else {
+                    fail!()
+                };
+                let Some(sum) = PedersenCommitment::sum(
+                    state
+                        .iter()
+                        .map(Assign::to_confidential_state)
+                        .map(|s| s.commitment),
+                ) else {
+                    fail!()
+                };
+                regs.set_s(*reg, Some(sum.serialize()));
+            }
 
             // All other future unsupported operations, which must set `st0` to `false`.
             _ => fail!(),
@@ -287,12 +311,13 @@ impl Bytecode for ContractOp {
             ContractOp::LdM(_) => 1,
 
             ContractOp::PcVs(_) => 2,
+            ContractOp::PcAs(_, _) => 3,
 
             ContractOp::Fail(_) => 0,
         }
     }
 
-    fn instr_range() -> RangeInclusive<u8> { INSTR_CNP..=0b11_001_111 }
+    fn instr_range() -> RangeInclusive<u8> { INSTR_CNP..=0b11_011_111 }
 
     fn instr_byte(&self) -> u8 {
         match self {
@@ -309,6 +334,7 @@ impl Bytecode for ContractOp {
             ContractOp::LdM(_) => INSTR_LDM,
 
             ContractOp::PcVs(_) => INSTR_PCVS,
+            ContractOp::PcAs(_, _) => INSTR_PCAS,
 
             ContractOp::Fail(other) => *other,
         }
@@ -373,6 +399,11 @@ impl Bytecode for ContractOp {
             }
 
             ContractOp::PcVs(state_type) => writer.write_u16(*state_type)?,
+            ContractOp::PcAs(state_type, reg) => {
+                writer.write_u16(*state_type)?;
+                writer.write_u4(reg)?;
+                writer.write_u4(u4::ZERO)?;
+            }
 
             ContractOp::Fail(_) => {}
         }
@@ -438,8 +469,263 @@ impl Bytecode for ContractOp {
             }
 
             INSTR_PCVS => Self::PcVs(reader.read_u16()?),
+            INSTR_PCAS => {
+                let i = Self::PcAs(reader.read_u16()?, reader.read_u4()?.into());
+                reader.read_u4()?; // Discard garbage bits
+                i
+            }
 
             x => Self::Fail(x),
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use aluvm::isa::{ControlFlowOp, Instr};
+    use aluvm::library::{Lib, LibId};
+    use amplify::confinement::{Confined, SmallBlob, SmallVec};
+    use commit_verify::CommitVerify;
+    use secp256k1_zkp::{Scalar, SecretKey};
+    use strict_encoding::StrictDumb;
+
+    use super::*;
+    use crate::validation::DefaultVerifier;
+    use crate::vm::{AluRuntime, AluScript, EntryPoint, RgbIsa};
+    use crate::{
+        AssignFungible, Assignments, BlindingFactor, GraphSeal, OpFullType, OpRef, Operation,
+        RevealedData, RevealedValue, Transition, Valencies,
+    };
+
+    /// Runs the code through AluVM as a transition validation script.
+    fn alu_run(code: &[ContractOp], info: &OpInfo) -> bool {
+        alu_run_isa(code.iter().copied().map(RgbIsa::Contract), info)
+    }
+
+    /// Runs RGB ISA instructions through AluVM as a transition validation
+    /// script.
+    fn alu_run_isa(code: impl IntoIterator<Item = RgbIsa>, info: &OpInfo) -> bool {
+        let code = code
+            .into_iter()
+            .map(Instr::ExtensionCodes)
+            .chain([Instr::ControlFlow(ControlFlowOp::Succ)])
+            .collect::<Vec<_>>();
+        let lib = Lib::assemble::<Instr<RgbIsa>>(&code).unwrap();
+        let OpFullType::StateTransition(ty) = info.ty else {
+            unreachable!("samples are state transitions")
+        };
+        let script = AluScript {
+            libs: Confined::try_from(bmap! { lib.id() => lib.clone() }).unwrap(),
+            entry_points: Confined::try_from(bmap! {
+                EntryPoint::ValidateTransition(ty) => LibSite::with(0, lib.id()),
+            })
+            .unwrap(),
+        };
+        AluRuntime::new(&script).run_validations(info).is_ok()
+    }
+
+    /// Reference interpreter for the contract operations, working directly
+    /// with the revealed operation data instead of the VM registers and
+    /// commitments. Returns `None` if the code uses instructions which are not
+    /// supported by the interpreter.
+    fn reference_run(code: &[ContractOp], info: &OpInfo) -> Option<bool> {
+        fn balance<'a>(
+            assigns: impl IntoIterator<Item = &'a AssignFungible<GraphSeal>>,
+        ) -> (u128, Option<SecretKey>) {
+            let (values, blindings): (Vec<_>, Vec<_>) = assigns
+                .into_iter()
+                .map(|assign| {
+                    let revealed = assign
+                        .as_revealed_state()
+                        .expect("reference interpreter requires revealed state");
+                    (revealed.value.as_u64() as u128, SecretKey::from(revealed.blinding))
+                })
+                .unzip();
+            let blinding = blindings.into_iter().reduce(|sum, key| {
+                sum.add_tweak(&Scalar::from(key))
+                    .expect("blinding factors must not sum up to zero")
+            });
+            (values.into_iter().sum(), blinding)
+        }
+
+        for op in code {
+            let passed = match op {
+                ContractOp::LdG(ty, index, _) => info
+                    .global
+                    .get(ty)
+                    .map_or(false, |values| values.len() > *index as usize),
+                ContractOp::PcVs(ty) => {
+                    match (info.prev_state.get(ty), info.owned_state.get(*ty)) {
+                        (None, None) => true,
+                        (
+                            Some(TypedAssigns::Fungible(inputs)),
+                            Some(TypedAssigns::Fungible(outputs)),
+                        ) => balance(inputs.iter()) == balance(outputs.iter()),
+                        _ => false,
+                    }
+                }
+                // Unimplemented opcodes, including PCCS, fail the validation
+                ContractOp::Fail(_) => false,
+                _ => return None,
+            };
+            if !passed {
+                return Some(false);
+            }
+        }
+        Some(true)
+    }
+
+    fn fungible(values: &[(u64, BlindingFactor)]) -> TypedAssigns<GraphSeal> {
+        let assigns = values
+            .iter()
+            .map(|(value, blinding)| {
+                AssignFungible::revealed(
+                    GraphSeal::strict_dumb(),
+                    RevealedValue::with(*value, *blinding),
+                )
+            })
+            .collect::<Vec<_>>();
+        TypedAssigns::Fungible(Confined::try_from(assigns).unwrap())
+    }
+
+    #[test]
+    fn load_metadata() {
+        let mut transition = Transition::strict_dumb();
+        transition.metadata = SmallBlob::try_from(b"meta".to_vec()).unwrap();
+        let op = OpRef::Transition(&transition);
+        let prev_state = Assignments::default();
+        let redeemed = Valencies::default();
+        let info = OpInfo::with(op.id(), false, &op, &prev_state, &redeemed, &DefaultVerifier);
+
+        let ldm = ContractOp::LdM(RegS::from(u4::with(2)));
+        let mut regs = CoreRegs::default();
+        let site = LibSite::with(0, LibId::from([0u8; 32]));
+        assert!(matches!(ldm.exec(&mut regs, site, &info), ExecStep::Next));
+        assert_eq!(regs.get_s(2).map(|bs| bs.to_vec()), Some(b"meta".to_vec()));
+        assert!(alu_run(&[ldm], &info));
+    }
+
+    #[test]
+    fn commitment_sum() {
+        let b1 = BlindingFactor::try_from([1u8; 32]).unwrap();
+        let b2 = BlindingFactor::try_from([2u8; 32]).unwrap();
+        let b12 = BlindingFactor::from(
+            SecretKey::from(b1)
+                .add_tweak(&Scalar::from(SecretKey::from(b2)))
+                .unwrap(),
+        );
+
+        let mut transition = Transition::strict_dumb();
+        transition.assignments = Confined::try_from(bmap! { 1 => fungible(&[(4, b1), (6, b2)]) })
+            .unwrap()
+            .into();
+        let op = OpRef::Transition(&transition);
+        let prev_state = Assignments::default();
+        let redeemed = Valencies::default();
+        let info = OpInfo::with(op.id(), false, &op, &prev_state, &redeemed, &DefaultVerifier);
+        let site = LibSite::with(0, LibId::from([0u8; 32]));
+
+        let pcas = ContractOp::PcAs(1, RegS::from(u4::with(2)));
+        let mut regs = CoreRegs::default();
+        assert!(matches!(pcas.exec(&mut regs, site, &info), ExecStep::Next));
+        let sum = PedersenCommitment::commit(&RevealedValue::with(10u64, b12));
+        assert_eq!(regs.get_s(2).map(|bs| bs.to_vec()), Some(sum.serialize().to_vec()));
+        assert!(alu_run(&[pcas], &info));
+
+        let absent = ContractOp::PcAs(2, RegS::from(u4::with(2)));
+        let mut regs = CoreRegs::default();
+        assert!(matches!(absent.exec(&mut regs, site, &info), ExecStep::Stop));
+        assert!(!alu_run(&[absent], &info));
+    }
+
+    #[test]
+    fn decode_and_run() {
+        let blinding = BlindingFactor::try_from([1u8; 32]).unwrap();
+        let mut transition = Transition::strict_dumb();
+        transition.assignments = Confined::try_from(bmap! { 1 => fungible(&[(10, blinding)]) })
+            .unwrap()
+            .into();
+        let op = OpRef::Transition(&transition);
+        let prev_state = Assignments::from(
+            Confined::try_from(bmap! { 1 => fungible(&[(10, blinding)]) }).unwrap(),
+        );
+        let redeemed = Valencies::default();
+        let info = OpInfo::with(op.id(), false, &op, &prev_state, &redeemed, &DefaultVerifier);
+
+        // PCVS gets decoded from the library and executed
+        assert!(alu_run(&[ContractOp::PcVs(1)], &info));
+
+        // Reserved opcodes inside and outside of the contract instruction
+        // range decode into failing instructions consuming the opcode byte
+        for reserved in
+            [RgbIsa::Contract(ContractOp::Fail(0b11_010_011)), RgbIsa::Fail(0b11_100_000)]
+        {
+            let code = [Instr::ExtensionCodes(reserved), Instr::ControlFlow(ControlFlowOp::Succ)];
+            let lib = Lib::assemble::<Instr<RgbIsa>>(&code).unwrap();
+            assert_eq!(lib.disassemble::<Instr<RgbIsa>>().unwrap(), code.to_vec());
+            assert!(!alu_run_isa([reserved], &info));
+        }
+    }
+
+    #[test]
+    fn reference_agreement() {
+        let b1 = BlindingFactor::try_from([1u8; 32]).unwrap();
+        let b2 = BlindingFactor::try_from([2u8; 32]).unwrap();
+        let b12 = BlindingFactor::from(
+            SecretKey::from(b1)
+                .add_tweak(&Scalar::from(SecretKey::from(b2)))
+                .unwrap(),
+        );
+
+        let mut transition = Transition::strict_dumb();
+        transition
+            .globals
+            .add_state(1, RevealedData::from(SmallVec::try_from(b"state".to_vec()).unwrap()))
+            .unwrap();
+        let samples = [
+            (bmap! { 1 => fungible(&[(4, b1), (6, b2)]) }, bmap! { 1 => fungible(&[(10, b12)]) }),
+            (bmap! { 1 => fungible(&[(4, b1), (6, b2)]) }, bmap! { 1 => fungible(&[(9, b12)]) }),
+            (bmap! { 1 => fungible(&[(10, b1)]) }, bmap! { 1 => fungible(&[(10, b2)]) }),
+            (bmap! { 1 => fungible(&[(10, b1)]) }, bmap! {}),
+            (bmap! {}, bmap! { 1 => fungible(&[(10, b1)]) }),
+            (bmap! {}, bmap! {}),
+        ];
+        let programs = [
+            vec![ContractOp::PcVs(1)],
+            vec![ContractOp::PcVs(2)],
+            vec![ContractOp::LdG(1, 0, RegS::from(u4::with(1)))],
+            vec![ContractOp::LdG(1, 1, RegS::from(u4::with(1)))],
+            vec![ContractOp::LdG(2, 0, RegS::from(u4::with(1)))],
+            vec![ContractOp::LdG(1, 0, RegS::from(u4::with(1))), ContractOp::PcVs(1)],
+            vec![ContractOp::Fail(INSTR_PCCS)],
+        ];
+
+        let mut outcomes = BTreeSet::new();
+        for (prev_state, owned_state) in samples {
+            let prev_state = Assignments::from(Confined::try_from(prev_state).unwrap());
+            transition.assignments = Confined::try_from(owned_state).unwrap().into();
+            let op = OpRef::Transition(&transition);
+            let redeemed = Valencies::default();
+            let info = OpInfo::with(op.id(), false, &op, &prev_state, &redeemed, &DefaultVerifier);
+            for code in &programs {
+                let outcome = reference_run(code, &info).expect("unsupported sample program");
+                assert_eq!(alu_run(code, &info), outcome, "disagreement on {code:?}");
+                outcomes.insert(outcome);
+            }
+
+            // PCCS is not implemented yet, so both interpreters must reject it
+            let pccs = [ContractOp::Fail(INSTR_PCCS)];
+            assert_eq!(reference_run(&pccs, &info), Some(false));
+            assert!(!alu_run(&pccs, &info));
+        }
+        // Make sure samples cover both accepting and rejecting cases
+        assert_eq!(outcomes.len(), 2);
+
+        let ldm = [ContractOp::LdM(RegS::from(u4::with(1)))];
+        let op = OpRef::Transition(&transition);
+        let prev_state = Assignments::default();
+        let redeemed = Valencies::default();
+        let info = OpInfo::with(op.id(), false, &op, &prev_state, &redeemed, &DefaultVerifier);
+        assert_eq!(reference_run(&ldm, &info), None);
+    }
+}
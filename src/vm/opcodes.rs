@@ -22,31 +22,128 @@
 
 #![allow(clippy::unusual_byte_groupings)]
 
+/// Opcodes of the RGB contract instructions.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[display(lowercase)]
+#[repr(u8)]
+pub enum RgbOpcode {
+    Cnp = 0b11_000_000,
+    Cns = 0b11_000_001,
+    Cng = 0b11_000_010,
+    Cnc = 0b11_000_011,
+
+    Ldp = 0b11_000_100,
+    Lds = 0b11_000_101,
+    Ldf = 0b11_000_110,
+    // Reserved 0b11_000_111
+    Ldg = 0b11_001_000,
+    Ldc = 0b11_001_001,
+    Ldm = 0b11_001_010,
+    // Reserved 0b11_001_111
+    Pcvs = 0b11_010_000,
+    Pccs = 0b11_010_001,
+    Pcas = 0b11_010_010,
+    // Reserved 0b11_010_011
+
+    // Reserved 0b11_011_100
+    // Reserved 0b11_011_101
+    // Reserved 0b11_011_110
+    // Reserved 0b11_011_111
+}
+
+impl RgbOpcode {
+    /// All defined contract opcodes.
+    pub const ALL: [RgbOpcode; 13] = [
+        RgbOpcode::Cnp,
+        RgbOpcode::Cns,
+        RgbOpcode::Cng,
+        RgbOpcode::Cnc,
+        RgbOpcode::Ldp,
+        RgbOpcode::Lds,
+        RgbOpcode::Ldf,
+        RgbOpcode::Ldg,
+        RgbOpcode::Ldc,
+        RgbOpcode::Ldm,
+        RgbOpcode::Pcvs,
+        RgbOpcode::Pccs,
+        RgbOpcode::Pcas,
+    ];
+
+    /// Weight of the instruction used in computing script complexity.
+    ///
+    /// Pedersen commitment operations are weighted heavily, since they perform
+    /// elliptic curve arithmetics over all the state of the operation.
+    pub const fn weight(self) -> u64 {
+        match self {
+            RgbOpcode::Cnp | RgbOpcode::Cns | RgbOpcode::Cng | RgbOpcode::Cnc => 2,
+            RgbOpcode::Ldp |
+            RgbOpcode::Lds |
+            RgbOpcode::Ldf |
+            RgbOpcode::Ldg |
+            RgbOpcode::Ldc |
+            RgbOpcode::Ldm => 4,
+            RgbOpcode::Pcvs | RgbOpcode::Pccs | RgbOpcode::Pcas => 1000,
+        }
+    }
+}
+
+/// Error indicating that the byte is not a defined RGB contract opcode.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display("byte {0:#04x} is not a defined RGB contract opcode")]
+pub struct ReservedOpcode(pub u8);
+
+impl TryFrom<u8> for RgbOpcode {
+    type Error = ReservedOpcode;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        RgbOpcode::ALL
+            .into_iter()
+            .find(|opcode| *opcode as u8 == byte)
+            .ok_or(ReservedOpcode(byte))
+    }
+}
+
+impl From<RgbOpcode> for u8 {
+    fn from(opcode: RgbOpcode) -> Self { opcode as u8 }
+}
+
 // CONTRACTS:
-pub const INSTR_CNP: u8 = 0b11_000_000;
-pub const INSTR_CNS: u8 = 0b11_000_001;
-pub const INSTR_CNG: u8 = 0b11_000_010;
-pub const INSTR_CNC: u8 = 0b11_000_011;
-
-pub const INSTR_LDP: u8 = 0b11_000_100;
-pub const INSTR_LDS: u8 = 0b11_000_101;
-pub const INSTR_LDF: u8 = 0b11_000_110;
-// Reserved 0b11_000_111
-
-pub const INSTR_LDG: u8 = 0b11_001_000;
-pub const INSTR_LDC: u8 = 0b11_001_001;
-pub const INSTR_LDM: u8 = 0b11_001_010;
-// Reserved 0b11_001_111
-
-pub const INSTR_PCVS: u8 = 0b11_010_000;
-pub const INSTR_PCCS: u8 = 0b11_010_001;
-// Reserved 0b11_010_010
-// Reserved 0b11_010_011
-
-// Reserved 0b11_011_100
-// Reserved 0b11_011_101
-// Reserved 0b11_011_110
-// Reserved 0b11_011_111
+pub const INSTR_CNP: u8 = RgbOpcode::Cnp as u8;
+pub const INSTR_CNS: u8 = RgbOpcode::Cns as u8;
+pub const INSTR_CNG: u8 = RgbOpcode::Cng as u8;
+pub const INSTR_CNC: u8 = RgbOpcode::Cnc as u8;
+
+pub const INSTR_LDP: u8 = RgbOpcode::Ldp as u8;
+pub const INSTR_LDS: u8 = RgbOpcode::Lds as u8;
+pub const INSTR_LDF: u8 = RgbOpcode::Ldf as u8;
+
+pub const INSTR_LDG: u8 = RgbOpcode::Ldg as u8;
+pub const INSTR_LDC: u8 = RgbOpcode::Ldc as u8;
+pub const INSTR_LDM: u8 = RgbOpcode::Ldm as u8;
+
+pub const INSTR_PCVS: u8 = RgbOpcode::Pcvs as u8;
+pub const INSTR_PCCS: u8 = RgbOpcode::Pccs as u8;
+pub const INSTR_PCAS: u8 = RgbOpcode::Pcas as u8;
+
+/// Returns mnemonic name of the RGB contract instruction with the given opcode,
+/// or `None` if the opcode is reserved.
+pub fn opcode_name(byte: u8) -> Option<&'static str> {
+    Some(match RgbOpcode::try_from(byte).ok()? {
+        RgbOpcode::Cnp => "CNP",
+        RgbOpcode::Cns => "CNS",
+        RgbOpcode::Cng => "CNG",
+        RgbOpcode::Cnc => "CNC",
+        RgbOpcode::Ldp => "LDP",
+        RgbOpcode::Lds => "LDS",
+        RgbOpcode::Ldf => "LDF",
+        RgbOpcode::Ldg => "LDG",
+        RgbOpcode::Ldc => "LDC",
+        RgbOpcode::Ldm => "LDM",
+        RgbOpcode::Pcvs => "PCVS",
+        RgbOpcode::Pccs => "PCCS",
+        RgbOpcode::Pcas => "PCAS",
+    })
+}
 
 // TIMECHAIN:
 
@@ -57,3 +154,33 @@ pub const INSTR_PCCS: u8 = 0b11_010_001;
 pub use aluvm::isa::opcodes::{INSTR_ISAE_FROM, INSTR_ISAE_TO};
 // pub const INSTR_ISAE_FROM: u8 = 0b11_000_000;
 // pub const INSTR_ISAE_TO: u8 = 0b11_000_000;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn opcodes() {
+        let consts = [
+            INSTR_CNP, INSTR_CNS, INSTR_CNG, INSTR_CNC, INSTR_LDP, INSTR_LDS, INSTR_LDF, INSTR_LDG,
+            INSTR_LDC, INSTR_LDM, INSTR_PCVS, INSTR_PCCS, INSTR_PCAS,
+        ];
+        for (byte, opcode) in consts.into_iter().zip(RgbOpcode::ALL) {
+            assert_eq!(RgbOpcode::try_from(byte), Ok(opcode));
+            assert_eq!(u8::from(opcode), byte);
+        }
+        assert_eq!(RgbOpcode::try_from(0b11_000_111), Err(ReservedOpcode(0b11_000_111)));
+        assert_eq!(RgbOpcode::try_from(0), Err(ReservedOpcode(0)));
+        assert_eq!(RgbOpcode::Pcvs.to_string(), "pcvs");
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(opcode_name(INSTR_CNP), Some("CNP"));
+        assert_eq!(opcode_name(INSTR_LDM), Some("LDM"));
+        assert_eq!(opcode_name(INSTR_PCCS), Some("PCCS"));
+        assert_eq!(opcode_name(INSTR_PCAS), Some("PCAS"));
+        assert_eq!(opcode_name(0b11_010_011), None);
+        assert_eq!(opcode_name(0b11_000_111), None);
+    }
+}
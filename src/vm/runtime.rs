@@ -20,24 +20,80 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
+#[cfg(feature = "vm-debug")]
+use std::cell::RefCell;
 use std::collections::{BTreeMap, BTreeSet};
 
 use aluvm::data::{ByteStr, Number};
+#[cfg(feature = "vm-debug")]
+use aluvm::reg::CoreRegs;
 use aluvm::reg::{Reg32, RegA, RegAFR, RegS};
 use aluvm::Vm;
 
-use crate::validation::OpInfo;
+use crate::validation::{OpInfo, ScriptError};
 use crate::vm::{AluScript, EntryPoint};
-use crate::OpFullType;
+#[cfg(feature = "vm-debug")]
+use crate::OpId;
+use crate::{OpFullType, ScriptComplexity};
+
+/// Final state of VM registers after running each of the script entry points,
+/// collected per operation.
+#[cfg(feature = "vm-debug")]
+pub type RegisterTrace = BTreeMap<OpId, Vec<(EntryPoint, CoreRegs)>>;
 
 pub struct AluRuntime<'script> {
-    script: &'script AluScript,
+    script: Cow<'script, AluScript>,
+    /// Reason for failing all validations due to the complexity cap, if any.
+    cap_error: Option<String>,
+    #[cfg(feature = "vm-debug")]
+    trace: RefCell<RegisterTrace>,
 }
 
 impl<'script> AluRuntime<'script> {
-    pub fn new(script: &'script AluScript) -> Self { AluRuntime { script } }
+    pub fn new(script: &'script AluScript) -> Self { Self::with_script(Cow::Borrowed(script), None) }
+
+    /// Constructs runtime failing validation of all operations if the script
+    /// [`AluScript::complexity`] exceeds the provided cap or can't be
+    /// computed since some of the script libraries can't be disassembled.
+    pub fn with_complexity_cap(script: &'script AluScript, cap: ScriptComplexity) -> Self {
+        Self::with_script(Cow::Borrowed(script), Some(cap))
+    }
+
+    /// Constructs runtime for a borrowed or owned script, enforcing the
+    /// complexity cap, if provided, in the same way as
+    /// [`AluRuntime::with_complexity_cap`].
+    pub fn with_script(script: Cow<'script, AluScript>, cap: Option<ScriptComplexity>) -> Self {
+        let cap_error = cap.and_then(|cap| match script.complexity() {
+            Ok(complexity) if cap.is_exceeded_by(complexity) => {
+                Some(format!("script complexity {complexity} exceeds the cap of {cap}"))
+            }
+            Ok(_) => None,
+            Err(err) => Some(format!("script complexity can't be computed: {err}")),
+        });
+        AluRuntime {
+            script,
+            cap_error,
+            #[cfg(feature = "vm-debug")]
+            trace: default!(),
+        }
+    }
+
+    /// Returns final register state for all script runs performed by the
+    /// runtime so far, clearing the collected data.
+    #[cfg(feature = "vm-debug")]
+    pub fn take_registers(&self) -> RegisterTrace { self.trace.take() }
+
+    pub fn run_validations(&self, info: &OpInfo) -> Result<(), ScriptError> {
+        if let Some(ref err) = self.cap_error {
+            return Err(ScriptError {
+                op: info.id,
+                entry_offset: 0,
+                isa_error: err.clone(),
+                exit_code: None,
+            });
+        }
 
-    pub fn run_validations(&self, info: &OpInfo) -> Result<(), String> {
         let mut regs = RegSetup::default();
 
         match info.ty {
@@ -72,7 +128,7 @@ impl<'script> AluRuntime<'script> {
         Ok(())
     }
 
-    fn run(&self, entry: EntryPoint, regs: &RegSetup, info: &OpInfo) -> Result<(), String> {
+    fn run(&self, entry: EntryPoint, regs: &RegSetup, info: &OpInfo) -> Result<(), ScriptError> {
         let mut vm = Vm::new();
 
         for ((reg, idx), val) in &regs.nums {
@@ -87,16 +143,31 @@ impl<'script> AluRuntime<'script> {
             );
         }
 
-        match self.script.entry_points.get(&entry) {
-            Some(site) => match vm.call(self.script, *site, info) {
-                true => Ok(()),
-                false => Err(vm
+        let Some(site) = self.script.entry_points.get(&entry) else {
+            return Ok(());
+        };
+        let success = vm.call(self.script.as_ref(), *site, info);
+
+        #[cfg(feature = "vm-debug")]
+        self.trace
+            .borrow_mut()
+            .entry(info.id)
+            .or_default()
+            .push((entry, CoreRegs::clone(&vm.registers)));
+
+        match success {
+            true => Ok(()),
+            false => Err(ScriptError {
+                op: info.id,
+                entry_offset: site.pos,
+                isa_error: vm
                     .registers
                     .get_s(0)
                     .and_then(|bs| String::from_utf8(bs.to_vec()).ok())
-                    .unwrap_or_else(|| s!("unspecified error"))),
-            },
-            None => Ok(()),
+                    .unwrap_or_else(|| s!("unspecified error")),
+                exit_code: Option::<Number>::from(vm.registers.get(RegA::A8, Reg32::Reg0))
+                    .map(u8::from),
+            }),
         }
     }
 }
@@ -22,8 +22,10 @@
 
 use std::collections::{btree_map, BTreeMap};
 use std::io;
+use std::str::FromStr;
 
 use aluvm::data::encoding::{Decode, Encode};
+use aluvm::isa::Instr;
 use aluvm::library::{Lib, LibId, LibSite};
 use aluvm::Program;
 use amplify::confinement::{Confined, SmallBlob, SmallOrdMap, TinyOrdMap};
@@ -32,23 +34,91 @@ use strict_encoding::{
     StrictType, TypedRead, TypedWrite, WriteStruct,
 };
 
-use crate::vm::RgbIsa;
+use crate::vm::{DisasmError, RgbIsa};
 use crate::{AssignmentType, ExtensionType, GlobalStateType, TransitionType, LIB_NAME_RGB};
 
 /// Maximum total number of libraries which may be used by a single program;
 /// i.e. maximal number of nodes in a library dependency tree.
 pub const LIBS_MAX_TOTAL: usize = 1024;
 
-#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+/// Entry point of a validation script.
+///
+/// Serialized with serde as a string (see its [`Display`](std::fmt::Display)
+/// implementation), such that it can be used as a key in JSON maps. The
+/// representation used by the previous versions (`"validateGenesis"`,
+/// `{"validateTransition": 1}` etc.) is still accepted by the deserializer.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
 #[derive(StrictDumb)]
 #[strict_type(lib = LIB_NAME_RGB)]
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
-    serde(crate = "serde_crate", rename_all = "camelCase")
+    serde(crate = "serde_crate", into = "String", try_from = "EntryPointRepr")
 )]
 pub enum EntryPoint {
     #[strict_type(dumb)]
+    #[display("genesis")]
+    ValidateGenesis,
+    #[display("transition:{0}")]
+    ValidateTransition(TransitionType),
+    #[display("extension:{0}")]
+    ValidateExtension(ExtensionType),
+    #[display("global:{0}")]
+    ValidateGlobalState(GlobalStateType),
+    #[display("owned:{0}")]
+    ValidateOwnedState(AssignmentType),
+}
+
+/// Error parsing [`EntryPoint`] from a string.
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display("invalid validation script entry point '{0}'")]
+pub struct EntryPointParseError(String);
+
+impl FromStr for EntryPoint {
+    type Err = EntryPointParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || EntryPointParseError(s.to_owned());
+        if s == "genesis" {
+            return Ok(EntryPoint::ValidateGenesis);
+        }
+        let (kind, ty) = s.split_once(':').ok_or_else(err)?;
+        let ty = ty.parse().map_err(|_| err())?;
+        Ok(match kind {
+            "transition" => EntryPoint::ValidateTransition(ty),
+            "extension" => EntryPoint::ValidateExtension(ty),
+            "global" => EntryPoint::ValidateGlobalState(ty),
+            "owned" => EntryPoint::ValidateOwnedState(ty),
+            _ => return Err(err()),
+        })
+    }
+}
+
+impl From<EntryPoint> for String {
+    fn from(entry_point: EntryPoint) -> Self { entry_point.to_string() }
+}
+
+impl TryFrom<String> for EntryPoint {
+    type Error = EntryPointParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> { EntryPoint::from_str(&s) }
+}
+
+/// Serde representations of [`EntryPoint`] accepted by the deserializer.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(crate = "serde_crate", untagged)]
+enum EntryPointRepr {
+    Legacy(LegacyEntryPoint),
+    Str(String),
+}
+
+/// Serde representation of [`EntryPoint`] used before it got serialized as a
+/// string.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(crate = "serde_crate", rename_all = "camelCase")]
+enum LegacyEntryPoint {
     ValidateGenesis,
     ValidateTransition(TransitionType),
     ValidateExtension(ExtensionType),
@@ -56,6 +126,32 @@ pub enum EntryPoint {
     ValidateOwnedState(AssignmentType),
 }
 
+#[cfg(feature = "serde")]
+impl TryFrom<EntryPointRepr> for EntryPoint {
+    type Error = EntryPointParseError;
+
+    fn try_from(repr: EntryPointRepr) -> Result<Self, Self::Error> {
+        Ok(match repr {
+            EntryPointRepr::Str(s) => return EntryPoint::try_from(s),
+            EntryPointRepr::Legacy(LegacyEntryPoint::ValidateGenesis) => {
+                EntryPoint::ValidateGenesis
+            }
+            EntryPointRepr::Legacy(LegacyEntryPoint::ValidateTransition(ty)) => {
+                EntryPoint::ValidateTransition(ty)
+            }
+            EntryPointRepr::Legacy(LegacyEntryPoint::ValidateExtension(ty)) => {
+                EntryPoint::ValidateExtension(ty)
+            }
+            EntryPointRepr::Legacy(LegacyEntryPoint::ValidateGlobalState(ty)) => {
+                EntryPoint::ValidateGlobalState(ty)
+            }
+            EntryPointRepr::Legacy(LegacyEntryPoint::ValidateOwnedState(ty)) => {
+                EntryPoint::ValidateOwnedState(ty)
+            }
+        })
+    }
+}
+
 impl StrictType for EntryPoint {
     const STRICT_LIB_NAME: &'static str = LIB_NAME_RGB;
 }
@@ -150,6 +246,15 @@ impl StrictDecode for AluScript {
                 .map(|(id, lib)| {
                     let lib = Lib::deserialize(lib)
                         .map_err(|err| DecodeError::DataIntegrityError(err.to_string()))?;
+                    // Entry points refer to the libraries by the ids from the index, so an
+                    // index not matching the library code would make the runtime execute code
+                    // different from the one the schema commits to by the library ids
+                    if lib.id() != id {
+                        return Err(DecodeError::DataIntegrityError(format!(
+                            "AluVM library is indexed with id {id} not matching its code id {}",
+                            lib.id()
+                        )));
+                    }
                     Ok((id, lib))
                 })
                 .collect::<Result<BTreeMap<_, _>, DecodeError>>()?;
@@ -163,6 +268,37 @@ impl StrictDecode for AluScript {
     }
 }
 
+impl AluScript {
+    /// Computes script complexity as a sum of weights of all instructions in
+    /// all of its libraries. Core AluVM instructions weigh `1`; the weights
+    /// of RGB-specific instructions are given by [`RgbIsa::weight`].
+    ///
+    /// This is a static measure of the script code size: it doesn't bound the
+    /// number of instructions executed at runtime, since the code may contain
+    /// loops.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DisasmError::Truncated`] if some of the libraries can't be
+    /// disassembled.
+    pub fn complexity(&self) -> Result<u64, DisasmError> {
+        let mut complexity = 0u64;
+        for lib in self.libs.values() {
+            let code = lib
+                .disassemble::<Instr<RgbIsa>>()
+                .map_err(|_| DisasmError::Truncated)?;
+            complexity += code
+                .iter()
+                .map(|instr| match instr {
+                    Instr::ExtensionCodes(isa) => isa.weight(),
+                    _ => 1,
+                })
+                .sum::<u64>();
+        }
+        Ok(complexity)
+    }
+}
+
 impl Program for AluScript {
     type Isa = RgbIsa;
     type Iter<'a> = btree_map::Values<'a, LibId, Lib> where Self: 'a;
@@ -175,3 +311,72 @@ impl Program for AluScript {
 
     fn entrypoint(&self) -> LibSite { panic!("AluScript doesn't have a single entry point") }
 }
+
+#[cfg(test)]
+mod test {
+    use strict_encoding::{StrictReader, StrictWriter};
+
+    use super::*;
+
+    #[test]
+    fn lib_index_integrity() {
+        let lib = Lib::assemble::<Instr<RgbIsa>>(&[Instr::Nop]).unwrap();
+        let decode = |id: LibId| {
+            let script = AluScript {
+                libs: Confined::try_from(bmap! { id => lib.clone() }).unwrap(),
+                entry_points: none!(),
+            };
+            let mut data = vec![];
+            script
+                .strict_encode(StrictWriter::with(u32::MAX as usize, &mut data))
+                .unwrap();
+            AluScript::strict_decode(&mut StrictReader::with(data.len(), io::Cursor::new(data)))
+                .map(|decoded| assert_eq!(decoded, script))
+        };
+
+        assert!(decode(lib.id()).is_ok());
+        assert!(matches!(decode(LibId::from([0xAB; 32])), Err(DecodeError::DataIntegrityError(_))));
+    }
+
+    #[test]
+    fn entry_point_str() {
+        for entry_point in [
+            EntryPoint::ValidateGenesis,
+            EntryPoint::ValidateTransition(1),
+            EntryPoint::ValidateExtension(2),
+            EntryPoint::ValidateGlobalState(3),
+            EntryPoint::ValidateOwnedState(4),
+        ] {
+            assert_eq!(EntryPoint::from_str(&entry_point.to_string()), Ok(entry_point));
+        }
+        assert_eq!(EntryPoint::ValidateTransition(10).to_string(), "transition:10");
+        assert!(EntryPoint::from_str("transition").is_err());
+        assert!(EntryPoint::from_str("genesis:1").is_err());
+        assert!(EntryPoint::from_str("owned:x").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn entry_point_serde() {
+        let entry_point = EntryPoint::ValidateTransition(4);
+        let json = serde_json::to_string(&entry_point).unwrap();
+        assert_eq!(json, "\"transition:4\"");
+        assert_eq!(serde_json::from_str::<EntryPoint>(&json).unwrap(), entry_point);
+
+        // Representation produced by the previous versions
+        assert_eq!(
+            serde_json::from_str::<EntryPoint>("\"validateGenesis\"").unwrap(),
+            EntryPoint::ValidateGenesis
+        );
+        assert_eq!(
+            serde_json::from_str::<EntryPoint>(r#"{"validateTransition":4}"#).unwrap(),
+            entry_point
+        );
+        assert_eq!(
+            serde_json::from_str::<EntryPoint>(r#"{"validateOwnedState":2}"#).unwrap(),
+            EntryPoint::ValidateOwnedState(2)
+        );
+        assert!(serde_json::from_str::<EntryPoint>("\"validate\"").is_err());
+        assert!(serde_json::from_str::<EntryPoint>(r#"{"validate":2}"#).is_err());
+    }
+}